@@ -0,0 +1,300 @@
+use crate::base::parser::{ElementEvent, ElementValue, ReaderError};
+
+use serde::de::{self, IntoDeserializer, Visitor};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeError {
+    #[error("ReaderError: {0}")]
+    Read(#[from] ReaderError),
+    #[error("unexpected end of document")]
+    UnexpectedEnd,
+    #[error("unexpected end of element {0:#X}")]
+    UnexpectedLeave(u32),
+    #[error("element {0:#X} occurs {1} times, where at most one is expected")]
+    MultipleOccurrences(u32, usize),
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl de::Error for DeError {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+// an element read in full, along with all of its children
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Master(u32, Vec<Node>),
+    Data(u32, ElementValue),
+}
+
+impl Node {
+    fn id(&self) -> u32 {
+        match self {
+            Self::Master(id, _) | Self::Data(id, _) => *id,
+        }
+    }
+
+    // reads the next element from the events, up to & including its `Leave` event
+    fn read<I: Iterator<Item = Result<ElementEvent, ReaderError>>>(
+        events: &mut I,
+    ) -> Result<Self, DeError> {
+        match events.next().ok_or(DeError::UnexpectedEnd)?? {
+            ElementEvent::Enter(id) => Self::read_children(id, events),
+            ElementEvent::Leave(id) => Err(DeError::UnexpectedLeave(id)),
+            ElementEvent::Data(id, value) => Ok(Self::Data(id, value)),
+        }
+    }
+
+    fn read_children<I: Iterator<Item = Result<ElementEvent, ReaderError>>>(
+        id: u32,
+        events: &mut I,
+    ) -> Result<Self, DeError> {
+        let mut children = Vec::new();
+        loop {
+            match events.next().ok_or(DeError::UnexpectedEnd)?? {
+                ElementEvent::Enter(child_id) => {
+                    children.push(Self::read_children(child_id, events)?)
+                }
+                ElementEvent::Leave(_) => return Ok(Self::Master(id, children)),
+                ElementEvent::Data(child_id, value) => children.push(Self::Data(child_id, value)),
+            }
+        }
+    }
+}
+
+// Element names are matched against fields in snake case, e.g. `FileName` -> `file_name` & `CRC32`
+// -> `crc32`.
+fn field_name(element_name: &str) -> String {
+    let chars: Vec<char> = element_name.chars().collect();
+    let mut name = String::with_capacity(chars.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                name.push('_');
+            }
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+// Deserializes a single element: master elements as structs (or maps) keyed by their children's
+// names, & data elements as their values.
+struct ElementDeserializer<'a> {
+    node: &'a Node,
+    name_of: fn(u32) -> Option<&'static str>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ElementDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.node {
+            Node::Master(_, children) => {
+                visitor.visit_map(ChildrenAccess::new(children, self.name_of))
+            }
+            Node::Data(_, ElementValue::UInt(value)) => visitor.visit_u64(*value),
+            Node::Data(_, ElementValue::Int(value)) => visitor.visit_i64(*value),
+            Node::Data(_, ElementValue::Float(value)) => visitor.visit_f64(*value),
+            Node::Data(_, ElementValue::Date(value)) => visitor.visit_i64(*value),
+            Node::Data(_, ElementValue::String(value)) => visitor.visit_str(value),
+            Node::Data(_, ElementValue::Utf8(value)) => visitor.visit_str(value),
+            Node::Data(_, ElementValue::Binary(value)) => visitor.visit_bytes(value),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    // binary data fits a `Vec<u8>` as well as a byte buffer
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.node {
+            Node::Data(_, ElementValue::Binary(value)) => visitor.visit_seq(
+                de::value::SeqDeserializer::<_, DeError>::new(value.iter().copied()),
+            ),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct tuple tuple_struct map struct enum identifier
+    }
+}
+
+// All occurrences of a child element within its parent, which deserialize as a sequence or (if
+// there's only one) as a single value.
+struct OccurrencesDeserializer<'a> {
+    nodes: Vec<&'a Node>,
+    name_of: fn(u32) -> Option<&'static str>,
+}
+
+impl<'a> OccurrencesDeserializer<'a> {
+    fn single(self) -> Result<ElementDeserializer<'a>, DeError> {
+        match self.nodes[..] {
+            [node] => Ok(ElementDeserializer {
+                node,
+                name_of: self.name_of,
+            }),
+            _ => Err(DeError::MultipleOccurrences(
+                self.nodes[0].id(),
+                self.nodes.len(),
+            )),
+        }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for OccurrencesDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.single()?.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        // a lone binary element is more likely to be read into a `Vec<u8>` than a `Vec<Vec<u8>>`
+        if let [node @ Node::Data(_, ElementValue::Binary(_))] = self.nodes[..] {
+            return ElementDeserializer {
+                node,
+                name_of: self.name_of,
+            }
+            .deserialize_seq(visitor);
+        }
+
+        let name_of = self.name_of;
+        visitor.visit_seq(de::value::SeqDeserializer::new(
+            self.nodes
+                .into_iter()
+                .map(move |node| ElementDeserializer { node, name_of }),
+        ))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct tuple tuple_struct map struct enum identifier
+    }
+}
+
+impl<'de, 'a> IntoDeserializer<'de, DeError> for ElementDeserializer<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+// the children of a master element, grouped by name in order of their first occurrence
+struct ChildrenAccess<'a> {
+    groups: std::vec::IntoIter<(String, Vec<&'a Node>)>,
+    value: Option<Vec<&'a Node>>,
+    name_of: fn(u32) -> Option<&'static str>,
+}
+
+impl<'a> ChildrenAccess<'a> {
+    fn new(children: &'a [Node], name_of: fn(u32) -> Option<&'static str>) -> Self {
+        let mut groups: Vec<(String, Vec<&'a Node>)> = Vec::new();
+        for child in children {
+            let name = match name_of(child.id()) {
+                Some(name) => field_name(name),
+                None => format!("{:#X}", child.id()),
+            };
+            match groups
+                .iter_mut()
+                .find(|(group_name, _)| *group_name == name)
+            {
+                Some((_, nodes)) => nodes.push(child),
+                None => groups.push((name, vec![child])),
+            }
+        }
+
+        Self {
+            groups: groups.into_iter(),
+            value: None,
+            name_of,
+        }
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for ChildrenAccess<'a> {
+    type Error = DeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        match self.groups.next() {
+            Some((name, nodes)) => {
+                self.value = Some(nodes);
+                seed.deserialize(name.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, DeError> {
+        let nodes = self
+            .value
+            .take()
+            .expect("a map value is only requested after its key");
+        seed.deserialize(OccurrencesDeserializer {
+            nodes,
+            name_of: self.name_of,
+        })
+    }
+}
+
+// Deserializes the next top-level element of a document, as walked by its events. `name_of` maps
+// element IDs to their schema names.
+pub fn from_events<T, I>(
+    mut events: I,
+    name_of: fn(u32) -> Option<&'static str>,
+) -> Result<T, DeError>
+where
+    T: de::DeserializeOwned,
+    I: Iterator<Item = Result<ElementEvent, ReaderError>>,
+{
+    let node = Node::read(&mut events)?;
+    T::deserialize(ElementDeserializer {
+        node: &node,
+        name_of,
+    })
+}
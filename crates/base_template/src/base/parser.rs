@@ -1,12 +1,21 @@
 use crate::base::element_defs::{
-    BinaryElementDef, DateElementDef, ElementDef, FloatElementDef, IntElementDef, StringElementDef,
-    UIntElementDef, Utf8ElementDef,
+    BinaryElementDef, Bound, DateElementDef, ElementDef, FloatElementDef, IntElementDef,
+    MasterElementDef, Range, StringElementDef, UIntElementDef, Utf8ElementDef,
 };
-use crate::base::stream::parse;
-
-use core::convert::From;
+use crate::base::header::DocumentConfig;
+#[cfg(feature = "std")]
+use crate::base::stream::stream_diff;
+use crate::base::stream::{crc32, parse, parse::ParseError};
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::{From, TryFrom, TryInto};
 use core::fmt::Debug;
 use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // marks an object with a single respective element type
 pub trait BoundTo
@@ -14,35 +23,324 @@ where
     Self::Element: ElementDef,
 {
     type Element;
+
+    // the ID & name of the element, e.g. to log or dispatch on any state or reader
+    fn element_id(&self) -> u32 {
+        Self::Element::ID
+    }
+
+    fn element_name(&self) -> &'static str {
+        Self::Element::NAME
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementLength {
+    // (a length beyond `usize` is fine as long as the element's data isn't read, e.g. if skipped)
+    Known(u64),
+    // the element ends at the first element that isn't one of its children
+    Unknown,
+}
+
+impl ElementLength {
+    pub fn known(self) -> Option<u64> {
+        match self {
+            Self::Known(len) => Some(len),
+            Self::Unknown => None,
+        }
+    }
+}
+
+impl ElementLength {
+    // converts a length parsed by `parse::element_len`
+    pub fn new(len: Option<u64>) -> Self {
+        match len {
+            Some(len) => Self::Known(len),
+            None => Self::Unknown,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ElementState<E: ElementDef, S> {
-    pub bytes_left: usize,
+    pub bytes_left: ElementLength,
     pub parent_state: S,
+    // the number of times each child element has occurred so far
+    pub occurrences: BTreeMap<u32, usize>,
+    // the CRC-32 check started by a `CRC-32` child element, if any
+    pub crc: Option<CrcCheck>,
     pub _phantom: PhantomData<E>,
 }
 
-#[derive(thiserror::Error, Debug)]
+// the ID of EBML's global `CRC-32` element
+pub const CRC32_ID: u32 = 0xBF;
+
+// a CRC-32 over all elements following a `CRC-32` element within the same parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcCheck {
+    pub expected: u32,
+    pub actual: u32,
+}
+
+impl CrcCheck {
+    pub fn new(expected: u32) -> Self {
+        Self {
+            expected,
+            actual: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.actual = crc32::update(self.actual, data);
+    }
+}
+
+// (`Display` is implemented by hand, as `thiserror` requires `std`)
+#[derive(Debug, PartialEq, Eq)]
 pub enum StateError {
-    #[error("invalid subelement id {1} (parent id = {:?})", *.0)]
     InvalidChildId(Option<u32>, u32),
-    #[error("unimplemeted feature: {0}")]
     Unimplemented(&'static str),
-    #[error("error parsing token")]
-    BadToken,
+    BadToken(ParseError),
+    OutOfRange(u32),
+    UnknownSize(u32),
+    LengthOutOfRange {
+        id: u32,
+        len: u64,
+    },
+    VersionUnsupported {
+        id: u32,
+        version: u64,
+    },
+    OccurrenceViolation {
+        parent_id: u32,
+        child_id: u32,
+        count: usize,
+    },
+    CrcMismatch {
+        id: u32,
+        expected: u32,
+        actual: u32,
+    },
+    ElementTooLarge {
+        id: u32,
+        len: u64,
+    },
+    ExceedsParent {
+        parent_id: u32,
+        id: u32,
+        len: u64,
+    },
+    // An element to skip that's too large to buffer, whose data starts `stream_left` octets before
+    // the end of the parsed stream. Readers skip its data in place, & then parse on.
+    SkipTooLarge {
+        id: u32,
+        len: u64,
+        stream_left: usize,
+    },
+}
+
+impl core::fmt::Display for StateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidChildId(parent_id, id) => {
+                write!(
+                    f,
+                    "invalid subelement id {} (parent id = {:?})",
+                    id, parent_id
+                )
+            }
+            Self::Unimplemented(feature) => write!(f, "unimplemeted feature: {}", feature),
+            Self::BadToken(error) => write!(f, "error parsing token: {}", error),
+            Self::OutOfRange(id) => write!(f, "element value out of range (id = {})", id),
+            Self::UnknownSize(id) => {
+                write!(f, "operation requires a known element size (id = {})", id)
+            }
+            Self::LengthOutOfRange { id, len } => {
+                write!(f, "element length {} out of range (id = {})", len, id)
+            }
+            Self::VersionUnsupported { id, version } => write!(
+                f,
+                "element {} is not supported in document version {}",
+                id, version
+            ),
+            Self::OccurrenceViolation {
+                parent_id,
+                child_id,
+                count,
+            } => write!(
+                f,
+                "element {} occurs {} time(s) in element {}, outside of its allowed occurrences",
+                child_id, count, parent_id
+            ),
+            Self::CrcMismatch {
+                id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "CRC-32 mismatch in element {} (expected = {:#010x}, actual = {:#010x})",
+                id, expected, actual
+            ),
+            Self::ElementTooLarge { id, len } => write!(
+                f,
+                "element {} of length {} is too large to address on this platform",
+                id, len
+            ),
+            Self::ExceedsParent { parent_id, id, len } => write!(
+                f,
+                "element {} of length {} overruns the end of its parent element {}",
+                id, len, parent_id
+            ),
+            Self::SkipTooLarge { id, len, .. } => write!(
+                f,
+                "element {} of length {} is too large to skip in memory",
+                id, len
+            ),
+        }
+    }
 }
 
-impl From<()> for StateError {
-    fn from(_value: ()) -> Self {
-        Self::BadToken
+#[cfg(feature = "std")]
+impl std::error::Error for StateError {}
+
+impl From<ParseError> for StateError {
+    fn from(error: ParseError) -> Self {
+        Self::BadToken(error)
+    }
+}
+
+// checks a newly-entered element's length against the octet lengths allowed by its schema
+pub fn check_length<E: ElementDef>(
+    len: ElementLength,
+) -> Result<ElementLength, nom::Err<StateError>> {
+    let in_range = |len| match usize::try_from(len) {
+        Ok(len) => E::LENGTH.contains(&len),
+        // (a length beyond `usize` only lies within a range w/o an upper bound)
+        Err(_) => matches!(
+            E::LENGTH,
+            Range::Excludes(_) | Range::IsWithin(_, Bound::Unbounded)
+        ),
+    };
+    match len {
+        ElementLength::Known(len) if !in_range(len) => {
+            Err(nom::Err::Failure(StateError::LengthOutOfRange {
+                id: E::ID,
+                len,
+            }))
+        }
+        len => Ok(len),
+    }
+}
+
+// checks a newly-entered element's `minver`/`maxver` against the document's version
+pub fn check_version<E: ElementDef>(config: &DocumentConfig) -> Result<(), nom::Err<StateError>> {
+    match config.doc_type_version {
+        Some(version)
+            if version < E::MIN_VERSION || E::MAX_VERSION.is_some_and(|max| version > max) =>
+        {
+            Err(nom::Err::Failure(StateError::VersionUnsupported {
+                id: E::ID,
+                version,
+            }))
+        }
+        _ => Ok(()),
+    }
+}
+
+// whether an element's `PATH` places it directly under the element at `parent_path` (`""` for
+// the top level of the document)
+pub fn is_child_path(parent_path: &str, path: &str, name: &str) -> bool {
+    // a parent that's itself global could be anywhere -> can't be checked
+    if parent_path.contains('(') {
+        return true;
+    }
+    let prefix = match path.strip_suffix(name) {
+        Some(prefix) => prefix,
+        None => return false,
+    };
+    let placeholder = match prefix.strip_suffix(r"\)") {
+        Some(placeholder) => placeholder,
+        // (a recursive element is nested within itself under the same path)
+        None => return path == parent_path || prefix.strip_suffix('\\') == Some(parent_path),
+    };
+
+    // a global element, e.g. `\(1-\)CRC-32` -> any depth in `[1, inf)` below the top level
+    let (base, occurrences) = match placeholder.rsplit_once('(') {
+        Some((base, occurrences)) => (base.strip_suffix('\\').unwrap_or(base), occurrences),
+        None => return false,
+    };
+    let (min, max) = match occurrences.split_once('-') {
+        Some(min_max) => min_max,
+        None => return false,
+    };
+    let depth = match parent_path.strip_prefix(base) {
+        Some(rest) if rest.is_empty() || rest.starts_with('\\') => rest.matches('\\').count(),
+        _ => return false,
+    };
+    let min = if min.is_empty() { Ok(0) } else { min.parse() };
+    let max = if max.is_empty() {
+        Ok(usize::MAX)
+    } else {
+        max.parse()
+    };
+    match (min, max) {
+        (Ok(min), Ok(max)) => min <= depth && depth <= max,
+        _ => false,
+    }
+}
+
+// catches a generator bug that wires an element under the wrong parent (only w/ the `check-paths`
+// feature, in debug builds)
+pub fn check_path<E: ElementDef>(parent_path: &str) {
+    if cfg!(feature = "check-paths") {
+        debug_assert!(
+            is_child_path(parent_path, E::PATH, E::NAME),
+            "element {} at path `{}` is not a child of `{}`",
+            E::NAME,
+            E::PATH,
+            parent_path,
+        );
     }
 }
 
-pub trait SkipStateNavigation {
+// skips over the data of an element that's not in the schema (in lenient mode)
+pub fn skip_unknown_element(
+    stream: &[u8],
+    id: u32,
+    len: Option<u64>,
+) -> nom::IResult<&[u8], &[u8], StateError> {
+    skip_unknown_element_as::<usize>(stream, id, len)
+}
+
+// (generic over the integer type that the data is addressed w/, to simulate narrower targets)
+fn skip_unknown_element_as<T: TryFrom<u64> + TryInto<usize>>(
+    stream: &[u8],
+    id: u32,
+    len: Option<u64>,
+) -> nom::IResult<&[u8], &[u8], StateError> {
+    let len = len.ok_or(nom::Err::Failure(StateError::UnknownSize(id)))?;
+    match T::try_from(len).ok().and_then(|len| len.try_into().ok()) {
+        Some(len) => {
+            nom::bytes::streaming::take::<_, _, ParseError>(len)(stream).map_err(nom::Err::convert)
+        }
+        // more data than any stream in memory can hold -> left for the reader to skip in place
+        None => Err(nom::Err::Failure(StateError::SkipTooLarge {
+            id,
+            len,
+            stream_left: stream.len(),
+        })),
+    }
+}
+
+pub trait SkipStateNavigation: Sized {
     type PrevStates;
 
     fn skip(self, stream: &[u8]) -> nom::IResult<&[u8], Self::PrevStates, StateError>;
+
+    // Splits off the length of the element's remaining data, when the data can be skipped w/o
+    // being read (i.e. no CRC-32 check needs it) -> readers stream past it, rather than buffering
+    // it to `skip`. Otherwise, the state is given back as-is.
+    fn skip_unread(self) -> Result<(u64, Self::PrevStates), Self>;
 }
 
 pub trait NextStateNavigation {
@@ -70,14 +368,18 @@ impl ParserMarker for BinaryParserMarker {}
 
 pub trait StateDataParser<'a, M: ParserMarker, T: 'a> {
     type NextState;
-    fn read(self, stream: &'a [u8]) -> nom::IResult<&[u8], (Self::NextState, T), StateError>;
+    fn read(self, stream: &'a [u8]) -> nom::IResult<&'a [u8], (Self::NextState, T), StateError>;
 }
 
 impl<E: UIntElementDef, S> StateDataParser<'_, UIntParserMarker, u64> for ElementState<E, S> {
     type NextState = S;
 
     fn read(self, stream: &[u8]) -> nom::IResult<&[u8], (S, u64), StateError> {
-        let (stream, data) = parse::uint(stream, self.bytes_left).map_err(nom::Err::convert)?;
+        let (stream, data) =
+            parse::uint(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
+        if !E::RANGE.contains(&data) {
+            return Err(nom::Err::Failure(StateError::OutOfRange(E::ID)));
+        }
 
         Ok((stream, (self.parent_state, data)))
     }
@@ -87,7 +389,11 @@ impl<E: IntElementDef, S> StateDataParser<'_, IntParserMarker, i64> for ElementS
     type NextState = S;
 
     fn read(self, stream: &[u8]) -> nom::IResult<&[u8], (S, i64), StateError> {
-        let (stream, data) = parse::int(stream, self.bytes_left).map_err(nom::Err::convert)?;
+        let (stream, data) =
+            parse::int(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
+        if !E::RANGE.contains(&data) {
+            return Err(nom::Err::Failure(StateError::OutOfRange(E::ID)));
+        }
 
         Ok((stream, (self.parent_state, data)))
     }
@@ -97,7 +403,11 @@ impl<E: FloatElementDef, S> StateDataParser<'_, FloatParserMarker, f64> for Elem
     type NextState = S;
 
     fn read(self, stream: &[u8]) -> nom::IResult<&[u8], (S, f64), StateError> {
-        let (stream, data) = parse::float64(stream, self.bytes_left).map_err(nom::Err::convert)?;
+        let (stream, data) =
+            parse::float64(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
+        if !E::RANGE.contains(&data) {
+            return Err(nom::Err::Failure(StateError::OutOfRange(E::ID)));
+        }
 
         Ok((stream, (self.parent_state, data)))
     }
@@ -107,7 +417,11 @@ impl<E: DateElementDef, S> StateDataParser<'_, DateParserMarker, i64> for Elemen
     type NextState = S;
 
     fn read(self, stream: &[u8]) -> nom::IResult<&[u8], (S, i64), StateError> {
-        let (stream, data) = parse::date(stream, self.bytes_left).map_err(nom::Err::convert)?;
+        let (stream, data) =
+            parse::date(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
+        if !E::RANGE.contains(&data) {
+            return Err(nom::Err::Failure(StateError::OutOfRange(E::ID)));
+        }
 
         Ok((stream, (self.parent_state, data)))
     }
@@ -118,9 +432,9 @@ impl<'a, E: StringElementDef, S> StateDataParser<'a, StringParserMarker, &'a str
 {
     type NextState = S;
 
-    fn read(self, stream: &'a [u8]) -> nom::IResult<&[u8], (S, &'a str), StateError> {
+    fn read(self, stream: &'a [u8]) -> nom::IResult<&'a [u8], (S, &'a str), StateError> {
         let (stream, data) =
-            parse::ascii_str(stream, self.bytes_left).map_err(nom::Err::convert)?;
+            parse::ascii_str(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
 
         Ok((stream, (self.parent_state, data)))
     }
@@ -133,7 +447,7 @@ impl<'a, E: Utf8ElementDef, S> StateDataParser<'a, Utf8ParserMarker, &'a str>
 
     fn read(self, stream: &[u8]) -> nom::IResult<&[u8], (S, &str), StateError> {
         let (stream, data) =
-            parse::unicode_str(stream, self.bytes_left).map_err(nom::Err::convert)?;
+            parse::unicode_str(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
 
         Ok((stream, (self.parent_state, data)))
     }
@@ -145,197 +459,1313 @@ impl<'a, E: BinaryElementDef, S> StateDataParser<'a, BinaryParserMarker, &'a [u8
     type NextState = S;
 
     fn read(self, stream: &[u8]) -> nom::IResult<&[u8], (S, &[u8]), StateError> {
-        let (stream, data) = parse::binary(stream, self.bytes_left).map_err(nom::Err::convert)?;
+        let (stream, data) =
+            parse::binary(stream, self.known_bytes_left()?).map_err(nom::Err::convert)?;
 
         Ok((stream, (self.parent_state, data)))
     }
 }
 
+// the value an element takes when it's empty, or absent from its parent
+pub trait DefaultValue<M: ParserMarker, T> {
+    fn default_value() -> Option<T>;
+}
+
+impl<E: UIntElementDef, S> DefaultValue<UIntParserMarker, u64> for ElementState<E, S> {
+    fn default_value() -> Option<u64> {
+        E::DEFAULT
+    }
+}
+
+impl<E: IntElementDef, S> DefaultValue<IntParserMarker, i64> for ElementState<E, S> {
+    fn default_value() -> Option<i64> {
+        E::DEFAULT
+    }
+}
+
+impl<E: FloatElementDef, S> DefaultValue<FloatParserMarker, f64> for ElementState<E, S> {
+    fn default_value() -> Option<f64> {
+        E::DEFAULT
+    }
+}
+
+impl<E: DateElementDef, S> DefaultValue<DateParserMarker, i64> for ElementState<E, S> {
+    fn default_value() -> Option<i64> {
+        E::DEFAULT
+    }
+}
+
+impl<'a, E: StringElementDef, S> DefaultValue<StringParserMarker, &'a str> for ElementState<E, S> {
+    fn default_value() -> Option<&'a str> {
+        E::DEFAULT
+    }
+}
+
+impl<'a, E: Utf8ElementDef, S> DefaultValue<Utf8ParserMarker, &'a str> for ElementState<E, S> {
+    fn default_value() -> Option<&'a str> {
+        E::DEFAULT
+    }
+}
+
+impl<'a, E: BinaryElementDef, S> DefaultValue<BinaryParserMarker, &'a [u8]> for ElementState<E, S> {
+    fn default_value() -> Option<&'a [u8]> {
+        E::DEFAULT
+    }
+}
+
+impl<E: ElementDef, S> ElementState<E, S> {
+    pub fn known_len(&self) -> Result<u64, nom::Err<StateError>> {
+        self.bytes_left
+            .known()
+            .ok_or(nom::Err::Failure(StateError::UnknownSize(E::ID)))
+    }
+
+    // the bytes left, as needed to read them in memory
+    pub fn known_bytes_left(&self) -> Result<usize, nom::Err<StateError>> {
+        let len = self.known_len()?;
+        usize::try_from(len)
+            .map_err(|_| nom::Err::Failure(StateError::ElementTooLarge { id: E::ID, len }))
+    }
+
+    // deducts a child element (its header & data) from the bytes left in this element
+    pub fn deduct_child(
+        &mut self,
+        id: u32,
+        len: Option<u64>,
+        header_len: usize,
+    ) -> Result<(), nom::Err<StateError>> {
+        if let ElementLength::Known(bytes_left) = &mut self.bytes_left {
+            // unknown-size elements are only supported within other unknown-size elements
+            let len = len.ok_or(nom::Err::Failure(StateError::UnknownSize(id)))?;
+            let child_len = len.saturating_add(header_len as u64);
+            *bytes_left = bytes_left.checked_sub(child_len).ok_or(nom::Err::Failure(
+                StateError::ExceedsParent {
+                    parent_id: E::ID,
+                    id,
+                    len,
+                },
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: MasterElementDef, S> ElementState<E, S> {
+    // counts another occurrence of a child element, which may not exceed the child's `MAX_OCCURS`
+    pub fn count_occurrence<C: ElementDef>(&mut self) -> Result<(), nom::Err<StateError>> {
+        let count = self.occurrences.entry(C::ID).or_insert(0);
+        *count += 1;
+        match C::MAX_OCCURS {
+            Some(max_occurs) if *count > max_occurs => {
+                Err(nom::Err::Failure(StateError::OccurrenceViolation {
+                    parent_id: E::ID,
+                    child_id: C::ID,
+                    count: *count,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // checks the element's CRC-32 (if it has one), upon leaving the element
+    pub fn check_crc(&self) -> Result<(), nom::Err<StateError>> {
+        match self.crc {
+            Some(crc) if crc.actual != crc.expected => {
+                Err(nom::Err::Failure(StateError::CrcMismatch {
+                    id: E::ID,
+                    expected: crc.expected,
+                    actual: crc.actual,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // checks that all mandatory children have occurred, upon leaving the element
+    pub fn check_mandatory_children(&self) -> Result<(), nom::Err<StateError>> {
+        for &(child_id, min_occurs) in E::MANDATORY_CHILDREN {
+            let count = self.occurrences.get(&child_id).copied().unwrap_or(0);
+            if count < min_occurs {
+                return Err(nom::Err::Failure(StateError::OccurrenceViolation {
+                    parent_id: E::ID,
+                    child_id,
+                    count,
+                }));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<E: ElementDef, S> BoundTo for ElementState<E, S> {
     type Element = E;
 }
 
+// The read-ahead used for readers that aren't already buffered, matching `std::io::BufReader`'s
+// default. Elements larger than the read-ahead are still read in full, by buffering them across
+// multiple reads.
+pub const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 pub struct ElementReader<R, S> {
     pub reader: R,
     pub state: S,
+    // data pulled out of `reader` for a parse that straddled the end of the reader's buffer
+    pub(crate) pending: Vec<u8>,
+    // the number of octets parsed so far
+    pub(crate) position: u64,
+}
+
+#[cfg(feature = "std")]
+impl<R, S> ElementReader<R, S> {
+    // the offset of the next octet to be parsed, from the start of the stream
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    // recovers the underlying reader, e.g. to read any data trailing the parsed elements
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
 }
 
+#[cfg(feature = "std")]
 #[derive(thiserror::Error, Debug)]
 pub enum ReaderError {
     #[error("IOError: {0}")]
     Io(#[from] std::io::Error),
-    #[error("ParseError: {0}")]
-    Parse(#[from] nom::Err<StateError>),
+    #[error("ParseError at offset {1:#x}: {0}")]
+    Parse(nom::Err<StateError>, u64),
+    #[error("unexpected end of stream inside element data")]
+    UnexpectedEof,
+    #[error("unsupported EBML read version {0}")]
+    UnsupportedEbmlVersion(u64),
+    #[error("element data can't be read as the requested type (id = {0})")]
+    DataType(u32),
+    #[error("no element named {0:?} in the schema")]
+    UnknownName(String),
+}
+
+// `io::Error`s can't be compared directly -> two I/O errors are equal if their kinds are
+#[cfg(feature = "std")]
+impl PartialEq for ReaderError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Io(e1), Self::Io(e2)) => e1.kind() == e2.kind(),
+            (Self::Parse(e1, offset1), Self::Parse(e2, offset2)) => e1 == e2 && offset1 == offset2,
+            (Self::UnexpectedEof, Self::UnexpectedEof) => true,
+            (Self::UnsupportedEbmlVersion(v1), Self::UnsupportedEbmlVersion(v2)) => v1 == v2,
+            (Self::DataType(id1), Self::DataType(id2)) => id1 == id2,
+            (Self::UnknownName(name1), Self::UnknownName(name2)) => name1 == name2,
+            _ => false,
+        }
+    }
 }
 
+#[cfg(feature = "std")]
 pub trait SkipReaderNavigation<R> {
     type PrevReaders;
 
     fn skip(self) -> Result<Self::PrevReaders, ReaderError>;
 }
 
+#[cfg(feature = "std")]
 pub trait NextReaderNavigation<R> {
     type NextReaders;
 
     fn next(self) -> Result<Self::NextReaders, ReaderError>;
 }
 
-impl<R: std::io::BufRead, S: SkipStateNavigation> SkipReaderNavigation<R> for ElementReader<R, S>
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, S: SkipStateNavigation + Clone> SkipReaderNavigation<R>
+    for ElementReader<R, S>
 where
     S::PrevStates: IntoReader<R>,
 {
     type PrevReaders = <S::PrevStates as IntoReader<R>>::Reader;
 
-    fn skip(mut self) -> Result<Self::PrevReaders, ReaderError> {
-        let stream = self.reader.fill_buf()?;
+    fn skip(self) -> Result<Self::PrevReaders, ReaderError> {
+        let Self {
+            mut reader,
+            state,
+            mut pending,
+            mut position,
+        } = self;
+        let prev_state = match state.skip_unread() {
+            // the data isn't needed -> streamed past, rather than buffered all at once
+            Ok((len, prev_state)) => {
+                skip_buffered(&mut reader, &mut pending, &mut position, len)?;
+                prev_state
+            }
+            Err(state) => parse_buffered(&mut reader, &mut pending, &mut position, |stream| {
+                state.clone().skip(stream)
+            })?,
+        };
 
-        let (next_stream, next_state) = self.state.skip(stream)?;
-        let stream_dist = stream.len() - next_stream.len();
-        self.reader.consume(stream_dist);
+        Ok(prev_state.into_reader(reader, position))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead + std::io::Seek, E: ElementDef, P: IntoReader<R>>
+    ElementReader<R, ElementState<E, P>>
+where
+    ElementState<E, P>: SkipStateNavigation<PrevStates = P> + CrcAccumulator + Clone,
+{
+    // Skips the element by seeking past its data, rather than reading through it. Falls back to
+    // `skip` when the data is needed for a CRC-32 check. Seeking past the end of the stream isn't
+    // an error in itself -> it surfaces when parsing continues after the skipped element.
+    pub fn seek_skip(self) -> Result<P::Reader, ReaderError> {
+        let data_len = self
+            .state
+            .known_len()
+            .map_err(|error| ReaderError::Parse(error, self.position))?;
+        // (data already pulled into `pending` past the element's end must be kept for its parent)
+        if E::ID == CRC32_ID || self.state.crc_pending() || self.pending.len() as u64 > data_len {
+            return self.skip();
+        }
 
-        Ok(next_state.into_reader(self.reader))
+        let Self {
+            mut reader,
+            state,
+            pending,
+            position,
+        } = self;
+        // whatever's left in `pending` is all from the front of the element
+        let len = data_len - pending.len() as u64;
+        reader.seek(std::io::SeekFrom::Current(
+            len.try_into().unwrap_or(i64::MAX),
+        ))?;
+
+        Ok(state.parent_state.into_reader(reader, position + data_len))
     }
 }
 
-impl<R: std::io::BufRead, S: NextStateNavigation> NextReaderNavigation<R> for ElementReader<R, S>
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, S: NextStateNavigation + Clone> NextReaderNavigation<R>
+    for ElementReader<R, S>
 where
     S::NextStates: IntoReader<R>,
 {
     type NextReaders = <S::NextStates as IntoReader<R>>::Reader;
 
-    fn next(mut self) -> Result<Self::NextReaders, ReaderError> {
-        let stream = self.reader.fill_buf()?;
+    fn next(self) -> Result<Self::NextReaders, ReaderError> {
+        let Self {
+            mut reader,
+            state,
+            mut pending,
+            mut position,
+        } = self;
+        let next_state = parse_buffered(&mut reader, &mut pending, &mut position, |stream| {
+            state.clone().next(stream)
+        })?;
+
+        Ok(next_state.into_reader(reader, position))
+    }
+}
+
+impl<E: ElementDef, P: ElementDef, G: CrcAccumulator> ElementState<E, ElementState<P, G>> {
+    // skips the rest of this element & all of its remaining siblings, up to the end of its parent
+    // -> the parent's own CRC-32 (if any) goes unchecked
+    pub fn skip_to_parent_end(self, stream: &[u8]) -> nom::IResult<&[u8], G, StateError> {
+        let (stream, data) = nom::bytes::streaming::take::<_, _, ParseError>(
+            self.known_bytes_left()? + self.parent_state.known_bytes_left()?,
+        )(stream)
+        .map_err(nom::Err::convert)?;
+
+        let mut grandparent_state = self.parent_state.parent_state;
+        grandparent_state.update_crc(data);
+        Ok((stream, grandparent_state))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: ElementDef, P: ElementDef, G: IntoReader<R> + CrcAccumulator>
+    ElementReader<R, ElementState<E, ElementState<P, G>>>
+where
+    ElementState<E, ElementState<P, G>>: Clone,
+{
+    pub fn skip_to_parent_end(self) -> Result<G::Reader, ReaderError> {
+        let Self {
+            mut reader,
+            state,
+            mut pending,
+            mut position,
+        } = self;
+        let next_state = parse_buffered(&mut reader, &mut pending, &mut position, |stream| {
+            state.clone().skip_to_parent_end(stream)
+        })?;
+
+        Ok(next_state.into_reader(reader, position))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, S: DocumentConfigured> ElementReader<R, S> {
+    // parses the ID of the upcoming element without consuming it -> `None` at the end of the stream
+    pub(crate) fn peek_next_id(&mut self) -> Result<Option<u32>, ReaderError> {
+        let max_id_length = self.state.document_config().max_id_length;
+        loop {
+            let stream = buffered(&mut self.reader, &self.pending)?;
+            if stream.is_empty() {
+                return Ok(None);
+            }
+            let needed = match parse::element_id_bounded(stream, max_id_length) {
+                Ok((_, id)) => return Ok(Some(id)),
+                Err(nom::Err::Incomplete(needed)) => needed,
+                Err(error) => {
+                    return Err(ReaderError::Parse(nom::Err::convert(error), self.position))
+                }
+            };
+            // any data pulled in stays at the front of the stream, for the following `next`
+            if !pull(&mut self.reader, &mut self.pending, needed)? {
+                return Err(ReaderError::Parse(
+                    nom::Err::Incomplete(needed),
+                    self.position,
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: MasterElementDef, P> ElementReader<R, ElementState<E, P>>
+where
+    ElementState<E, P>: DocumentConfigured,
+{
+    // the ID of the element's next child, without moving into it -> `None` once the element has
+    // no data left
+    pub fn peek_id(&mut self) -> Result<Option<u32>, ReaderError> {
+        if self.state.bytes_left == ElementLength::Known(0) {
+            return Ok(None);
+        }
+        self.peek_next_id()
+    }
+}
+
+// gives access to the document-wide configuration from any state within the document
+pub trait DocumentConfigured {
+    fn document_config(&self) -> DocumentConfig;
+    fn document_config_mut(&mut self) -> &mut DocumentConfig;
+}
+
+impl<E: ElementDef, S: DocumentConfigured> DocumentConfigured for ElementState<E, S> {
+    fn document_config(&self) -> DocumentConfig {
+        self.parent_state.document_config()
+    }
+
+    fn document_config_mut(&mut self) -> &mut DocumentConfig {
+        self.parent_state.document_config_mut()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R, S: DocumentConfigured> ElementReader<R, S> {
+    // enables/disables checking `CRC-32` elements, for the rest of the document
+    pub fn verify_crc(mut self, verify: bool) -> Self {
+        self.state.document_config_mut().verify_crc = verify;
+        self
+    }
+
+    // enables/disables skipping elements that aren't in the schema, for the rest of the document
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.state.document_config_mut().lenient = lenient;
+        self
+    }
+
+    // enables/disables rejecting element lengths w/ wider encodings than necessary, for the rest of
+    // the document
+    pub fn strict_lengths(mut self, strict: bool) -> Self {
+        self.state.document_config_mut().strict_lengths = strict;
+        self
+    }
+}
+
+// feeds the data read at the current position to the CRC-32 checks of all enclosing elements
+pub trait CrcAccumulator {
+    fn update_crc(&mut self, data: &[u8]);
+    fn start_crc(&mut self, expected: u32);
+    // whether any enclosing element has a CRC-32 check in progress
+    fn crc_pending(&self) -> bool;
+}
+
+impl<E: ElementDef, S: CrcAccumulator> CrcAccumulator for ElementState<E, S> {
+    fn update_crc(&mut self, data: &[u8]) {
+        if let Some(crc) = &mut self.crc {
+            crc.update(data);
+        }
+        self.parent_state.update_crc(data);
+    }
+
+    fn start_crc(&mut self, expected: u32) {
+        self.crc = Some(CrcCheck::new(expected));
+    }
+
+    fn crc_pending(&self) -> bool {
+        self.crc.is_some() || self.parent_state.crc_pending()
+    }
+}
+
+// reports the bytes left to read in the nearest master element enclosing the current position
+pub trait RemainingBytes {
+    fn remaining_in_current_master(&self) -> Option<u64>;
+}
+
+#[cfg(feature = "std")]
+impl<R, S: RemainingBytes> ElementReader<R, S> {
+    pub fn remaining_in_current_master(&self) -> Option<u64> {
+        self.state.remaining_in_current_master()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: AsRef<[u8]>, S> ElementReader<std::io::Cursor<T>, S> {
+    pub fn total_remaining(&self) -> usize {
+        let total_len = self.reader.get_ref().as_ref().len();
+        total_len.saturating_sub(self.reader.position().try_into().unwrap_or(usize::MAX))
+    }
+}
 
-        let (next_stream, next_state) = self.state.next(stream)?;
-        let stream_dist = stream.len() - next_stream.len();
-        self.reader.consume(stream_dist);
+#[cfg(feature = "std")]
+impl<S> ElementReader<&[u8], S> {
+    pub fn total_remaining(&self) -> usize {
+        self.reader.len()
+    }
+}
 
-        Ok(next_state.into_reader(self.reader))
+// Data that straddles the end of a reader's buffer is moved into a separate `pending` buffer, which
+// then stands in for the front of the stream until it has been consumed.
+#[cfg(feature = "std")]
+fn buffered<'a, R: std::io::BufRead>(
+    reader: &'a mut R,
+    pending: &'a [u8],
+) -> std::io::Result<&'a [u8]> {
+    if pending.is_empty() {
+        reader.fill_buf()
+    } else {
+        Ok(pending)
     }
 }
 
+// moves data from the reader into `pending`; returns false once the reader is exhausted
+#[cfg(feature = "std")]
+fn pull<R: std::io::BufRead>(
+    reader: &mut R,
+    pending: &mut Vec<u8>,
+    needed: nom::Needed,
+) -> std::io::Result<bool> {
+    let was_empty = pending.is_empty();
+    let buf = reader.fill_buf()?;
+    let len = match needed {
+        // the reader's buffer was too short on its own -> all of it is needed
+        _ if was_empty => buf.len(),
+        // only take what's needed, so that `pending` never holds data past the current parse
+        nom::Needed::Size(size) => size.get().min(buf.len()),
+        nom::Needed::Unknown => 1.min(buf.len()),
+    };
+    pending.extend_from_slice(&buf[..len]);
+    reader.consume(len);
+
+    Ok(len > 0)
+}
+
+#[cfg(feature = "std")]
+fn buffered_at_least<'a, R: std::io::BufRead>(
+    reader: &'a mut R,
+    pending: &'a mut Vec<u8>,
+    len: usize,
+) -> std::io::Result<&'a [u8]> {
+    loop {
+        let available = buffered(reader, pending)?.len();
+        if available >= len || !pull(reader, pending, nom::Needed::new(len - available))? {
+            break;
+        }
+    }
+    buffered(reader, pending)
+}
+
+// Parses from the front of the stream, pulling in more data whenever `parse` reports that its
+// input is incomplete.
+#[cfg(feature = "std")]
+pub(crate) fn parse_buffered<R: std::io::BufRead, T>(
+    reader: &mut R,
+    pending: &mut Vec<u8>,
+    position: &mut u64,
+    parse: impl Fn(&[u8]) -> nom::IResult<&[u8], T, StateError>,
+) -> Result<T, ReaderError> {
+    loop {
+        let stream = buffered(reader, pending)?;
+        let needed = match parse(stream) {
+            Err(nom::Err::Incomplete(needed)) => needed,
+            // skips an element too large to buffer, & then parses again from the element after it
+            Err(nom::Err::Failure(StateError::SkipTooLarge {
+                len, stream_left, ..
+            })) => {
+                let header_len = stream.len() - stream_left;
+                skip_buffered(reader, pending, position, header_len as u64 + len)?;
+                continue;
+            }
+            _ => break,
+        };
+        if !pull(reader, pending, needed)? {
+            break;
+        }
+    }
+
+    let stream = buffered(reader, pending)?;
+    let (next_stream, value) =
+        parse(stream).map_err(|error| ReaderError::Parse(error, *position))?;
+    let stream_dist = stream_diff(stream, next_stream);
+    *position += stream_dist as u64;
+    if pending.is_empty() {
+        reader.consume(stream_dist);
+    } else {
+        pending.drain(..stream_dist);
+    }
+
+    Ok(value)
+}
+
+// Moves `len` octets past the front of the stream, a buffer-full at a time. The octets don't
+// have to fit into memory all at once (or even into a `usize`).
+#[cfg(feature = "std")]
+fn skip_buffered<R: std::io::BufRead>(
+    reader: &mut R,
+    pending: &mut Vec<u8>,
+    position: &mut u64,
+    mut len: u64,
+) -> Result<(), ReaderError> {
+    while len > 0 {
+        let available = buffered(reader, pending)?.len();
+        if available == 0 {
+            return Err(ReaderError::UnexpectedEof);
+        }
+        let amt = usize::try_from(len).map_or(available, |len| len.min(available));
+        if pending.is_empty() {
+            reader.consume(amt);
+        } else {
+            pending.drain(..amt);
+        }
+        *position += amt as u64;
+        len -= amt as u64;
+    }
+
+    Ok(())
+}
+
+// Data readers buffer an element's entire payload before parsing it, so a payload that is still
+// incomplete can never be completed by that reader.
+#[cfg(feature = "std")]
+pub(crate) fn incomplete_as_eof(position: u64) -> impl Fn(nom::Err<StateError>) -> ReaderError {
+    move |error| match error {
+        nom::Err::Incomplete(_) => ReaderError::UnexpectedEof,
+        error => ReaderError::Parse(error, position),
+    }
+}
+
+#[cfg(feature = "std")]
 pub trait ReaderDataParser<'a, R, M: ParserMarker, T: 'a> {
     fn read(&'a mut self) -> Result<T, ReaderError>;
 }
 
+#[cfg(feature = "std")]
 impl<R: std::io::BufRead, E: UIntElementDef + Clone, S: Clone>
     ReaderDataParser<'_, R, UIntParserMarker, u64> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<u64, ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: std::io::BufRead, E: IntElementDef + Clone, S: Clone>
     ReaderDataParser<'_, R, IntParserMarker, i64> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<i64, ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: std::io::BufRead, E: FloatElementDef + Clone, S: Clone>
     ReaderDataParser<'_, R, FloatParserMarker, f64> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<f64, ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: std::io::BufRead, E: DateElementDef + Clone, S: Clone>
     ReaderDataParser<'_, R, DateParserMarker, i64> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<i64, ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
+// the EBML epoch, 2001-01-01T00:00:00 UTC, in seconds since the Unix epoch
+pub const EBML_EPOCH_UNIX_SECS: u64 = 978_307_200;
+
+// converts a date element's value (in nanoseconds since the EBML epoch) to a system time, which
+// e.g. `chrono::DateTime<Utc>` converts from
+#[cfg(feature = "std")]
+pub fn date_to_system_time(date: i64) -> SystemTime {
+    let ebml_epoch = UNIX_EPOCH + Duration::from_secs(EBML_EPOCH_UNIX_SECS);
+    let offset = Duration::from_nanos(date.unsigned_abs());
+    if date < 0 {
+        ebml_epoch - offset
+    } else {
+        ebml_epoch + offset
+    }
+}
+
+// converts a system time to a date element's value, if it's within ~292 years of the EBML epoch
+#[cfg(feature = "std")]
+pub fn system_time_to_date(time: SystemTime) -> Option<i64> {
+    let ebml_epoch = UNIX_EPOCH + Duration::from_secs(EBML_EPOCH_UNIX_SECS);
+    match time.duration_since(ebml_epoch) {
+        Ok(offset) => offset.as_nanos().try_into().ok(),
+        Err(error) => 0i128
+            .checked_sub(error.duration().as_nanos().try_into().ok()?)?
+            .try_into()
+            .ok(),
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: DateElementDef + Clone, S: Clone>
+    ElementReader<R, ElementState<E, S>>
+{
+    // reads the date as a system time, rather than as nanoseconds since the EBML epoch
+    pub fn read_system_time(&mut self) -> Result<SystemTime, ReaderError> {
+        ReaderDataParser::<R, DateParserMarker, i64>::read(self).map(date_to_system_time)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: ElementDef, S> ElementReader<R, ElementState<E, S>> {
+    // reads the element's data as-is, regardless of its type (e.g. to hash or forward it)
+    pub fn read_raw(&mut self) -> Result<&[u8], ReaderError> {
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+
+        stream.get(..len).ok_or(ReaderError::UnexpectedEof)
+    }
+}
+
+#[cfg(feature = "std")]
 impl<'a, R: std::io::BufRead, E: StringElementDef + Clone, S: Clone>
     ReaderDataParser<'a, R, StringParserMarker, &'a str> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<&str, ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, R: std::io::BufRead, E: Utf8ElementDef + Clone, S: Clone>
     ReaderDataParser<'a, R, Utf8ParserMarker, &'a str> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<&str, ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, R: std::io::BufRead, E: BinaryElementDef + Clone, S: Clone>
     ReaderDataParser<'a, R, BinaryParserMarker, &'a [u8]> for ElementReader<R, ElementState<E, S>>
 {
     fn read(&mut self) -> Result<&[u8], ReaderError> {
-        let stream = self.reader.fill_buf()?;
-        let (_, (_, data)) = self.state.clone().read(stream)?;
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        let stream = buffered_at_least(&mut self.reader, &mut self.pending, len)?;
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(stream)
+            .map_err(incomplete_as_eof(position))?;
 
         Ok(data)
     }
 }
 
-impl<E: ElementDef, S, R: std::io::BufRead> From<ElementReader<R, ElementState<E, S>>>
-    for ElementState<E, S>
+#[cfg(feature = "std")]
+impl<R, E: ElementDef, S> ElementReader<R, ElementState<E, S>> {
+    // reads the element's data, or its schema default if the element is empty
+    pub fn read_or_default<'a, M: ParserMarker, T: 'a>(&'a mut self) -> Result<T, ReaderError>
+    where
+        Self: ReaderDataParser<'a, R, M, T>,
+        ElementState<E, S>: DefaultValue<M, T>,
+    {
+        if self.state.bytes_left == ElementLength::Known(0) {
+            if let Some(default) = ElementState::<E, S>::default_value() {
+                return Ok(default);
+            }
+        }
+        ReaderDataParser::<R, M, T>::read(self)
+    }
+}
+
+#[cfg(all(feature = "bytes", feature = "std"))]
+impl<R: std::io::BufRead, E: BinaryElementDef + Clone, S: Clone>
+    ElementReader<R, ElementState<E, S>>
+{
+    // copies the element's data into a reference-counted `bytes::Bytes` buffer, which can be
+    // cheaply shared after the reader has moved on
+    pub fn copy_to_bytes(&mut self) -> Result<bytes::Bytes, ReaderError> {
+        let data: &[u8] = self.read()?;
+
+        Ok(bytes::Bytes::copy_from_slice(data))
+    }
+}
+
+#[cfg(all(feature = "bytes", feature = "std"))]
+impl<E: BinaryElementDef + Clone, S: Clone>
+    ElementReader<std::io::Cursor<bytes::Bytes>, ElementState<E, S>>
 {
+    // reads the element's data as a slice of the stream's own `bytes::Bytes` buffer, w/o copying it
+    pub fn read_bytes(&mut self) -> Result<bytes::Bytes, ReaderError> {
+        // (the cursor's buffer is the whole stream, so data is only ever pulled out of it at the end
+        // of a truncated stream)
+        if !self.pending.is_empty() {
+            return self.copy_to_bytes();
+        }
+        let data: &[u8] = self.read()?;
+        let len = data.len();
+        let start = self.reader.position() as usize;
+
+        Ok(self.reader.get_ref().slice(start..start + len))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: BinaryElementDef, S: CrcAccumulator>
+    ElementReader<R, ElementState<E, S>>
+{
+    // reads the element's data a buffer-full at a time, rather than buffering all of it at once
+    pub fn binary_chunks(&mut self) -> BinaryChunks<'_, R, E, S> {
+        BinaryChunks {
+            reader: self,
+            last_len: 0,
+        }
+    }
+}
+
+// The data of a binary element, in chunks of whatever the underlying reader has buffered. Each
+// chunk borrows the reader's buffer, so chunks are read w/ `next_chunk` rather than as an
+// `Iterator`; to copy them elsewhere (e.g. to a file), use `std::io::Read`. (A `CRC-32` element
+// must be read in full, for its check to start.)
+#[cfg(feature = "std")]
+pub struct BinaryChunks<'a, R, E: ElementDef, S> {
+    reader: &'a mut ElementReader<R, ElementState<E, S>>,
+    // the length of the last chunk, which is consumed upon reading the next one
+    last_len: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: BinaryElementDef, S: CrcAccumulator> BinaryChunks<'_, R, E, S> {
+    pub fn next_chunk(&mut self) -> Option<Result<&[u8], ReaderError>> {
+        if let Err(error) = self.consume_last() {
+            return Some(Err(error.into()));
+        }
+        let reader = &mut *self.reader;
+        let len = match reader.state.known_bytes_left() {
+            Ok(0) => return None,
+            Ok(len) => len,
+            Err(error) => return Some(Err(ReaderError::Parse(error, reader.position))),
+        };
+        let chunk = match buffered(&mut reader.reader, &reader.pending) {
+            Ok([]) => return Some(Err(ReaderError::UnexpectedEof)),
+            Ok(chunk) => &chunk[..len.min(chunk.len())],
+            Err(error) => return Some(Err(error.into())),
+        };
+        self.last_len = chunk.len();
+
+        Some(Ok(chunk))
+    }
+
+    // Moves the reader past the last chunk. Until then, the chunk stays at the front of the
+    // reader's buffer -> if the chunks are dropped first, the reader simply reads it again.
+    fn consume_last(&mut self) -> std::io::Result<()> {
+        let len = core::mem::take(&mut self.last_len);
+        if len == 0 {
+            return Ok(());
+        }
+        let reader = &mut *self.reader;
+        let data = buffered(&mut reader.reader, &reader.pending)?;
+        reader.state.parent_state.update_crc(&data[..len]);
+        if reader.pending.is_empty() {
+            reader.reader.consume(len);
+        } else {
+            reader.pending.drain(..len);
+        }
+        reader.position += len as u64;
+        if let ElementLength::Known(bytes_left) = &mut reader.state.bytes_left {
+            *bytes_left -= len as u64;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead, E: BinaryElementDef, S: CrcAccumulator> std::io::Read
+    for BinaryChunks<'_, R, E, S>
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let chunk = match self.next_chunk() {
+            Some(chunk) => chunk.map_err(std::io::Error::other)?,
+            None => return Ok(0),
+        };
+        let len = chunk.len().min(buf.len());
+        buf[..len].copy_from_slice(&chunk[..len]);
+        // only what was copied is consumed
+        self.last_len = len;
+
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: ElementDef, S, R> From<ElementReader<R, ElementState<E, S>>> for ElementState<E, S> {
     fn from(reader: ElementReader<R, ElementState<E, S>>) -> Self {
         reader.state
     }
 }
 
+#[cfg(feature = "std")]
+impl<E: ElementDef, S, R> From<ElementReader<R, ElementState<E, S>>> for Box<ElementState<E, S>> {
+    fn from(reader: ElementReader<R, ElementState<E, S>>) -> Self {
+        Box::new(reader.state)
+    }
+}
+
+#[cfg(feature = "std")]
 impl<R, S: BoundTo> BoundTo for ElementReader<R, S> {
     type Element = S::Element;
 }
 
+#[cfg(feature = "std")]
 pub trait IntoReader<R: std::io::BufRead> {
     type Reader;
 
-    fn into_reader(self, reader: R) -> Self::Reader;
+    fn into_reader(self, reader: R, position: u64) -> Self::Reader;
 }
 
+#[cfg(feature = "std")]
 impl<E: ElementDef, S, R: std::io::BufRead> IntoReader<R> for ElementState<E, S> {
     type Reader = ElementReader<R, ElementState<E, S>>;
 
-    fn into_reader(self, reader: R) -> Self::Reader {
+    fn into_reader(self, reader: R, position: u64) -> Self::Reader {
         Self::Reader {
             reader,
             state: self,
+            pending: Vec::new(),
+            position,
+        }
+    }
+}
+
+// recursive elements box their own states
+#[cfg(feature = "std")]
+impl<S: IntoReader<R>, R: std::io::BufRead> IntoReader<R> for Box<S> {
+    type Reader = S::Reader;
+
+    fn into_reader(self, reader: R, position: u64) -> Self::Reader {
+        (*self).into_reader(reader, position)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementData<'a> {
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    Date(i64),
+    String(&'a str),
+    Utf8(&'a str),
+    Binary(&'a [u8]),
+}
+
+// an owned copy of an element's data, e.g. to outlive the reader that it was read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementValue {
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    Date(i64),
+    String(String),
+    Utf8(String),
+    Binary(Vec<u8>),
+}
+
+impl From<ElementData<'_>> for ElementValue {
+    fn from(data: ElementData) -> Self {
+        match data {
+            ElementData::UInt(value) => Self::UInt(value),
+            ElementData::Int(value) => Self::Int(value),
+            ElementData::Float(value) => Self::Float(value),
+            ElementData::Date(value) => Self::Date(value),
+            ElementData::String(value) => Self::String(value.to_string()),
+            ElementData::Utf8(value) => Self::Utf8(value.to_string()),
+            ElementData::Binary(value) => Self::Binary(value.to_vec()),
+        }
+    }
+}
+
+// a single step in walking a document, as yielded by a generated `ElementEvents` iterator
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementEvent {
+    // a master element was entered/left (by its ID)
+    Enter(u32),
+    Leave(u32),
+    // a non-master element was read in full
+    Data(u32, ElementValue),
+}
+
+// handles the data of every element with a given ID, as registered in a `HandlerRegistry`
+pub trait ElementHandler {
+    fn handle(&mut self, id: u32, data: ElementData);
+}
+
+impl<H: ElementHandler + ?Sized> ElementHandler for &mut H {
+    fn handle(&mut self, id: u32, data: ElementData) {
+        (**self).handle(id, data)
+    }
+}
+
+#[derive(Default)]
+pub struct HandlerRegistry<'a> {
+    handlers: BTreeMap<u32, Box<dyn ElementHandler + 'a>>,
+}
+
+impl<'a> HandlerRegistry<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // replaces any handler previously registered for the same element
+    pub fn register<E: ElementDef, H: ElementHandler + 'a>(&mut self, handler: H) -> &mut Self {
+        self.register_id(E::ID, handler)
+    }
+
+    // like `register`, for an element ID only known at runtime
+    pub fn register_id<H: ElementHandler + 'a>(&mut self, id: u32, handler: H) -> &mut Self {
+        self.handlers.insert(id, Box::new(handler));
+        self
+    }
+
+    pub fn is_registered(&self, id: u32) -> bool {
+        self.handlers.contains_key(&id)
+    }
+
+    pub fn handle(&mut self, id: u32, data: ElementData) {
+        if let Some(handler) = self.handlers.get_mut(&id) {
+            handler.handle(id, data);
+        }
+    }
+}
+
+// a type that elements' data can be read as, e.g. by a generated `collect_by_id`
+pub trait FromElementData: Sized {
+    // `None` if the data is of another type
+    fn from_element_data(data: ElementData) -> Option<Self>;
+}
+
+impl FromElementData for u64 {
+    fn from_element_data(data: ElementData) -> Option<Self> {
+        match data {
+            ElementData::UInt(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+// (dates are signed nanoseconds since the EBML epoch)
+impl FromElementData for i64 {
+    fn from_element_data(data: ElementData) -> Option<Self> {
+        match data {
+            ElementData::Int(value) | ElementData::Date(value) => Some(value),
+            _ => None,
         }
     }
 }
 
+impl FromElementData for f64 {
+    fn from_element_data(data: ElementData) -> Option<Self> {
+        match data {
+            ElementData::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromElementData for String {
+    fn from_element_data(data: ElementData) -> Option<Self> {
+        match data {
+            ElementData::String(value) | ElementData::Utf8(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl FromElementData for Vec<u8> {
+    fn from_element_data(data: ElementData) -> Option<Self> {
+        match data {
+            ElementData::Binary(value) => Some(value.to_vec()),
+            _ => None,
+        }
+    }
+}
+
+impl FromElementData for ElementValue {
+    fn from_element_data(data: ElementData) -> Option<Self> {
+        Some(data.into())
+    }
+}
+
+// collects the data of every element that it handles
+pub struct ElementCollector<T> {
+    values: Vec<T>,
+    // the first element whose data wasn't of type `T`
+    mismatched: Option<u32>,
+}
+
+impl<T> ElementCollector<T> {
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            mismatched: None,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn into_values(self) -> Result<Vec<T>, ReaderError> {
+        match self.mismatched {
+            Some(id) => Err(ReaderError::DataType(id)),
+            None => Ok(self.values),
+        }
+    }
+}
+
+impl<T> Default for ElementCollector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: FromElementData> ElementHandler for ElementCollector<T> {
+    fn handle(&mut self, id: u32, data: ElementData) {
+        match T::from_element_data(data) {
+            Some(value) => self.values.push(value),
+            None => {
+                self.mismatched.get_or_insert(id);
+            }
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! impl_remaining_bytes {
+    ( master $State:ident ) => {
+        impl RemainingBytes for $State {
+            fn remaining_in_current_master(&self) -> Option<u64> {
+                self.bytes_left.known()
+            }
+        }
+    };
+
+    ( child $State:ident ) => {
+        impl RemainingBytes for $State {
+            fn remaining_in_current_master(&self) -> Option<u64> {
+                self.parent_state.remaining_in_current_master()
+            }
+        }
+    };
+
+    ( $States:ident, [ $( $StateName:ident ),+ ] ) => {
+        impl RemainingBytes for $States {
+            fn remaining_in_current_master(&self) -> Option<u64> {
+                match self {
+                    $(
+                        Self::$StateName(state) => state.remaining_in_current_master(),
+                    )+
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_document_configured {
+    ( $States:ident, [ $( $StateName:ident ),+ ] ) => {
+        impl DocumentConfigured for $States {
+            fn document_config(&self) -> DocumentConfig {
+                match self {
+                    $(
+                        Self::$StateName(state) => state.document_config(),
+                    )+
+                }
+            }
+
+            fn document_config_mut(&mut self) -> &mut DocumentConfig {
+                match self {
+                    $(
+                        Self::$StateName(state) => state.document_config_mut(),
+                    )+
+                }
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_crc_accumulator {
+    ( $States:ident, [ $( $StateName:ident ),+ ] ) => {
+        impl CrcAccumulator for $States {
+            fn update_crc(&mut self, data: &[u8]) {
+                match self {
+                    $(
+                        Self::$StateName(state) => state.update_crc(data),
+                    )+
+                }
+            }
+
+            fn start_crc(&mut self, expected: u32) {
+                match self {
+                    $(
+                        Self::$StateName(state) => state.start_crc(expected),
+                    )+
+                }
+            }
+
+            fn crc_pending(&self) -> bool {
+                match self {
+                    $(
+                        Self::$StateName(state) => state.crc_pending(),
+                    )+
+                }
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! impl_skip_state_navigation {
     ( $State:ident, $PrevStates:ident ) => {
         impl SkipStateNavigation for $State {
             type PrevStates = $PrevStates;
 
-            fn skip(self, stream: &[u8]) -> nom::IResult<&[u8], Self::PrevStates, StateError> {
-                let (stream, _) = nom::bytes::streaming::take::<_, _, ()>(self.bytes_left)(stream)
-                    .map_err(nom::Err::convert)?;
+            fn skip(mut self, stream: &[u8]) -> nom::IResult<&[u8], Self::PrevStates, StateError> {
+                let (stream, data) = nom::bytes::streaming::take::<_, _, parse::ParseError>(
+                    self.known_bytes_left()?,
+                )(stream)
+                .map_err(nom::Err::convert)?;
+
+                self.parent_state.update_crc(data);
+                // a `CRC-32` element starts a check over the rest of its parent
+                if <<Self as BoundTo>::Element as ElementDef>::ID == CRC32_ID
+                    && self.document_config().verify_crc
+                {
+                    if let Ok(expected) = core::convert::TryInto::try_into(data) {
+                        self.parent_state.start_crc(u32::from_le_bytes(expected));
+                    }
+                }
                 Ok((stream, self.parent_state))
             }
+
+            fn skip_unread(self) -> Result<(u64, Self::PrevStates), Self> {
+                match self.bytes_left {
+                    ElementLength::Known(len)
+                        if <<Self as BoundTo>::Element as ElementDef>::ID != CRC32_ID
+                            && !self.parent_state.crc_pending() =>
+                    {
+                        Ok((len, self.parent_state))
+                    }
+                    _ => Err(self),
+                }
+            }
         }
     };
 }
@@ -358,22 +1788,33 @@ macro_rules! impl_next_state_navigation {
             type NextStates = _DocumentNextStates;
 
             fn next(self, stream: &[u8]) -> nom::IResult<&[u8], Self::NextStates, StateError> {
-                let (stream, id) = parse::element_id(stream).map_err(nom::Err::convert)?;
-                let (stream, len) = parse::element_len(stream).map_err(nom::Err::convert)?;
-                let len: usize = len
-                    .ok_or(nom::Err::Failure(StateError::Unimplemented(
-                        "TODO: handle optionally unsized elements",
-                    )))?
-                    .try_into()
-                    .expect("overflow in storing element bytelength");
+                let config = self.document_config();
+                let (stream, id) = parse::element_id_bounded(stream, config.max_id_length)
+                    .map_err(nom::Err::convert)?;
+                let (stream, len) = parse::element_len_bounded(
+                    stream,
+                    config.max_size_length,
+                    config.strict_lengths,
+                )
+                    .map_err(nom::Err::convert)?;
 
                 Ok((
                     stream,
                     match id {
                         $(
-                            <<$ElementState as BoundTo>::Element as ElementDef>::ID =>
-                                Self::NextStates::$ElementName($ElementState::new(len, self.into())),
+                            <<$ElementState as BoundTo>::Element as ElementDef>::ID => {
+                                let len = check_length::<<$ElementState as BoundTo>::Element>(
+                                    ElementLength::new(len),
+                                )?;
+                                check_version::<<$ElementState as BoundTo>::Element>(&config)?;
+                                check_path::<<$ElementState as BoundTo>::Element>("");
+                                Self::NextStates::$ElementName($ElementState::new(len, self.into()))
+                            }
                         )*
+                        id if config.lenient => {
+                            let (stream, _) = skip_unknown_element(stream, id, len)?;
+                            return self.next(stream);
+                        }
                         id => return Err(nom::Err::Failure(StateError::InvalidChildId(None, id))),
                     },
                 ))
@@ -386,39 +1827,80 @@ macro_rules! impl_next_state_navigation {
             type NextStates = $NextStates;
 
             fn next(mut self, stream: &[u8]) -> nom::IResult<&[u8], Self::NextStates, StateError> {
-                match self {
-                    Self { bytes_left: 0, .. } => Ok((stream, Self::NextStates::Parent(self.parent_state))),
-                    _ => {
-                        let orig_stream = stream;
-
-                        let (stream, id) = parse::element_id(stream).map_err(nom::Err::convert)?;
-                        let (stream, len) = parse::element_len(stream).map_err(nom::Err::convert)?;
-                        let len: usize = len
-                            .ok_or(nom::Err::Failure(StateError::Unimplemented(
-                                "TODO: handle optionally unsized elements",
-                            )))?
-                            .try_into()
-                            .expect("overflow in storing element bytelength");
-
-                        self.bytes_left -= len + stream_diff(orig_stream, stream);
-
-                        Ok((
-                            stream,
-                            match id {
-                                $(
-                                    <<$ElementState as BoundTo>::Element as ElementDef>::ID =>
-                                        Self::NextStates::$ElementName($ElementState::new(len, self.into())),
-                                )*
-                                id => {
-                                    return Err(nom::Err::Failure(StateError::InvalidChildId(
-                                        Some(<<Self as BoundTo>::Element as ElementDef>::ID),
-                                        id,
-                                    )))
-                                }
-                            },
-                        ))
-                    }
+                if self.bytes_left == ElementLength::Known(0) {
+                    self.check_mandatory_children()?;
+                    self.check_crc()?;
+                    return Ok((stream, Self::NextStates::Parent(self.parent_state)));
+                }
+                let orig_stream = stream;
+                let config = self.document_config();
+
+                let (stream, id) = parse::element_id_bounded(stream, config.max_id_length)
+                    .map_err(nom::Err::convert)?;
+                let is_child = match id {
+                    $(
+                        <<$ElementState as BoundTo>::Element as ElementDef>::ID => true,
+                    )*
+                    _ => false,
+                };
+                if !is_child {
+                    match self.bytes_left {
+                        // the element ID belongs to the parent's remaining elements
+                        ElementLength::Unknown => {
+                            self.check_mandatory_children()?;
+                            self.check_crc()?;
+                            return Ok((orig_stream, Self::NextStates::Parent(self.parent_state)));
+                        }
+                        // an unknown element -> skipped below in lenient mode, once its length is known
+                        ElementLength::Known(_) if config.lenient => {}
+                        ElementLength::Known(_) => return Err(nom::Err::Failure(StateError::InvalidChildId(
+                            Some(<<Self as BoundTo>::Element as ElementDef>::ID),
+                            id,
+                        ))),
+                    };
+                }
+
+                let (stream, len) = parse::element_len_bounded(
+                    stream,
+                    config.max_size_length,
+                    config.strict_lengths,
+                )
+                    .map_err(nom::Err::convert)?;
+                self.update_crc(&orig_stream[..stream_diff(orig_stream, stream)]);
+                self.deduct_child(id, len, stream_diff(orig_stream, stream))?;
+                if !is_child {
+                    let (stream, data) = match skip_unknown_element(stream, id, len) {
+                        // data that the reader skips in place can't be fed to a CRC-32 check
+                        Err(nom::Err::Failure(StateError::SkipTooLarge { id, len, .. }))
+                            if self.crc_pending() =>
+                        {
+                            return Err(nom::Err::Failure(StateError::ElementTooLarge { id, len }));
+                        }
+                        result => result?,
+                    };
+                    self.update_crc(data);
+                    return self.next(stream);
                 }
+
+                Ok((
+                    stream,
+                    match id {
+                        $(
+                            <<$ElementState as BoundTo>::Element as ElementDef>::ID => {
+                                let len = check_length::<<$ElementState as BoundTo>::Element>(
+                                    ElementLength::new(len),
+                                )?;
+                                check_version::<<$ElementState as BoundTo>::Element>(&config)?;
+                                self.count_occurrence::<<$ElementState as BoundTo>::Element>()?;
+                                check_path::<<$ElementState as BoundTo>::Element>(
+                                    <<Self as BoundTo>::Element as ElementDef>::PATH,
+                                );
+                                Self::NextStates::$ElementName($ElementState::new(len, self.into()))
+                            }
+                        )*
+                        _ => unreachable!("element ID was already matched as a child"),
+                    },
+                ))
             }
         }
     };
@@ -429,10 +1911,10 @@ macro_rules! impl_into_reader {
     ( $States:ident, $Readers:ident, [ $( $ElementName:ident ),* ] ) => {
         impl<R: BufRead> IntoReader<R> for $States {
             type Reader = $Readers<R>;
-            fn into_reader(self, reader: R) -> Self::Reader {
+            fn into_reader(self, reader: R, position: u64) -> Self::Reader {
                 match self {
                     $(
-                        Self::$ElementName(state) => Self::Reader::$ElementName(state.into_reader(reader)),
+                        Self::$ElementName(state) => Self::Reader::$ElementName(state.into_reader(reader, position)),
                     )*
                 }
             }
@@ -447,7 +1929,7 @@ macro_rules! impl_from_readers_for_states {
             fn from(enumed_reader: $Readers<R>) -> Self {
                 match enumed_reader {
                     $(
-                        $Readers::$ElementName(reader) => Self::$ElementName(reader.state),
+                        $Readers::$ElementName(reader) => Self::$ElementName(reader.into()),
                     )*
                 }
             }
@@ -484,3 +1966,511 @@ macro_rules! impl_from_subreaders_for_readers {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::base::element_defs::Range;
+    use core::ops::Bound;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, EBML_EPOCH_UNIX_SECS, 0)]
+    #[case(1_500_000_000, EBML_EPOCH_UNIX_SECS + 1, 500_000_000)]
+    // 2021-01-01T00:00:00 UTC
+    #[case(631_152_000_000_000_000, 1_609_459_200, 0)]
+    // 1970-01-01T00:00:00 UTC
+    #[case(-978_307_200_000_000_000, 0, 0)]
+    fn date_system_time(#[case] date: i64, #[case] unix_secs: u64, #[case] subsec_nanos: u32) {
+        let time = UNIX_EPOCH + Duration::new(unix_secs, subsec_nanos);
+        assert_eq!(date_to_system_time(date), time);
+        assert_eq!(system_time_to_date(time), Some(date));
+    }
+
+    #[test]
+    fn system_time_to_date_out_of_range() {
+        let time = UNIX_EPOCH + Duration::from_secs(EBML_EPOCH_UNIX_SECS + 300 * 365 * 86_400);
+        assert_eq!(system_time_to_date(time), None);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct UnitIntervalDef;
+
+    impl ElementDef for UnitIntervalDef {
+        const NAME: &'static str = "UnitInterval";
+        const ID: u32 = 0x81;
+        const PATH: &'static str = r"\UnitInterval";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = None;
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+
+    impl FloatElementDef for UnitIntervalDef {
+        const RANGE: Range<f64> = Range::IsWithin(Bound::Excluded(0.0), Bound::Included(1.0));
+        const DEFAULT: Option<f64> = None;
+    }
+
+    #[rstest]
+    #[case(0.5, true)]
+    #[case(1.0, true)]
+    #[case(0.0, false)]
+    #[case(1.5, false)]
+    #[case(f64::NAN, false)]
+    fn read_ranged_float(#[case] value: f64, #[case] is_in_range: bool) {
+        let stream = value.to_be_bytes();
+        let state = ElementState::<UnitIntervalDef, ()> {
+            bytes_left: ElementLength::Known(stream.len() as u64),
+            parent_state: (),
+            occurrences: BTreeMap::new(),
+            crc: None,
+            _phantom: PhantomData,
+        };
+
+        let result: nom::IResult<_, ((), f64), _> = state.read(&stream[..]);
+        match result {
+            Ok((_, (_, data))) => {
+                assert!(is_in_range);
+                assert_eq!(data, value);
+            }
+            Err(nom::Err::Failure(StateError::OutOfRange(id))) => {
+                assert!(!is_in_range);
+                assert_eq!(id, UnitIntervalDef::ID);
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct EbmlVersionDef;
+
+    impl ElementDef for EbmlVersionDef {
+        const NAME: &'static str = "EBMLVersion";
+        const ID: u32 = 0x4286;
+        const PATH: &'static str = r"\EBML\EBMLVersion";
+
+        const MIN_OCCURS: usize = 1;
+        const MAX_OCCURS: Option<usize> = Some(1);
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+
+    impl UIntElementDef for EbmlVersionDef {
+        const RANGE: Range<u64> = Range::Excludes(0);
+        const DEFAULT: Option<u64> = Some(1);
+    }
+
+    #[rstest]
+    #[case(&[0x01], true)]
+    #[case(&[0x00], false)]
+    #[case(&[], false)] // zero-octet integers decode to 0
+    fn read_ranged_uint(#[case] stream: &[u8], #[case] is_in_range: bool) {
+        let state = ElementState::<EbmlVersionDef, ()> {
+            bytes_left: ElementLength::Known(stream.len() as u64),
+            parent_state: (),
+            occurrences: BTreeMap::new(),
+            crc: None,
+            _phantom: PhantomData,
+        };
+
+        let result: nom::IResult<_, ((), u64), _> = state.read(stream);
+        match result {
+            Ok((_, (_, data))) => {
+                assert!(is_in_range);
+                assert_eq!(data, 1);
+            }
+            Err(nom::Err::Failure(StateError::OutOfRange(id))) => {
+                assert!(!is_in_range);
+                assert_eq!(id, EbmlVersionDef::ID);
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case(&[], 1)]
+    #[case(&[0x02], 2)]
+    fn read_or_default(#[case] stream: &[u8], #[case] expt_value: u64) {
+        let mut reader = ElementReader {
+            reader: stream,
+            state: ElementState::<EbmlVersionDef, ()> {
+                bytes_left: ElementLength::Known(stream.len() as u64),
+                parent_state: (),
+                occurrences: BTreeMap::new(),
+                crc: None,
+                _phantom: PhantomData,
+            },
+            pending: Vec::new(),
+            position: 0,
+        };
+
+        let value: u64 = reader.read_or_default().unwrap();
+        assert_eq!(value, expt_value);
+    }
+
+    #[rstest]
+    #[case(&[0x02, 0xFF], 1, Ok(&[0x02][..]))]
+    #[case(&[0x00, 0x02], 2, Ok(&[0x00, 0x02][..]))]
+    #[case(&[0x02], 2, Err(ReaderError::UnexpectedEof))]
+    fn read_raw(
+        #[case] stream: &[u8],
+        #[case] len: u64,
+        #[case] expt_result: Result<&[u8], ReaderError>,
+    ) {
+        let mut reader = ElementReader {
+            reader: stream,
+            state: ElementState::<EbmlVersionDef, ()> {
+                bytes_left: ElementLength::Known(len),
+                parent_state: (),
+                occurrences: BTreeMap::new(),
+                crc: None,
+                _phantom: PhantomData,
+            },
+            pending: Vec::new(),
+            position: 0,
+        };
+
+        assert_eq!(reader.read_raw(), expt_result);
+    }
+
+    #[rstest]
+    fn default_value_absent() {
+        // e.g. an `\EBML` header that omits its `EBMLVersion` element
+        let value: Option<u64> = ElementState::<EbmlVersionDef, ()>::default_value();
+        assert_eq!(value, Some(1));
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Crc32Def;
+
+    impl ElementDef for Crc32Def {
+        const NAME: &'static str = "CRC-32";
+        const ID: u32 = 0xBF;
+        const PATH: &'static str = r"\(1-\)CRC-32";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = Some(1);
+        const LENGTH: Range<usize> = Range::IsExactly(4);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+
+    #[rstest]
+    #[case(ElementLength::Known(4), true)]
+    #[case(ElementLength::Known(5), false)]
+    #[case(ElementLength::Known(0), false)]
+    #[case(ElementLength::Unknown, true)]
+    fn check_crc32_length(#[case] len: ElementLength, #[case] is_in_range: bool) {
+        match check_length::<Crc32Def>(len) {
+            Ok(checked_len) => {
+                assert!(is_in_range);
+                assert_eq!(checked_len, len);
+            }
+            Err(nom::Err::Failure(StateError::LengthOutOfRange { id, len: bad_len })) => {
+                assert!(!is_in_range);
+                assert_eq!(id, Crc32Def::ID);
+                assert_eq!(ElementLength::Known(bad_len), len);
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    fn skip_oversized_element() {
+        let len = Some(u64::from(u32::MAX) + 1);
+        // simulates a target w/ a 32-bit `usize`
+        assert_eq!(
+            skip_unknown_element_as::<u32>(&[0x00; 4], 0x81, len),
+            Err(nom::Err::Failure(StateError::SkipTooLarge {
+                id: 0x81,
+                len: 1 << 32,
+                stream_left: 4,
+            }))
+        );
+        #[cfg(target_pointer_width = "64")]
+        assert!(matches!(
+            skip_unknown_element(&[0x00; 4], 0x81, len),
+            Err(nom::Err::Incomplete(_))
+        ));
+    }
+
+    // `len` octets of data, w/o holding them in memory
+    struct Unbuffered {
+        len: u64,
+    }
+
+    impl std::io::Read for Unbuffered {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let amt = usize::try_from(self.len).map_or(buf.len(), |len| len.min(buf.len()));
+            self.len -= amt as u64;
+            Ok(amt)
+        }
+    }
+
+    #[rstest]
+    fn skip_oversized_element_buffered() {
+        let len = u64::from(u32::MAX) + 1;
+        let header = [
+            0x81, // element ID
+            0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // element length = 2^32
+        ];
+        let next_element = [
+            0x82, // element ID
+            0x80, // element length = 0
+        ];
+        let mut reader = std::io::BufReader::with_capacity(
+            1 << 20,
+            std::io::Read::chain(
+                std::io::Read::chain(&header[..], Unbuffered { len }),
+                &next_element[..],
+            ),
+        );
+        let mut pending = Vec::new();
+        let mut position = 0;
+
+        // skips element 0x81 (simulating a target w/ a 32-bit `usize`) -> the parse is restarted
+        // after it, from element 0x82
+        let id = parse_buffered(&mut reader, &mut pending, &mut position, |stream| {
+            let (stream, id) = parse::element_id(stream).map_err(nom::Err::convert)?;
+            let (stream, len) = parse::element_len(stream).map_err(nom::Err::convert)?;
+            match id {
+                0x81 => skip_unknown_element_as::<u32>(stream, id, len).map(|(s, _)| (s, id)),
+                id => Ok((stream, id)),
+            }
+        })
+        .unwrap();
+        assert_eq!(id, 0x82);
+        assert_eq!(position, (header.len() + next_element.len()) as u64 + len);
+    }
+
+    #[rstest]
+    #[case(ElementLength::Known(7), Some(4), Ok(ElementLength::Known(1)))]
+    #[case(ElementLength::Known(6), Some(4), Ok(ElementLength::Known(0)))]
+    #[case(ElementLength::Unknown, Some(u64::MAX), Ok(ElementLength::Unknown))]
+    #[case(
+        ElementLength::Known(5),
+        Some(4),
+        Err(StateError::ExceedsParent { parent_id: EbmlDef::ID, id: 0x81, len: 4 })
+    )]
+    #[case(
+        ElementLength::Known(5),
+        Some(u64::MAX),
+        Err(StateError::ExceedsParent { parent_id: EbmlDef::ID, id: 0x81, len: u64::MAX })
+    )]
+    #[case(ElementLength::Known(5), None, Err(StateError::UnknownSize(0x81)))]
+    fn deduct_child(
+        #[case] bytes_left: ElementLength,
+        #[case] len: Option<u64>,
+        #[case] expt_result: Result<ElementLength, StateError>,
+    ) {
+        let mut state = ElementState::<EbmlDef, ()> {
+            bytes_left,
+            parent_state: (),
+            occurrences: BTreeMap::new(),
+            crc: None,
+            _phantom: PhantomData,
+        };
+
+        // a child w/ a 2-octet header
+        let result = state
+            .deduct_child(0x81, len, 2)
+            .map(|_| state.bytes_left)
+            .map_err(|error| match error {
+                nom::Err::Failure(error) => error,
+                error => panic!("unexpected error: {:?}", error),
+            });
+        assert_eq!(result, expt_result);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct EbmlDef;
+
+    impl ElementDef for EbmlDef {
+        const NAME: &'static str = "EBML";
+        const ID: u32 = 0x1A45DFA3;
+        const PATH: &'static str = r"\EBML";
+
+        const MIN_OCCURS: usize = 1;
+        const MAX_OCCURS: Option<usize> = Some(1);
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+
+    impl MasterElementDef for EbmlDef {
+        const UNKNOWN_SIZE_ALLOWED: bool = false;
+        const RECURSIVE: bool = false;
+        const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[(EbmlVersionDef::ID, 1)];
+    }
+
+    #[rstest]
+    #[case(0, Some(0))]
+    #[case(1, None)]
+    #[case(2, Some(2))]
+    fn check_occurrences(#[case] count: usize, #[case] expt_violation: Option<usize>) {
+        let mut state = ElementState::<EbmlDef, ()> {
+            bytes_left: ElementLength::Known(0),
+            parent_state: (),
+            occurrences: BTreeMap::new(),
+            crc: None,
+            _phantom: PhantomData,
+        };
+
+        let result = (0..count)
+            .try_for_each(|_| state.count_occurrence::<EbmlVersionDef>())
+            .and_then(|_| state.check_mandatory_children());
+        match (result, expt_violation) {
+            (Ok(()), None) => {}
+            (
+                Err(nom::Err::Failure(StateError::OccurrenceViolation {
+                    parent_id,
+                    child_id,
+                    count,
+                })),
+                Some(expt_count),
+            ) => {
+                assert_eq!(parent_id, EbmlDef::ID);
+                assert_eq!(child_id, EbmlVersionDef::ID);
+                assert_eq!(count, expt_count);
+            }
+            (result, _) => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct VersionedDef;
+
+    impl ElementDef for VersionedDef {
+        const NAME: &'static str = "Versioned";
+        const ID: u32 = 0x4287;
+        const PATH: &'static str = r"\EBML\Versioned";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = None;
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 2;
+        const MAX_VERSION: Option<u64> = Some(3);
+    }
+
+    #[rstest]
+    #[case(None, true)]
+    #[case(Some(1), false)]
+    #[case(Some(2), true)]
+    #[case(Some(3), true)]
+    #[case(Some(4), false)]
+    fn check_versioned(#[case] doc_type_version: Option<u64>, #[case] is_supported: bool) {
+        let config = DocumentConfig {
+            doc_type_version,
+            ..DocumentConfig::default()
+        };
+
+        match check_version::<VersionedDef>(&config) {
+            Ok(()) => assert!(is_supported),
+            Err(nom::Err::Failure(StateError::VersionUnsupported { id, version })) => {
+                assert!(!is_supported);
+                assert_eq!(id, VersionedDef::ID);
+                assert_eq!(Some(version), doc_type_version);
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[rstest]
+    #[case("", r"\EBML", "EBML", true)]
+    #[case(r"\EBML", r"\EBML\Versioned", "Versioned", true)]
+    #[case("", r"\EBML\Versioned", "Versioned", false)]
+    #[case(r"\EBML\Versioned", r"\EBML\Versioned", "Versioned", true)]
+    #[case(r"\UnitInterval", r"\EBML\Versioned", "Versioned", false)]
+    #[case(r"\EBMLVersion", r"\EBML\Version", "Version", false)]
+    #[case("", r"\(1-\)CRC-32", "CRC-32", false)]
+    #[case(r"\EBML", r"\(1-\)CRC-32", "CRC-32", true)]
+    #[case(r"\EBML\Versioned", r"\(1-\)CRC-32", "CRC-32", true)]
+    #[case("", r"\(-\)Void", "Void", true)]
+    #[case(r"\EBML\Versioned", r"\(-1\)Void", "Void", false)]
+    #[case(r"\EBML", r"\EBML\(1-\)Global", "Global", false)]
+    #[case(r"\EBML\Versioned", r"\EBML\(1-\)Global", "Global", true)]
+    #[case(r"\UnitInterval\Versioned", r"\EBML\(1-\)Global", "Global", false)]
+    fn child_paths(
+        #[case] parent_path: &str,
+        #[case] path: &str,
+        #[case] name: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(is_child_path(parent_path, path, name), expected);
+    }
+
+    #[test]
+    fn check_paths() {
+        check_path::<EbmlDef>("");
+        check_path::<EbmlVersionDef>(EbmlDef::PATH);
+        check_path::<Crc32Def>(EbmlDef::PATH);
+    }
+
+    #[cfg(feature = "check-paths")]
+    #[test]
+    #[should_panic(expected = "is not a child of")]
+    fn check_miswired_path() {
+        check_path::<EbmlVersionDef>(UnitIntervalDef::PATH);
+    }
+
+    #[test]
+    fn state_error_eq() {
+        assert_eq!(
+            StateError::InvalidChildId(Some(0x1A45DFA3), 0x4286),
+            StateError::InvalidChildId(Some(0x1A45DFA3), 0x4286),
+        );
+        assert_ne!(
+            StateError::InvalidChildId(Some(0x1A45DFA3), 0x4286),
+            StateError::InvalidChildId(None, 0x4286),
+        );
+    }
+
+    #[rstest]
+    #[case(
+        ReaderError::Io(std::io::ErrorKind::UnexpectedEof.into()),
+        ReaderError::Io(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "eof")),
+        true
+    )]
+    #[case(
+        ReaderError::Io(std::io::ErrorKind::UnexpectedEof.into()),
+        ReaderError::Io(std::io::ErrorKind::InvalidData.into()),
+        false
+    )]
+    #[case(
+        ReaderError::Parse(nom::Err::Failure(StateError::UnknownSize(0x81)), 3),
+        ReaderError::Parse(nom::Err::Failure(StateError::UnknownSize(0x81)), 3),
+        true
+    )]
+    #[case(
+        ReaderError::Parse(nom::Err::Failure(StateError::UnknownSize(0x81)), 3),
+        ReaderError::Parse(nom::Err::Failure(StateError::UnknownSize(0x81)), 4),
+        false
+    )]
+    #[case(
+        ReaderError::Parse(nom::Err::Failure(StateError::UnknownSize(0x81)), 3),
+        ReaderError::Parse(nom::Err::Error(StateError::UnknownSize(0x81)), 3),
+        false
+    )]
+    #[case(ReaderError::UnexpectedEof, ReaderError::UnexpectedEof, true)]
+    #[case(
+        ReaderError::UnexpectedEof,
+        ReaderError::UnsupportedEbmlVersion(2),
+        false
+    )]
+    fn reader_error_eq(
+        #[case] error1: ReaderError,
+        #[case] error2: ReaderError,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(error1 == error2, expected);
+    }
+}
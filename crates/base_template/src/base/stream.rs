@@ -1,4 +1,4 @@
-use std::convert::TryInto;
+use core::convert::TryInto;
 
 pub fn stream_diff<'a>(first: &'a [u8], second: &'a [u8]) -> usize {
     unsafe { second.as_ptr().offset_from(first.as_ptr()) }
@@ -7,16 +7,77 @@ pub fn stream_diff<'a>(first: &'a [u8], second: &'a [u8]) -> usize {
 }
 
 pub mod parse {
-    use std::cmp::min;
-    use std::mem::size_of;
-    use std::ops::RangeFrom;
+    use core::cmp::min;
+    use core::convert::TryInto;
+    use core::mem::size_of;
+    use core::ops::RangeFrom;
 
     use nom::{
-        bits::streaming::take as take_bits, bytes::streaming::take as take_bytes,
-        error::ParseError, Err, IResult, InputIter, InputLength, Needed, Slice, ToUsize,
+        bits::streaming::take as take_bits,
+        bytes::streaming::take as take_bytes,
+        error::{ErrorKind, ParseError as NomParseError},
+        Err, IResult, InputIter, InputLength, Needed, Slice, ToUsize,
     };
 
-    fn take_rem<I, E: ParseError<(I, usize)>>(
+    // why a value couldn't be parsed
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ParseError {
+        // a VINT w/ more octets than allowed, e.g. by a document's `EBMLMaxIDLength`
+        VintTooLong,
+        // an element ID whose VINT_DATA is all 0's or all 1's
+        ReservedId,
+        // an element ID that isn't encoded w/ as few octets as possible
+        NonMinimalId,
+        // an element length that isn't encoded w/ as few octets as possible (w/ strict lengths)
+        NonMinimalLength,
+        // a data length that the value's type can't have
+        InvalidLength(usize),
+        // an element length that's unknown where the data has to be read as a whole
+        UnknownSize,
+        // an element length too large to address in memory
+        TooLarge(u64),
+        // a different element than expected, e.g. anything but `\EBML` at the start of a document
+        UnexpectedId(u32),
+        // an enumerated value w/o a label
+        UnknownEnumValue(u64),
+        InvalidAscii,
+        InvalidUtf8,
+        // the data ended in the middle of a value, where no more data could follow
+        UnexpectedEof,
+    }
+
+    impl core::fmt::Display for ParseError {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self {
+                Self::VintTooLong => write!(f, "VINT longer than allowed"),
+                Self::ReservedId => write!(f, "reserved element ID"),
+                Self::NonMinimalId => write!(f, "element ID not in its shortest encoding"),
+                Self::NonMinimalLength => write!(f, "element length not in its shortest encoding"),
+                Self::InvalidLength(len) => write!(f, "invalid data length {}", len),
+                Self::UnknownSize => write!(f, "unknown element length"),
+                Self::TooLarge(len) => write!(f, "element length {} too large", len),
+                Self::UnexpectedId(id) => write!(f, "unexpected element ID {:#x}", id),
+                Self::UnknownEnumValue(value) => write!(f, "no label for enum value {}", value),
+                Self::InvalidAscii => write!(f, "invalid ASCII string"),
+                Self::InvalidUtf8 => write!(f, "invalid UTF-8 string"),
+                Self::UnexpectedEof => write!(f, "unexpected end of data"),
+            }
+        }
+    }
+
+    // `nom`'s own parsers only fail on data that's complete but too short
+    impl<I> NomParseError<I> for ParseError {
+        fn from_error_kind(_input: I, _kind: ErrorKind) -> Self {
+            Self::UnexpectedEof
+        }
+
+        fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+            other
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn take_rem<I, E: NomParseError<(I, usize)>>(
     ) -> impl Fn((I, usize)) -> IResult<(I, usize), (u8, usize), E>
     where
         I: Slice<RangeFrom<usize>> + InputIter<Item = u8> + InputLength,
@@ -28,7 +89,7 @@ pub mod parse {
         }
     }
 
-    fn take_zeros<I, C, E: ParseError<(I, usize)>>(
+    fn take_zeros<I, C, E: NomParseError<(I, usize)>>(
         max_count: C,
     ) -> impl Fn((I, usize)) -> IResult<(I, usize), usize, E>
     where
@@ -66,99 +127,127 @@ pub mod parse {
         }
     }
 
-    macro_rules! make_vlen_parser {
-        ($func_name:ident, $uint:ty) => {
-            fn $func_name(input: &[u8]) -> IResult<&[u8], ($uint, usize), ()> {
-                // Parse length from stream
-                let ((input, bit_offset), len) = take_zeros(size_of::<$uint>())((input, 0))?;
-                if len >= size_of::<$uint>() {
-                    return Err(nom::Err::Error(()));
-                }
-                let ((input, bit_offset), _) =
-                    take_bits::<_, usize, _, ()>(1u8)((input, bit_offset))?;
-                let ((input, _), (leftover_bits, _)) = take_rem()((input, bit_offset))?;
-                let (input, bytes) = take_bytes(len)(input)?;
+    // Parses a VINT of at most `max_octets` octets (up to 8), as bounded by e.g. a document's
+    // `EBMLMaxIDLength` & `EBMLMaxSizeLength`; yields the VINT's data along with its octet length.
+    pub fn vint(input: &[u8], max_octets: usize) -> IResult<&[u8], (u64, usize), ParseError> {
+        let max_octets = min(max_octets, size_of::<u64>());
 
-                let mut buffer = [0u8; size_of::<$uint>()];
-                buffer[size_of::<$uint>() - len - 1] = leftover_bits;
-                buffer[(size_of::<$uint>() - len)..].copy_from_slice(bytes);
+        // Parse length from stream
+        let ((input, bit_offset), len) = take_zeros(max_octets)((input, 0))?;
+        if len >= max_octets {
+            return Err(nom::Err::Error(ParseError::VintTooLong));
+        }
+        let ((input, bit_offset), _) =
+            take_bits::<_, usize, _, ParseError>(1u8)((input, bit_offset))?;
+        let ((input, _), (leftover_bits, _)) = take_rem()((input, bit_offset))?;
+        let (input, bytes) = take_bytes(len)(input)?;
 
-                Ok((input, (<$uint>::from_be_bytes(buffer), len + 1)))
-            }
-        };
-    }
+        let mut buffer = [0u8; size_of::<u64>()];
+        buffer[size_of::<u64>() - len - 1] = leftover_bits;
+        buffer[(size_of::<u64>() - len)..].copy_from_slice(bytes);
 
-    make_vlen_parser!(vlen_to_u32, u32);
-    make_vlen_parser!(vlen_to_u64, u64);
+        Ok((input, (u64::from_be_bytes(buffer), len + 1)))
+    }
 
-    pub fn element_id(input: &[u8]) -> IResult<&[u8], u32, ()> {
-        let ((_, _), bytelen_m1) = take_zeros(size_of::<u32>())((input, 0))?;
-        if bytelen_m1 == size_of::<u32>() {
-            return Err(nom::Err::Error(()));
-        }
-        let bytelen = bytelen_m1 + 1;
+    pub fn element_id(input: &[u8]) -> IResult<&[u8], u32, ParseError> {
+        element_id_bounded(input, size_of::<u32>())
+    }
 
-        let (input, bytes) = take_bytes(bytelen)(input)?;
-        let mut buffer = [0u8; size_of::<u32>()];
-        buffer[(size_of::<u32>() - bytes.len())..].copy_from_slice(bytes);
-        let result = u32::from_be_bytes(buffer);
+    // parses an element ID of at most `max_octets` octets (up to 4)
+    pub fn element_id_bounded(input: &[u8], max_octets: usize) -> IResult<&[u8], u32, ParseError> {
+        let max_octets = min(max_octets, size_of::<u32>());
+        let (input, (result_data, bytelen)) = vint(input, max_octets)?;
 
-        let result_data = result ^ (1u32 << (7 * bytelen));
         if result_data == 0 || result_data.count_ones() == 7 * (bytelen as u32) {
             // if all non-length bits are 0's or 1's
             // corner-case: reserved ID's
-            return Err(nom::Err::Error(()));
+            return Err(nom::Err::Error(ParseError::ReservedId));
         }
-        let sig_bits = 8 * size_of::<u32>() - ((result_data + 1).leading_zeros() as usize);
-        if sig_bits <= 7 * bytelen_m1 {
+        let sig_bits = u64::BITS as usize - ((result_data + 1).leading_zeros() as usize);
+        if sig_bits <= 7 * (bytelen - 1) {
             // element ID's must use the smallest representation possible
-            return Err(nom::Err::Error(()));
+            return Err(nom::Err::Error(ParseError::NonMinimalId));
         }
 
+        // element ID's keep their VINT marker bit
+        let result = result_data | (1u64 << (7 * bytelen));
+        Ok((
+            input,
+            result.try_into().expect("4-octet VINT fits in a u32"),
+        ))
+    }
+
+    // VINT-encoded integer *values*, e.g. Matroska's lace sizes; unlike element lengths, an all-1's
+    // VINT is a regular value
+    pub fn unsigned_vint(input: &[u8]) -> IResult<&[u8], u64, ParseError> {
+        let (input, (result, _bytelen)) = vint(input, size_of::<u64>())?;
+
         Ok((input, result))
     }
 
-    pub fn element_len(input: &[u8]) -> IResult<&[u8], Option<u64>, ()> {
-        let (new_input, (result, bytelen_m1)) = vlen_to_u64(input)?;
+    // signed VINT values are shifted s.t. the range of an n-byte VINT is centered on 0,
+    // i.e. `value = unsigned_value - (2^(7n-1) - 1)`
+    pub fn signed_vint(input: &[u8]) -> IResult<&[u8], i64, ParseError> {
+        let (input, (result, bytelen)) = vint(input, size_of::<u64>())?;
+        let offset = (1i64 << (7 * bytelen - 1)) - 1;
+
+        Ok((input, result as i64 - offset))
+    }
+
+    pub fn element_len(input: &[u8]) -> IResult<&[u8], Option<u64>, ParseError> {
+        element_len_bounded(input, size_of::<u64>(), false)
+    }
 
-        Ok(if result.count_ones() == 7 * (bytelen_m1 as u32) {
+    // parses an element length of at most `max_octets` octets (up to 8); `strict_lengths` rejects
+    // lengths that are encoded w/ more octets than their value needs
+    pub fn element_len_bounded(
+        input: &[u8],
+        max_octets: usize,
+        strict_lengths: bool,
+    ) -> IResult<&[u8], Option<u64>, ParseError> {
+        let (new_input, (result, bytelen)) = vint(input, max_octets)?;
+
+        if result.count_ones() == 7 * (bytelen as u32) {
             // if all non-length bits are 1's
             // corner-case: reserved ID's
-            (new_input, None)
-        } else {
-            (new_input, Some(result))
-        })
+            return Ok((new_input, None));
+        }
+        // v the all-1's value of one octet fewer is reserved for unknown lengths
+        if strict_lengths && bytelen > 1 && result < (1u64 << (7 * (bytelen - 1))) - 1 {
+            return Err(nom::Err::Error(ParseError::NonMinimalLength));
+        }
+        Ok((new_input, Some(result)))
     }
 
-    fn parse_length<'a>(input: &'a [u8], buffer: &mut [u8]) -> IResult<&'a [u8], (), ()> {
+    fn parse_length<'a>(input: &'a [u8], buffer: &mut [u8]) -> IResult<&'a [u8], (), ParseError> {
         let (input, bytes) = take_bytes(buffer.len())(input)?;
         buffer.copy_from_slice(bytes);
 
         Ok((input, ()))
     }
 
-    pub fn uint(input: &[u8], length: usize) -> IResult<&[u8], u64, ()> {
-        assert!(
-            length <= size_of::<u64>(),
-            "invalid length for uint (expected n<{:?}, found {:?})",
-            size_of::<u64>(),
-            length,
-        );
-
+    // EBML integers are big-endian, & may use any length from 0 (-> value = 0) to 8 bytes
+    pub fn uint(input: &[u8], length: usize) -> IResult<&[u8], u64, ParseError> {
+        let i0 = size_of::<u64>()
+            .checked_sub(length)
+            .ok_or(nom::Err::Error(ParseError::InvalidLength(length)))?;
+        if length == 0 {
+            return Ok((input, 0));
+        }
         let mut buffer = [0u8; size_of::<u64>()];
-        let i0 = size_of::<i64>() - length;
         let (input, _) = parse_length(input, &mut buffer[i0..])?;
 
         Ok((input, u64::from_be_bytes(buffer)))
     }
 
-    pub fn int(input: &[u8], length: usize) -> IResult<&[u8], i64, ()> {
-        assert!(
-            length <= size_of::<i64>(),
-            "invalid length for int (expected n<{:?}, found {:?})",
-            size_of::<i64>(),
-            length,
-        );
+    pub fn int(input: &[u8], length: usize) -> IResult<&[u8], i64, ParseError> {
+        let i0 = size_of::<i64>()
+            .checked_sub(length)
+            .ok_or(nom::Err::Error(ParseError::InvalidLength(length)))?;
+        if length == 0 {
+            // don't peek at the sign bit of the next element's data
+            return Ok((input, 0));
+        }
 
         let buffer_fill: u8 = match take_bits(1usize)((input, 0))? {
             ((_, 1), 0) => 0x00,
@@ -166,19 +255,31 @@ pub mod parse {
             _ => unreachable!(),
         };
         let mut buffer = [buffer_fill; size_of::<i64>()];
-        let i0 = size_of::<i64>() - length;
         let (input, _) = parse_length(input, &mut buffer[i0..])?;
 
         Ok((input, i64::from_be_bytes(buffer)))
     }
 
-    pub fn float32(input: &[u8], length: usize) -> IResult<&[u8], f32, ()> {
-        assert!(
-            length == size_of::<f32>(),
-            "invalid length for f32 (expected {:?}, found {:?})",
-            size_of::<f32>(),
-            length,
-        );
+    pub fn enum_label<'a, 'b>(
+        input: &'a [u8],
+        length: usize,
+        enums: &[(&'b str, u64)],
+    ) -> IResult<&'a [u8], &'b str, ParseError> {
+        let (input, value) = uint(input, length)?;
+        let (label, _) = enums
+            .iter()
+            .find(|(_, enum_value)| *enum_value == value)
+            .ok_or(nom::Err::Error(ParseError::UnknownEnumValue(value)))?;
+
+        Ok((input, label))
+    }
+
+    pub fn float32(input: &[u8], length: usize) -> IResult<&[u8], f32, ParseError> {
+        match length {
+            0 => return Ok((input, 0.0)),
+            4 => {}
+            _ => return Err(nom::Err::Error(ParseError::InvalidLength(length))),
+        }
 
         let mut buffer = [0u8; size_of::<f32>()];
         let (input, _) = parse_length(input, &mut buffer)?;
@@ -186,13 +287,13 @@ pub mod parse {
         Ok((input, f32::from_be_bytes(buffer)))
     }
 
-    pub fn float64(input: &[u8], length: usize) -> IResult<&[u8], f64, ()> {
-        assert!(
-            length == size_of::<f64>(),
-            "invalid length for f64 (expected {:?}, found {:?})",
-            size_of::<f64>(),
-            length,
-        );
+    pub fn float64(input: &[u8], length: usize) -> IResult<&[u8], f64, ParseError> {
+        match length {
+            0 => return Ok((input, 0.0)),
+            4 => return float32(input, length).map(|(input, value)| (input, value.into())),
+            8 => {}
+            _ => return Err(nom::Err::Error(ParseError::InvalidLength(length))),
+        }
 
         let mut buffer = [0u8; size_of::<f64>()];
         let (input, _) = parse_length(input, &mut buffer)?;
@@ -200,7 +301,7 @@ pub mod parse {
         Ok((input, f64::from_be_bytes(buffer)))
     }
 
-    pub fn ascii_str(input: &[u8], length: usize) -> IResult<&[u8], &str, ()> {
+    pub fn ascii_str(input: &[u8], length: usize) -> IResult<&[u8], &str, ParseError> {
         let (input, bytes) = take_bytes(length)(input)?;
 
         // Need to step through each character to find any null-bytes
@@ -214,22 +315,24 @@ pub mod parse {
                     // Terminate on null-bytes
                     Some((i, 0x00)) => break i,
                     // Error on non-ASCII
-                    Some((_, byte)) if !byte.is_ascii() => Err(nom::Err::Error(())),
+                    Some((_, byte)) if !byte.is_ascii() => {
+                        Err(nom::Err::Error(ParseError::InvalidAscii))
+                    }
                     // Ignore valid ASCII
                     _ => Ok(()),
                 }?;
             }
         };
-        let result = std::str::from_utf8(&bytes[..valid_len]).unwrap(); // guaranteed to be valid in prior loop
+        let result = core::str::from_utf8(&bytes[..valid_len]).unwrap(); // guaranteed to be valid in prior loop
 
         Ok((input, result))
     }
 
-    pub fn unicode_str(input: &[u8], length: usize) -> IResult<&[u8], &str, ()> {
+    pub fn unicode_str(input: &[u8], length: usize) -> IResult<&[u8], &str, ParseError> {
         let (input, bytes) = take_bytes(length)(input)?;
 
         // Need to step through each character to find any null-bytes
-        // cannot simply use `std::str::from_utf8` because:
+        // cannot simply use `core::str::from_utf8` because:
         // - trailing bytes may be invalid -> function would error on otherwise good string
         // - null-bytes may exist mid-character -> would incorrectly split string in middle
         let valid_len = {
@@ -244,36 +347,42 @@ pub mod parse {
                     // Check byte length of character
                     let leading_1s = first_byte.leading_ones() as usize;
                     if (leading_1s >= 5) || leading_1s == 1 {
-                        return Err(nom::Err::Error(()));
+                        return Err(nom::Err::Error(ParseError::InvalidUtf8));
                     }
                     // Validate bytes in character width
                     for _ in 0..leading_1s.saturating_sub(1) {
                         iter.next()
                             .filter(|(_i, x)| x.leading_ones() == 1)
-                            .ok_or(nom::Err::Error(()))?;
+                            .ok_or(nom::Err::Error(ParseError::InvalidUtf8))?;
                     }
                 } else {
                     break length;
                 }
             }
         };
-        let result = std::str::from_utf8(&bytes[..valid_len]).unwrap(); // guaranteed to be valid in prior loop
+        let result = core::str::from_utf8(&bytes[..valid_len]).unwrap(); // guaranteed to be valid in prior loop
 
         Ok((input, result))
     }
 
-    pub fn date(input: &[u8], length: usize) -> IResult<&[u8], i64, ()> {
-        assert!(
-            length == size_of::<i64>(),
-            "invalid length for timestamp (expected {:?}, found {:?})",
-            size_of::<i64>(),
-            length,
-        );
+    // reads the exact field contents, including any null-padding
+    pub fn unicode_str_raw(input: &[u8], length: usize) -> IResult<&[u8], &str, ParseError> {
+        let (input, bytes) = take_bytes(length)(input)?;
+        let result =
+            core::str::from_utf8(bytes).map_err(|_| nom::Err::Error(ParseError::InvalidUtf8))?;
+
+        Ok((input, result))
+    }
+
+    pub fn date(input: &[u8], length: usize) -> IResult<&[u8], i64, ParseError> {
+        if length != 0 && length != size_of::<i64>() {
+            return Err(nom::Err::Error(ParseError::InvalidLength(length)));
+        }
 
         int(input, length)
     }
 
-    pub fn binary(input: &[u8], length: usize) -> IResult<&[u8], &[u8], ()> {
+    pub fn binary(input: &[u8], length: usize) -> IResult<&[u8], &[u8], ParseError> {
         take_bytes(length)(input)
     }
 
@@ -329,29 +438,114 @@ pub mod parse {
         #[rstest(source, expt_result,
             case(&[0x40, 0x7F, 0xFF], (&source[2..], 0x407F)),
             case(&[0xDF, 0xFF], (&source[1..], 0xDF)),
+            case(&[0x2A, 0xD7, 0xB1], (&source[3..], 0x2AD7B1)), // TimestampScale
+            case(&[0x1A, 0x45, 0xDF, 0xA3, 0x9F], (&source[4..], 0x1A45DFA3)), // EBML
+            case(&[0x18, 0x53, 0x80, 0x67], (&source[4..], 0x18538067)), // Segment
+            case(&[0x15, 0x49, 0xA9, 0x66], (&source[4..], 0x1549A966)), // Info
+            // all but one of the data bits are 1's -> not reserved
+            case(&[0x1F, 0xFF, 0xFF, 0xFE], (&source[4..], 0x1FFFFFFE)),
+            case(&[0x3F, 0xFF, 0xFE], (&source[3..], 0x3FFFFE)),
+            case(&[0x10, 0x20, 0x00, 0x00], (&source[4..], 0x10200000)),
         )]
         fn test_element_id(source: &'static [u8], expt_result: (&'static [u8], u32)) {
             assert_eq!(element_id(source), Ok(expt_result));
         }
 
-        #[rstest(source,
-            case(&[0x80]),
-            case(&[0xFF]),
-            case(&[0x40, 0x7E]),
-            case(&[0x7F, 0xFF]),
-            case(&[0x20, 0x3F, 0xFE]),
-            case(&[0x3F, 0xFF, 0xFF]),
-            case(&[0x10, 0x1F, 0xFF, 0xFE]),
-            case(&[0x1F, 0xFF, 0xFF, 0xFF]),
+        #[rstest(source, expt_error,
+            case(&[0x80], ParseError::ReservedId),
+            case(&[0xFF], ParseError::ReservedId),
+            case(&[0x40, 0x7E], ParseError::NonMinimalId),
+            case(&[0x7F, 0xFF], ParseError::ReservedId),
+            case(&[0x20, 0x3F, 0xFE], ParseError::NonMinimalId),
+            case(&[0x3F, 0xFF, 0xFF], ParseError::ReservedId),
+            case(&[0x10, 0x1F, 0xFF, 0xFE], ParseError::NonMinimalId),
+            case(&[0x1F, 0xFF, 0xFF, 0xFF], ParseError::ReservedId),
+            case(&[0x08, 0x10, 0x00, 0x00, 0x00], ParseError::VintTooLong),
         )]
-        fn test_element_id_err(source: &'static [u8]) {
-            assert_eq!(element_id(source), Err(nom::Err::Error(())));
+        fn test_element_id_err(source: &'static [u8], expt_error: ParseError) {
+            assert_eq!(element_id(source), Err(nom::Err::Error(expt_error)));
         }
 
-        #[test]
-        fn test_element_len() {
-            let source = [0x40, 0x01, 0xFF];
-            assert_eq!(element_len(&source[..]), Ok((&source[2..], Some(1))));
+        #[rstest]
+        #[case(&[0x40, 0x01, 0xFF], Some(1), 2)]
+        #[case(&[0x81], Some(1), 1)]
+        #[case(&[0x7F, 0xFE], Some(0x3FFE), 2)]
+        // all-ones lengths are unknown, at any width
+        #[case(&[0xFF], None, 1)]
+        #[case(&[0x7F, 0xFF], None, 2)]
+        #[case(&[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], None, 8)]
+        fn test_element_len(
+            #[case] source: &'static [u8],
+            #[case] expt_len: Option<u64>,
+            #[case] bytelen: usize,
+        ) {
+            assert_eq!(element_len(source), Ok((&source[bytelen..], expt_len)));
+        }
+
+        #[rstest]
+        #[case(&[0x81], false, Ok(Some(1)))]
+        #[case(&[0x40, 0x01], false, Ok(Some(1)))]
+        #[case(&[0x81], true, Ok(Some(1)))]
+        #[case(&[0x40, 0x01], true, Err(nom::Err::Error(ParseError::NonMinimalLength)))]
+        #[case(&[0x10, 0x00, 0x00, 0x01], true, Err(nom::Err::Error(ParseError::NonMinimalLength)))]
+        // 0x7F needs 2 octets, since `0xFF` is an unknown length
+        #[case(&[0x40, 0x7F], true, Ok(Some(0x7F)))]
+        #[case(&[0x40, 0x7E], true, Err(nom::Err::Error(ParseError::NonMinimalLength)))]
+        #[case(&[0x7F, 0xFF], true, Ok(None))]
+        fn test_element_len_strict(
+            #[case] source: &'static [u8],
+            #[case] strict_lengths: bool,
+            #[case] expt_result: Result<Option<u64>, nom::Err<ParseError>>,
+        ) {
+            assert_eq!(
+                element_len_bounded(source, 8, strict_lengths).map(|(_, len)| len),
+                expt_result
+            );
+        }
+
+        #[rstest]
+        #[case(&[0x81], 4, Ok((1, 1)))]
+        #[case(&[0x40, 0x02], 4, Ok((2, 2)))]
+        #[case(&[0x10, 0x00, 0x00, 0x01], 4, Ok((1, 4)))]
+        #[case(&[0x08, 0x00, 0x00, 0x00, 0x01], 4, Err(nom::Err::Error(ParseError::VintTooLong)))]
+        #[case(&[0x08, 0x00, 0x00, 0x00, 0x01], 8, Ok((1, 5)))]
+        #[case(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], 8, Ok((1, 8)))]
+        #[case(&[0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], 8, Err(nom::Err::Error(ParseError::VintTooLong)))]
+        #[case(&[0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], 9, Err(nom::Err::Error(ParseError::VintTooLong)))]
+        #[case(&[0x81], 0, Err(nom::Err::Error(ParseError::VintTooLong)))]
+        fn test_vint(
+            #[case] source: &'static [u8],
+            #[case] max_octets: usize,
+            #[case] expt_result: Result<(u64, usize), nom::Err<ParseError>>,
+        ) {
+            assert_eq!(
+                vint(source, max_octets),
+                expt_result.map(|value| (&[][..], value))
+            );
+        }
+
+        #[rstest]
+        #[case(&[0x81], 1)]
+        #[case(&[0xFF], 0x7F)]
+        #[case(&[0x40, 0x02], 2)]
+        #[case(&[0x7F, 0xFF], 0x3FFF)]
+        #[case(&[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 0xFF_FFFF_FFFF_FFFF)]
+        fn test_unsigned_vint(#[case] source: &'static [u8], #[case] expt_value: u64) {
+            assert_eq!(unsigned_vint(source), Ok((&[][..], expt_value)));
+        }
+
+        // see the Matroska spec's "EBML Lacing" examples
+        #[rstest]
+        #[case(&[0xBF], 0)]
+        #[case(&[0x80], -63)]
+        #[case(&[0xFE], 63)]
+        #[case(&[0x5F, 0xFF], 0)]
+        #[case(&[0x40, 0x00], -8191)]
+        #[case(&[0x5F, 0xFE], -1)]
+        #[case(&[0x60, 0x00], 1)]
+        #[case(&[0x2F, 0xFF, 0xFF], 0)]
+        fn test_signed_vint(#[case] source: &'static [u8], #[case] expt_value: i64) {
+            assert_eq!(signed_vint(source), Ok((&[][..], expt_value)));
         }
 
         #[test]
@@ -369,6 +563,120 @@ pub mod parse {
             );
         }
 
+        #[rstest]
+        #[case(0, 0)]
+        #[case(1, 0x01)]
+        #[case(2, 0x0102)]
+        #[case(3, 0x010203)]
+        #[case(4, 0x01020304)]
+        #[case(5, 0x0102030405)]
+        #[case(6, 0x010203040506)]
+        #[case(7, 0x01020304050607)]
+        #[case(8, 0x0102030405060708)]
+        fn test_uint_lengths(#[case] length: usize, #[case] expt_value: u64) {
+            let source = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xFF];
+            assert_eq!(
+                uint(&source[..], length),
+                Ok((&source[length..], expt_value))
+            );
+        }
+
+        #[rstest]
+        #[case(&[0x80, 0x00], 0, 0)]
+        #[case(&[0x7F, 0x00], 1, 127)]
+        #[case(&[0x80, 0x00], 1, -128)]
+        #[case(&[0xFF, 0xFE], 2, -2)]
+        // the sign is extended, w/o clearing the high bit of the leading byte
+        #[case(&[0xFF, 0x01], 2, -255)]
+        #[case(&[0x00, 0xFF, 0xFE], 3, 0xFFFE)]
+        #[case(&[0xFF, 0x00, 0x00, 0x00], 4, -0x0100_0000)]
+        #[case(&[0x80, 0x00, 0x00, 0x00, 0x00], 5, -0x80_0000_0000)]
+        #[case(&[0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 6, 0x7FFF_FFFF_FFFF)]
+        #[case(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 7, -1)]
+        #[case(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], 8, i64::MIN)]
+        #[case(&[0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 8, i64::MAX)]
+        fn test_int_lengths(
+            #[case] source: &'static [u8],
+            #[case] length: usize,
+            #[case] expt_value: i64,
+        ) {
+            assert_eq!(int(source, length), Ok((&source[length..], expt_value)));
+        }
+
+        #[test]
+        fn test_int_big_endian() {
+            // EBML integers store their most significant byte first
+            let source = [0x01, 0x00];
+            assert_eq!(uint(&source[..], 2), Ok((&source[2..], 0x0100)));
+            assert_eq!(int(&source[..], 2), Ok((&source[2..], 0x0100)));
+        }
+
+        #[test]
+        fn test_int_invalid_length() {
+            let source = [0x00; 9];
+            let error = nom::Err::Error(ParseError::InvalidLength(9));
+            assert_eq!(uint(&source[..], 9), Err(error.clone()));
+            assert_eq!(int(&source[..], 9), Err(error));
+        }
+
+        #[test]
+        fn test_int_empty_input() {
+            assert_eq!(int(&[], 0), Ok((&[][..], 0)));
+            assert!(matches!(int(&[], 1), Err(nom::Err::Incomplete(_))));
+        }
+
+        #[rstest]
+        #[case(&[0x40, 0x00])] // 2-octet VINT
+        #[case(&[0x80])] // 1-octet VINT
+        fn test_empty_uint(#[case] source: &'static [u8]) {
+            let (input, length) = element_len(source).unwrap();
+            assert_eq!(length, Some(0));
+            assert_eq!(uint(input, 0), Ok((&[][..], 0)));
+        }
+
+        #[test]
+        fn test_empty_numbers() {
+            let source = [0xFF];
+            assert_eq!(int(&source[..], 0), Ok((&source[..], 0)));
+            assert_eq!(float32(&source[..], 0), Ok((&source[..], 0.0)));
+            assert_eq!(float64(&source[..], 0), Ok((&source[..], 0.0)));
+            assert_eq!(date(&source[..], 0), Ok((&source[..], 0)));
+        }
+
+        #[rstest]
+        #[case(1)]
+        #[case(3)]
+        #[case(8)]
+        fn test_float32_invalid_length(#[case] length: usize) {
+            let source = [0x00; 8];
+            assert_eq!(
+                float32(&source[..], length),
+                Err(nom::Err::Error(ParseError::InvalidLength(length)))
+            );
+        }
+
+        #[rstest]
+        #[case(1)]
+        #[case(5)]
+        #[case(9)]
+        fn test_float64_invalid_length(#[case] length: usize) {
+            let source = [0x00; 9];
+            assert_eq!(
+                float64(&source[..], length),
+                Err(nom::Err::Error(ParseError::InvalidLength(length)))
+            );
+            assert_eq!(
+                date(&source[..], length),
+                Err(nom::Err::Error(ParseError::InvalidLength(length)))
+            );
+        }
+
+        #[test]
+        fn test_float64_from_4_octets() {
+            let source = 3.0f32.to_be_bytes();
+            assert_eq!(float64(&source[..], 4), Ok((&[][..], 3.0)));
+        }
+
         #[test]
         fn test_float32() {
             let num = 3.0f32;
@@ -389,6 +697,26 @@ pub mod parse {
             assert_eq!(ascii_str(&source[..], 8), Ok((&source[8..], "I am a s")));
         }
 
+        #[rstest]
+        #[case(b"caf\xC3\xA9", Ok("café"))]
+        #[case(b"\x80abc", Err(ParseError::InvalidUtf8))]
+        #[case(b"\xF8\x80\x80\x80\x80", Err(ParseError::InvalidUtf8))]
+        fn test_non_ascii_str(
+            #[case] source: &[u8],
+            #[case] expt_result: Result<&str, ParseError>,
+        ) {
+            assert_eq!(
+                ascii_str(source, source.len()),
+                Err(nom::Err::Error(ParseError::InvalidAscii))
+            );
+            assert_eq!(
+                unicode_str(source, source.len()),
+                expt_result
+                    .map(|value| (&[][..], value))
+                    .map_err(nom::Err::Error)
+            );
+        }
+
         #[test]
         fn test_unicode_str() {
             let s = "知ら ない の か ？ 死神 の 霊 絡 は 色 が 違う って こと ｡";
@@ -399,6 +727,17 @@ pub mod parse {
             );
         }
 
+        #[test]
+        fn test_unicode_str_raw() {
+            let source = b"hello\0\xFF";
+            assert_eq!(unicode_str(source, 6), Ok((&source[6..], "hello")));
+            assert_eq!(unicode_str_raw(source, 6), Ok((&source[6..], "hello\0")));
+            assert_eq!(
+                unicode_str_raw(source, 7),
+                Err(nom::Err::Error(ParseError::InvalidUtf8))
+            );
+        }
+
         #[test]
         fn test_date() {
             let source = [0x40, 0x01, 0xFF, 0x00, 0x40, 0x01, 0xFF, 0x00, 0xFF, 0xFF];
@@ -414,9 +753,9 @@ pub mod parse {
 }
 
 pub mod serialize {
-    use std::cmp::{max, min, Ordering};
-    use std::mem::size_of;
-    use std::num::NonZeroU32;
+    use core::cmp::{max, min, Ordering};
+    use core::mem::size_of;
+    use core::num::NonZeroU32;
 
     use nom::{Err, IResult, Needed};
 
@@ -464,7 +803,7 @@ pub mod serialize {
         min_length: Option<usize>,
         max_length: Option<usize>,
     ) -> IResult<&mut [u8], usize, ()> {
-        let bitlen = 8 * size_of::<u64>() - value.leading_zeros() as usize;
+        let bitlen = u64::BITS as usize - value.leading_zeros() as usize;
         let mut vint_len = bitlen.saturating_sub(1) / 7 + 1;
 
         if let Some(length) = min_length {
@@ -504,22 +843,25 @@ pub mod serialize {
             _ => return Err(nom::Err::Error(())),
         };
         let buffer = &value.to_be_bytes()[size_of::<u32>() - bytelen..];
-        let (output, _) = give_bytes(&mut output[..buffer.len()], buffer)?;
+        let (output, _) = give_bytes(output, buffer)?;
 
         Ok((output, bytelen))
     }
 
+    // writes an element length of `bytelen` octets if given, else of as few octets as possible; it
+    // can't be wider than `max_octets` (up to 8), e.g. a document's `EBMLMaxSizeLength`
     pub fn element_len(
         output: &mut [u8],
         value: Option<u64>,
         bytelen: Option<usize>,
+        max_octets: usize,
     ) -> IResult<&mut [u8], usize, ()> {
         match value {
             None => {
                 let bytelen = bytelen.unwrap_or(1);
                 let value = !(u64::MAX << (7 * bytelen));
 
-                vlen_int(output, value, Some(bytelen), Some(8))
+                vlen_int(output, value, Some(bytelen), Some(max_octets))
             }
             Some(value) => {
                 let min_bytelen = (value.count_ones() / 7 + 1) as usize; // ensures that VINT_DATA of len's are not all 1's
@@ -528,12 +870,65 @@ pub mod serialize {
                     output,
                     value,
                     Some(bytelen.map_or(min_bytelen, |x| max(x, min_bytelen))),
-                    Some(8),
+                    Some(max_octets),
                 )
             }
         }
     }
 
+    // the octets of an element length that's written after its element's data, via `backpatch_len`
+    #[derive(Debug, PartialEq)]
+    pub struct ReservedLen<'a>(&'a mut [u8]);
+
+    // reserves a `max_octets`-wide element length (up to 8), e.g. for a master element whose size
+    // isn't known until its children are written
+    pub fn reserve_len(
+        output: &mut [u8],
+        max_octets: usize,
+    ) -> IResult<&mut [u8], ReservedLen<'_>, ()> {
+        if !(1..=size_of::<u64>()).contains(&max_octets) {
+            return Err(nom::Err::Error(()));
+        }
+        if output.len() < max_octets {
+            return Err(Err::Incomplete(Needed::new(max_octets - output.len())));
+        }
+        let (reserved, output) = output.split_at_mut(max_octets);
+
+        Ok((output, ReservedLen(reserved)))
+    }
+
+    // fills in a reserved element length, once its element's data has been written
+    pub fn backpatch_len(reserved: ReservedLen, value: u64) -> Result<(), nom::Err<()>> {
+        let ReservedLen(output) = reserved;
+        let mut buffer = [0x00u8; size_of::<u64>()];
+        let (_, bytelen) = element_len(&mut buffer[..], Some(value), Some(output.len()), 8)?;
+        if bytelen != output.len() {
+            // the value doesn't fit in the reserved octets
+            return Err(nom::Err::Error(()));
+        }
+        output.copy_from_slice(&buffer[..bytelen]);
+
+        Ok(())
+    }
+
+    // the CRC-32 that a `CRC-32` element stores for the data following it in its parent
+    pub fn crc32(data: &[u8]) -> u32 {
+        super::crc32::update(0, data)
+    }
+
+    // writes a whole `CRC-32` element over `data`, w/ its value in little-endian (as per the spec)
+    pub fn crc32_element<'a>(
+        output: &'a mut [u8],
+        data: &[u8],
+    ) -> IResult<&'a mut [u8], usize, ()> {
+        let id = NonZeroU32::new(0xBF).ok_or(nom::Err::Error(()))?;
+        let (output, id_len) = element_id(output, id)?;
+        let (output, len_len) = element_len(output, Some(4), None, 8)?;
+        let (output, _) = give_bytes(output, &crc32(data).to_le_bytes())?;
+
+        Ok((output, id_len + len_len + 4))
+    }
+
     pub fn uint(output: &mut [u8], value: u64, length: usize) -> IResult<&mut [u8], (), ()> {
         let byte_offset = size_of::<u64>()
             .checked_sub(length)
@@ -559,6 +954,20 @@ pub mod serialize {
         give_bytes(output, &source[byte_offset..])
     }
 
+    pub fn enum_label<'a>(
+        output: &'a mut [u8],
+        enums: &[(&str, u64)],
+        label: &str,
+        length: usize,
+    ) -> IResult<&'a mut [u8], (), ()> {
+        let (_, value) = enums
+            .iter()
+            .find(|(enum_label, _)| *enum_label == label)
+            .ok_or(nom::Err::Error(()))?;
+
+        uint(output, *value, length)
+    }
+
     pub fn float32(output: &mut [u8], value: f32, length: usize) -> IResult<&mut [u8], (), ()> {
         if length != size_of::<f32>() {
             return Err(nom::Err::Error(()));
@@ -575,6 +984,8 @@ pub mod serialize {
         give_bytes(output, &source[..])
     }
 
+    // writes a string w/ a null terminator when it's shorter than its field; the rest of the field
+    // is skipped, i.e. left as it was in `output` (e.g. for a buffer that's been zeroed beforehand)
     pub fn string<'a>(
         output: &'a mut [u8],
         value: &str,
@@ -592,6 +1003,28 @@ pub mod serialize {
         }
     }
 
+    // writes a string that fills its whole field, padded w/ null bytes regardless of what was in
+    // `output` beforehand; a string of exactly the field's length isn't terminated
+    pub fn string_exact<'a>(
+        output: &'a mut [u8],
+        value: &str,
+        length: usize,
+    ) -> IResult<&'a mut [u8], (), ()> {
+        let value = value.as_bytes();
+        if length < value.len() {
+            return Err(nom::Err::Error(()));
+        }
+        if output.len() < length {
+            return Err(Err::Incomplete(Needed::new(length - output.len())));
+        }
+        let (field, output) = output.split_at_mut(length);
+        let (text, padding) = field.split_at_mut(value.len());
+        text.copy_from_slice(value);
+        padding.fill(0);
+
+        Ok((output, ()))
+    }
+
     pub fn date(output: &mut [u8], value: i64, length: usize) -> IResult<&mut [u8], (), ()> {
         if length != size_of::<i64>() {
             return Err(nom::Err::Error(()));
@@ -636,6 +1069,10 @@ pub mod serialize {
             case(0x81, &[0x81, 0x00, 0x00, 0x00, 0x00]),
             case(0x6345, &[0x63, 0x45, 0x00, 0x00, 0x00]),
             case(0x407F, &[0x40, 0x7F, 0x00, 0x00, 0x00]),
+            case(0x1A45DFA3, &[0x1A, 0x45, 0xDF, 0xA3, 0x00]),
+            case(0x18538067, &[0x18, 0x53, 0x80, 0x67, 0x00]),
+            case(0x1549A966, &[0x15, 0x49, 0xA9, 0x66, 0x00]),
+            case(0x1FFFFFFE, &[0x1F, 0xFF, 0xFF, 0xFE, 0x00]),
         )]
         fn test_element_id(value: u32, expt_output: &[u8]) {
             let mut output = [0x00u8; 5];
@@ -657,11 +1094,51 @@ pub mod serialize {
         )]
         fn test_element_len(value: Option<u64>, length: Option<usize>, expt_output: &[u8]) {
             let mut output = [0x00u8; 9];
-            let result = element_len(&mut output[..], value, length);
+            let result = element_len(&mut output[..], value, length, 8);
             assert!(result.is_ok());
             assert_eq!(output, expt_output);
         }
 
+        #[rstest]
+        #[case(Some(0x7E), None, 1, Ok(1))]
+        #[case(Some(0x7F), None, 1, Err(nom::Err::Error(())))]
+        #[case(Some(0x3FFE), None, 2, Ok(2))]
+        #[case(Some(0x1_0000_0000), None, 4, Err(nom::Err::Error(())))]
+        #[case(Some(1), Some(3), 2, Err(nom::Err::Error(())))]
+        #[case(None, Some(2), 2, Ok(2))]
+        #[case(None, Some(3), 2, Err(nom::Err::Error(())))]
+        fn test_element_len_max_octets(
+            #[case] value: Option<u64>,
+            #[case] length: Option<usize>,
+            #[case] max_octets: usize,
+            #[case] expt_result: Result<usize, nom::Err<()>>,
+        ) {
+            let mut output = [0x00u8; 9];
+            let result = element_len(&mut output[..], value, length, max_octets);
+            assert_eq!(result.map(|(_, bytelen)| bytelen), expt_result);
+        }
+
+        #[rstest]
+        #[case(b"", 0)]
+        #[case(b"123456789", 0xCBF43926)]
+        #[case(b"The quick brown fox jumps over the lazy dog", 0x414FA339)]
+        fn test_crc32(#[case] data: &[u8], #[case] expt_crc: u32) {
+            assert_eq!(crc32(data), expt_crc);
+        }
+
+        #[test]
+        fn test_crc32_element() {
+            let mut output = [0x00u8; 7];
+            let (rest, bytelen) = crc32_element(&mut output[..], b"123456789").unwrap();
+            assert_eq!((rest.len(), bytelen), (1, 6));
+            assert_eq!(output, [0xBF, 0x84, 0x26, 0x39, 0xF4, 0xCB, 0x00]);
+
+            assert_eq!(
+                crc32_element(&mut output[..5], b"123456789"),
+                Err(Err::Incomplete(Needed::new(1)))
+            );
+        }
+
         #[rstest(value, length, expt_output,
             case(0x01, 1, &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
             case(0x01, 2, &[0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
@@ -707,6 +1184,7 @@ pub mod serialize {
         #[rstest(value, expt_output,
             case(&"hello", &[0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x00, 0xFF, 0xFF, 0xFF]),
             case(&"え？", &[0xE3, 0x81, 0x88, 0xEF, 0xBC, 0x9F, 0xFF, 0xFF, 0xFF]),
+            case(&"hi", &[0x68, 0x69, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
         )]
         fn test_string(value: &str, expt_output: &[u8]) {
             let mut output = [0xFFu8; 9];
@@ -715,6 +1193,33 @@ pub mod serialize {
             assert_eq!(output, expt_output);
         }
 
+        #[rstest(value, length, expt_output,
+            case(&"hello", 5, &[0x68, 0x65, 0x6C, 0x6C, 0x6F, 0xFF, 0xFF, 0xFF, 0xFF]),
+            case(&"hello", 6, &[0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x00, 0xFF, 0xFF, 0xFF]),
+            case(&"hello", 8, &[0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x00, 0x00, 0x00, 0xFF]),
+            case(&"", 2, &[0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+        )]
+        fn test_string_exact(value: &str, length: usize, expt_output: &[u8]) {
+            let mut output = [0xFFu8; 9];
+            let (rest, ()) = string_exact(&mut output[..], value, length).unwrap();
+            assert_eq!(rest.len(), 9 - length);
+            assert_eq!(output, expt_output);
+        }
+
+        #[test]
+        fn test_string_exact_errors() {
+            let mut output = [0xFFu8; 9];
+            assert_eq!(
+                string_exact(&mut output[..], "hello", 4),
+                Err(nom::Err::Error(()))
+            );
+            assert_eq!(
+                string_exact(&mut output[..4], "abc", 6),
+                Err(Err::Incomplete(Needed::new(2)))
+            );
+            assert_eq!(output, [0xFFu8; 9]);
+        }
+
         #[rstest(value, length, expt_output,
             case(-1, 8, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]),
         )]
@@ -727,11 +1232,58 @@ pub mod serialize {
     }
 }
 
+// the CRC-32 used by EBML's `CRC-32` element (i.e. IEEE 802.3, w/ the reversed polynomial)
+pub mod crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xEDB88320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    // continues the CRC-32 `crc` (starting from 0) over more data
+    pub fn update(crc: u32, data: &[u8]) -> u32 {
+        !data.iter().fold(!crc, |crc, &byte| {
+            TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rstest::*;
+
+        #[rstest]
+        #[case(b"", 0)]
+        #[case(b"123456789", 0xCBF43926)]
+        fn test_update(#[case] data: &[u8], #[case] expt_crc: u32) {
+            assert_eq!(update(0, data), expt_crc);
+
+            // updating in pieces gives the same result
+            let (first, second) = data.split_at(data.len() / 2);
+            assert_eq!(update(update(0, first), second), expt_crc);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::num::NonZeroU32;
     use proptest::prelude::*;
-    use std::num::NonZeroU32;
 
     proptest! {
         #[test]
@@ -742,10 +1294,10 @@ mod tests {
                 &mut buffer[..],
                 NonZeroU32::new(value).expect("`NonZeroU32::new` failed"),
             ).expect("failed to write value");
-            let (_input, result) = parse::element_id(&buffer[..]).expect(&format!(
+            let (_input, result) = parse::element_id(&buffer[..]).unwrap_or_else(|_| panic!(
                 "failed to read value from [{}, {}, {}, {}, {}]",
                 buffer[0], buffer[1], buffer[2], buffer[3], buffer[4],
-            )[..]);
+            ));
 
             prop_assert_eq!(result, value);
         }
@@ -758,10 +1310,10 @@ mod tests {
                 &mut buffer[..],
                 NonZeroU32::new(value).expect("`NonZeroU32::new` failed"),
             ).expect("failed to write value");
-            let (_input, result) = parse::element_id(&buffer[..]).expect(&format!(
+            let (_input, result) = parse::element_id(&buffer[..]).unwrap_or_else(|_| panic!(
                 "failed to read value from [{}, {}, {}, {}, {}]",
                 buffer[0], buffer[1], buffer[2], buffer[3], buffer[4],
-            )[..]);
+            ));
 
             prop_assert_eq!(result, value);
         }
@@ -774,10 +1326,10 @@ mod tests {
                 &mut buffer[..],
                 NonZeroU32::new(value).expect("`NonZeroU32::new` failed"),
             ).expect("failed to write value");
-            let (_input, result) = parse::element_id(&buffer[..]).expect(&format!(
+            let (_input, result) = parse::element_id(&buffer[..]).unwrap_or_else(|_| panic!(
                 "failed to read value from [{}, {}, {}, {}, {}]",
                 buffer[0], buffer[1], buffer[2], buffer[3], buffer[4],
-            )[..]);
+            ));
 
             prop_assert_eq!(result, value);
         }
@@ -790,10 +1342,10 @@ mod tests {
                 &mut buffer[..],
                 NonZeroU32::new(value).expect("`NonZeroU32::new` failed"),
             ).expect("failed to write value");
-            let (_input, result) = parse::element_id(&buffer[..]).expect(&format!(
+            let (_input, result) = parse::element_id(&buffer[..]).unwrap_or_else(|_| panic!(
                 "failed to read value from [{}, {}, {}, {}, {}]",
                 buffer[0], buffer[1], buffer[2], buffer[3], buffer[4],
-            )[..]);
+            ));
 
             prop_assert_eq!(result, value);
         }
@@ -803,7 +1355,7 @@ mod tests {
             let value = Some(value);
             let mut buffer = [0x00u8; 9];
 
-            let (_output, _bytelen) = serialize::element_len(&mut buffer[..], value, None).expect("failed to write value");
+            let (_output, _bytelen) = serialize::element_len(&mut buffer[..], value, None, 8).expect("failed to write value");
             let (_input, result) = parse::element_len(&buffer[..]).expect("failed to read value");
 
             prop_assert_eq!(result, value);
@@ -881,5 +1433,109 @@ mod tests {
             prop_assert_eq!(result, value);
         }
 
+        #[test]
+        fn write_read_eq_ascii_padded(value in "[ -~]{0,8}", padding in 1usize..8) {
+            let length = value.len() + padding;
+            let mut buffer = vec![0x00u8; length];
+
+            let (_output, _bytelen) = serialize::string(&mut buffer[..], &value, length).expect("failed to write value");
+            let (_input, result) = parse::ascii_str(&buffer[..], length).expect("failed to read value");
+
+            prop_assert_eq!(result, value);
+        }
+
+        #[test]
+        fn write_read_eq_unicode_padded(value in "\\PC{0,5}", padding in 1usize..8) {
+            let length = value.len() + padding;
+            let mut buffer = vec![0x00u8; length];
+
+            let (_output, _bytelen) = serialize::string(&mut buffer[..], &value, length).expect("failed to write value");
+            let (_input, result) = parse::unicode_str(&buffer[..], length).expect("failed to read value");
+            prop_assert_eq!(result, &value);
+
+            let (_input, result) = parse::unicode_str_raw(&buffer[..], length).expect("failed to read value");
+            prop_assert_eq!(result, value + &"\0".repeat(padding));
+        }
+
+    }
+
+    const TRACK_TYPES: [(&str, u64); 3] = [("video", 1), ("audio", 2), ("subtitle", 0x11)];
+
+    #[test]
+    fn write_read_eq_enum_label() {
+        let mut buffer = [0x00u8; 1];
+
+        serialize::enum_label(&mut buffer[..], &TRACK_TYPES, "audio", 1)
+            .expect("failed to write value");
+        assert_eq!(buffer, [0x02]);
+        let (_input, result) =
+            parse::enum_label(&buffer[..], 1, &TRACK_TYPES).expect("failed to read value");
+
+        assert_eq!(result, "audio");
+    }
+
+    #[test]
+    fn write_read_unknown_enum_label() {
+        let mut buffer = [0x03u8; 1];
+
+        assert_eq!(
+            serialize::enum_label(&mut buffer[..], &TRACK_TYPES, "metadata", 1),
+            Err(nom::Err::Error(()))
+        );
+        assert_eq!(
+            parse::enum_label(&buffer[..], 1, &TRACK_TYPES),
+            Err(nom::Err::Error(parse::ParseError::UnknownEnumValue(3)))
+        );
+    }
+
+    #[test]
+    fn write_master_w_backpatched_len() {
+        let mut buffer = [0x00u8; 16];
+
+        let output = &mut buffer[..];
+        let (output, _) = serialize::element_id(output, NonZeroU32::new(0x1A45DFA3).unwrap())
+            .expect("failed to write ID");
+        let (output, reserved) =
+            serialize::reserve_len(output, 4).expect("failed to reserve length");
+        let data_len = output.len();
+        let (output, _) = serialize::element_id(output, NonZeroU32::new(0x4286).unwrap())
+            .expect("failed to write ID");
+        let (output, _) =
+            serialize::element_len(output, Some(1), None, 8).expect("failed to write length");
+        let (output, _) = serialize::uint(output, 1, 1).expect("failed to write value");
+        let data_len = data_len - output.len();
+        serialize::backpatch_len(reserved, data_len as u64).expect("failed to backpatch length");
+
+        assert_eq!(
+            buffer[..12],
+            [0x1A, 0x45, 0xDF, 0xA3, 0x10, 0x00, 0x00, 0x04, 0x42, 0x86, 0x81, 0x01]
+        );
+        let (input, id) = parse::element_id(&buffer[..]).expect("failed to read ID");
+        assert_eq!(id, 0x1A45DFA3);
+        let (input, len) = parse::element_len(input).expect("failed to read length");
+        assert_eq!(len, Some(4));
+        let (_input, id) = parse::element_id(&input[..4]).expect("failed to read ID");
+        assert_eq!(id, 0x4286);
+    }
+
+    #[test]
+    fn backpatch_len_overflow() {
+        let mut buffer = [0x00u8; 2];
+
+        let (_output, reserved) =
+            serialize::reserve_len(&mut buffer[..], 1).expect("failed to reserve length");
+        assert_eq!(
+            serialize::backpatch_len(reserved, 0x7F),
+            Err(nom::Err::Error(()))
+        );
+        assert_eq!(buffer, [0x00, 0x00]);
+        assert_eq!(
+            serialize::reserve_len(&mut buffer[..], 9),
+            Err(nom::Err::Error(()))
+        );
+        assert_eq!(
+            serialize::reserve_len(&mut buffer[..], 3),
+            Err(nom::Err::Incomplete(nom::Needed::new(1)))
+        );
     }
 }
@@ -0,0 +1,440 @@
+use crate::base::element_defs::{
+    BinaryElementDef, DateElementDef, ElementDef, FloatElementDef, IntElementDef, MasterElementDef,
+    StringElementDef, UIntElementDef, Utf8ElementDef,
+};
+use crate::base::header::{EbmlHeader, EBML_ID};
+use crate::base::parser::{system_time_to_date, ReaderError};
+use crate::base::stream::serialize;
+
+use core::cmp::max;
+use core::convert::TryInto;
+use core::num::NonZeroU32;
+use std::io::Write;
+use std::time::SystemTime;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WriterError {
+    #[error("IOError: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("ReaderError: {0}")]
+    Read(#[from] ReaderError),
+    #[error("failed to serialize element (id = {0})")]
+    Serialize(u32),
+    #[error("cannot end element {1} (open element = {:?})", *.0)]
+    UnmatchedEnd(Option<u32>, u32),
+    #[error("{0} master element(s) left unclosed")]
+    UnclosedElements(usize),
+    #[error("value is outside of the range allowed for element (id = {0})")]
+    ValueOutOfRange(u32),
+    #[error("length {len} is outside of the range allowed for element (id = {id})")]
+    LengthOutOfRange { id: u32, len: usize },
+    #[error("the EBML header must precede all elements ({0} master element(s) open)")]
+    HeaderNotFirst(usize),
+    #[error("EBMLMaxSizeLength {0} is outside of the range 1-8")]
+    MaxSizeLengthOutOfRange(u64),
+}
+
+// a master element that has been started, but not yet ended
+// -> its children are buffered until its length is known
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriterState {
+    pub id: u32,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct ElementWriter<W> {
+    pub(crate) writer: W,
+    pub(crate) open: Vec<WriterState>,
+    // the widest element length allowed, i.e. the document's `EBMLMaxSizeLength`
+    pub(crate) max_size_length: usize,
+}
+
+impl<W: Write> ElementWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            open: Vec::new(),
+            max_size_length: 8,
+        }
+    }
+
+    // limits element lengths to `max_octets`, e.g. as declared by the document's header
+    pub fn max_size_length(mut self, max_octets: usize) -> Self {
+        self.max_size_length = max_octets;
+        self
+    }
+
+    // writes the `\EBML` header that starts a new document, & limits element lengths to the
+    // header's `EBMLMaxSizeLength`
+    pub fn write_header(&mut self, header: &EbmlHeader) -> Result<(), WriterError> {
+        if !self.open.is_empty() {
+            return Err(WriterError::HeaderNotFirst(self.open.len()));
+        }
+        // (element lengths are at most 8 octets wide, & can't be 0 octets wide)
+        if !(1..=8).contains(&header.max_size_length) {
+            return Err(WriterError::MaxSizeLengthOutOfRange(header.max_size_length));
+        }
+        let bytes = header
+            .to_bytes()
+            .map_err(|_| WriterError::Serialize(EBML_ID))?;
+        self.writer.write_all(&bytes)?;
+        self.max_size_length = header.max_size_length.try_into().unwrap_or(usize::MAX);
+        Ok(())
+    }
+
+    // the number of master elements currently open
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    pub fn start<E: MasterElementDef>(&mut self) {
+        self.open.push(WriterState {
+            id: E::ID,
+            body: Vec::new(),
+        });
+    }
+
+    pub fn end<E: MasterElementDef>(&mut self) -> Result<(), WriterError> {
+        match self.open.pop() {
+            Some(state) if state.id == E::ID => {
+                check_length::<E>(state.body.len())?;
+                self.write_element(E::ID, &state.body)
+            }
+            state => Err(WriterError::UnmatchedEnd(state.map(|s| s.id), E::ID)),
+        }
+    }
+
+    pub fn write_uint<E: UIntElementDef>(&mut self, value: u64) -> Result<(), WriterError> {
+        if !E::RANGE.contains(&value) {
+            return Err(WriterError::ValueOutOfRange(E::ID));
+        }
+        let length = (u64::BITS - value.leading_zeros() + 7) as usize / 8;
+        self.write_data::<E>(length, |output| serialize::uint(output, value, length))
+    }
+
+    pub fn write_int<E: IntElementDef>(&mut self, value: i64) -> Result<(), WriterError> {
+        if !E::RANGE.contains(&value) {
+            return Err(WriterError::ValueOutOfRange(E::ID));
+        }
+        let spare_bits = max(value.leading_zeros(), value.leading_ones()) - 1; // keep the sign bit
+        let length = 8 - (spare_bits / 8) as usize;
+        self.write_data::<E>(length, |output| serialize::int(output, value, length))
+    }
+
+    // floats are always written at full (64-bit) precision
+    pub fn write_float<E: FloatElementDef>(&mut self, value: f64) -> Result<(), WriterError> {
+        if !E::RANGE.contains(&value) {
+            return Err(WriterError::ValueOutOfRange(E::ID));
+        }
+        self.write_data::<E>(8, |output| serialize::float64(output, value, 8))
+    }
+
+    pub fn write_date<E: DateElementDef>(&mut self, value: i64) -> Result<(), WriterError> {
+        if !E::RANGE.contains(&value) {
+            return Err(WriterError::ValueOutOfRange(E::ID));
+        }
+        self.write_data::<E>(8, |output| serialize::date(output, value, 8))
+    }
+
+    // writes a date given as a system time, rather than as nanoseconds since the EBML epoch
+    pub fn write_system_time<E: DateElementDef>(
+        &mut self,
+        time: SystemTime,
+    ) -> Result<(), WriterError> {
+        let value = system_time_to_date(time).ok_or(WriterError::Serialize(E::ID))?;
+        self.write_date::<E>(value)
+    }
+
+    pub fn write_string<E: StringElementDef>(&mut self, value: &str) -> Result<(), WriterError> {
+        let length = value.len();
+        self.write_data::<E>(length, |output| serialize::string(output, value, length))
+    }
+
+    pub fn write_utf8<E: Utf8ElementDef>(&mut self, value: &str) -> Result<(), WriterError> {
+        let length = value.len();
+        self.write_data::<E>(length, |output| serialize::string(output, value, length))
+    }
+
+    pub fn write_binary<E: BinaryElementDef>(&mut self, value: &[u8]) -> Result<(), WriterError> {
+        self.write_data::<E>(value.len(), |output| serialize::binary(output, value))
+    }
+
+    // writes an element's data as-is, regardless of its type (e.g. as read from another document)
+    pub fn write_raw<E: ElementDef>(&mut self, data: &[u8]) -> Result<(), WriterError> {
+        check_length::<E>(data.len())?;
+        self.write_element(E::ID, data)
+    }
+
+    // flushes the written document, & returns the underlying writer
+    pub fn finish(mut self) -> Result<W, WriterError> {
+        if !self.open.is_empty() {
+            return Err(WriterError::UnclosedElements(self.open.len()));
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_data<E: ElementDef>(
+        &mut self,
+        length: usize,
+        serialize: impl FnOnce(&mut [u8]) -> nom::IResult<&mut [u8], (), ()>,
+    ) -> Result<(), WriterError> {
+        check_length::<E>(length)?;
+        let mut data = vec![0u8; length];
+        serialize(&mut data).map_err(|_| WriterError::Serialize(E::ID))?;
+        self.write_element(E::ID, &data)
+    }
+
+    // writes a complete element into its open parent, or directly to the output if top-level
+    fn write_element(&mut self, id: u32, data: &[u8]) -> Result<(), WriterError> {
+        let mut header = [0u8; 12]; // ID's take at most 4 octets, lengths at most 8
+        let id_len = NonZeroU32::new(id)
+            .and_then(|nz_id| serialize::element_id(&mut header[..], nz_id).ok())
+            .map(|(_, id_len)| id_len)
+            .ok_or(WriterError::Serialize(id))?;
+        let (_, len_len) = serialize::element_len(
+            &mut header[id_len..],
+            Some(data.len() as u64),
+            None,
+            self.max_size_length,
+        )
+        .map_err(|_| WriterError::Serialize(id))?;
+        let header = &header[..id_len + len_len];
+
+        match self.open.last_mut() {
+            Some(state) => {
+                state.body.extend_from_slice(header);
+                state.body.extend_from_slice(data);
+            }
+            None => {
+                self.writer.write_all(header)?;
+                self.writer.write_all(data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// checks an element's octet length against the lengths allowed by its schema
+fn check_length<E: ElementDef>(len: usize) -> Result<(), WriterError> {
+    if !E::LENGTH.contains(&len) {
+        return Err(WriterError::LengthOutOfRange { id: E::ID, len });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::element_defs::{Bound, Range};
+
+    use rstest::*;
+
+    struct GroupDef;
+    impl ElementDef for GroupDef {
+        const NAME: &'static str = "Group";
+        const ID: u32 = 0x81;
+        const PATH: &'static str = r"\Group";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = None;
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+    impl MasterElementDef for GroupDef {
+        const UNKNOWN_SIZE_ALLOWED: bool = false;
+        const RECURSIVE: bool = false;
+        const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[];
+    }
+
+    struct CountDef;
+    impl ElementDef for CountDef {
+        const NAME: &'static str = "Count";
+        const ID: u32 = 0x82;
+        const PATH: &'static str = r"\Group\Count";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = None;
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+    impl UIntElementDef for CountDef {
+        const RANGE: Range<u64> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const DEFAULT: Option<u64> = None;
+    }
+
+    struct VersionDef;
+    impl ElementDef for VersionDef {
+        const NAME: &'static str = "EBMLVersion";
+        const ID: u32 = 0x4286;
+        const PATH: &'static str = r"\Group\EBMLVersion";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = None;
+        const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+    impl UIntElementDef for VersionDef {
+        const RANGE: Range<u64> = Range::Excludes(0);
+        const DEFAULT: Option<u64> = None;
+    }
+
+    struct ChecksumDef;
+    impl ElementDef for ChecksumDef {
+        const NAME: &'static str = "Checksum";
+        const ID: u32 = 0x83;
+        const PATH: &'static str = r"\Group\Checksum";
+
+        const MIN_OCCURS: usize = 0;
+        const MAX_OCCURS: Option<usize> = None;
+        const LENGTH: Range<usize> = Range::IsExactly(4);
+        const RECURRING: bool = false;
+        const MIN_VERSION: u64 = 1;
+        const MAX_VERSION: Option<u64> = None;
+    }
+    impl BinaryElementDef for ChecksumDef {
+        const DEFAULT: Option<&'static [u8]> = None;
+    }
+
+    #[rstest]
+    #[case(0, &[0x82, 0x80])]
+    #[case(0xFF, &[0x82, 0x81, 0xFF])]
+    #[case(0x100, &[0x82, 0x82, 0x01, 0x00])]
+    fn write_uint(#[case] value: u64, #[case] expt_output: &[u8]) {
+        let mut writer = ElementWriter::new(Vec::new());
+        writer.write_uint::<CountDef>(value).unwrap();
+        assert_eq!(writer.finish().unwrap(), expt_output);
+    }
+
+    #[rstest]
+    fn write_nested() {
+        let mut writer = ElementWriter::new(Vec::new());
+        writer.start::<GroupDef>();
+        writer.write_uint::<CountDef>(5).unwrap();
+        assert_eq!(writer.depth(), 1);
+        writer.end::<GroupDef>().unwrap();
+
+        assert_eq!(
+            writer.finish().unwrap(),
+            &[0x81, 0x83, 0x82, 0x81, 0x05][..]
+        );
+    }
+
+    #[rstest]
+    fn write_unclosed() {
+        let mut writer = ElementWriter::new(Vec::new());
+        writer.start::<GroupDef>();
+        assert!(matches!(
+            writer.finish(),
+            Err(WriterError::UnclosedElements(1))
+        ));
+    }
+
+    #[rstest]
+    fn write_over_max_size_length() {
+        let mut writer = ElementWriter::new(Vec::new()).max_size_length(1);
+        writer.start::<GroupDef>();
+        // 43 * 3 octets -> the group's length needs 2 octets
+        for _ in 0..43 {
+            writer.write_uint::<CountDef>(1).unwrap();
+        }
+        assert!(matches!(
+            writer.end::<GroupDef>(),
+            Err(WriterError::Serialize(0x81))
+        ));
+
+        let mut writer = ElementWriter::new(Vec::new()).max_size_length(2);
+        writer.start::<GroupDef>();
+        for _ in 0..43 {
+            writer.write_uint::<CountDef>(1).unwrap();
+        }
+        writer.end::<GroupDef>().unwrap();
+        assert_eq!(writer.finish().unwrap()[..3], [0x81, 0x40, 0x81]);
+    }
+
+    #[rstest]
+    #[case(0, false)]
+    #[case(1, true)]
+    #[case(8, true)]
+    #[case(9, false)]
+    fn write_header_max_size_length(#[case] max_size_length: u64, #[case] expt_ok: bool) {
+        let mut header = EbmlHeader::new("test", 1, 1);
+        header.max_size_length = max_size_length;
+        let mut writer = ElementWriter::new(Vec::new());
+        let result = writer.write_header(&header);
+        if expt_ok {
+            assert!(result.is_ok());
+            assert_eq!(writer.max_size_length, max_size_length as usize);
+        } else {
+            assert!(matches!(
+                result,
+                Err(WriterError::MaxSizeLengthOutOfRange(len)) if len == max_size_length
+            ));
+            assert!(writer.finish().unwrap().is_empty());
+        }
+    }
+
+    #[rstest]
+    fn write_header_after_elements() {
+        let mut writer = ElementWriter::new(Vec::new());
+        writer.start::<GroupDef>();
+        assert!(matches!(
+            writer.write_header(&EbmlHeader::new("test", 1, 1)),
+            Err(WriterError::HeaderNotFirst(1))
+        ));
+    }
+
+    #[rstest]
+    fn write_raw() {
+        let mut writer = ElementWriter::new(Vec::new());
+        writer.start::<GroupDef>();
+        writer.write_raw::<CountDef>(&[0x00, 0x05]).unwrap();
+        writer.write_raw::<ChecksumDef>(&[1, 2, 3, 4]).unwrap();
+        assert!(matches!(
+            writer.write_raw::<ChecksumDef>(&[1, 2, 3]),
+            Err(WriterError::LengthOutOfRange { id: 0x83, len: 3 })
+        ));
+        writer.end::<GroupDef>().unwrap();
+
+        assert_eq!(
+            writer.finish().unwrap(),
+            &[0x81, 0x8A, 0x82, 0x82, 0x00, 0x05, 0x83, 0x84, 1, 2, 3, 4][..]
+        );
+    }
+
+    #[rstest]
+    fn write_out_of_range() {
+        let mut writer = ElementWriter::new(Vec::new());
+        assert!(matches!(
+            writer.write_uint::<VersionDef>(0),
+            Err(WriterError::ValueOutOfRange(0x4286))
+        ));
+        writer.write_uint::<VersionDef>(1).unwrap();
+        assert_eq!(writer.finish().unwrap(), &[0x42, 0x86, 0x81, 0x01][..]);
+    }
+
+    #[rstest]
+    #[case(&[1, 2, 3], false)]
+    #[case(&[1, 2, 3, 4], true)]
+    #[case(&[1, 2, 3, 4, 5], false)]
+    fn write_length_out_of_range(#[case] value: &[u8], #[case] expt_ok: bool) {
+        let mut writer = ElementWriter::new(Vec::new());
+        let result = writer.write_binary::<ChecksumDef>(value);
+        if expt_ok {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(
+                result,
+                Err(WriterError::LengthOutOfRange { id: 0x83, len }) if len == value.len()
+            ));
+        }
+        assert_eq!(writer.depth(), 0);
+    }
+}
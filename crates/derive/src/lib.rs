@@ -0,0 +1,165 @@
+use iron_ebmlem::parser_gen::Builder;
+use iron_ebmlem::serde_schema::{EbmlSchema, Element, ElementType};
+
+use proc_macro::TokenStream;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Ident, ItemStruct, Lit, LitInt, LitStr};
+
+struct ElementDefs(Vec<ItemStruct>);
+
+impl Parse for ElementDefs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+        Ok(Self(items))
+    }
+}
+
+fn parse_element_type(ident: &Ident) -> syn::Result<ElementType> {
+    Ok(match ident.to_string().as_str() {
+        "integer" => ElementType::SignedInteger,
+        "uinteger" => ElementType::UnsignedInteger,
+        "float" => ElementType::Float,
+        "string" => ElementType::String,
+        "date" => ElementType::Date,
+        "utf8" => ElementType::Utf8,
+        "master" => ElementType::Master,
+        "binary" => ElementType::Binary,
+        _ => return Err(syn::Error::new(ident.span(), "unknown element type")),
+    })
+}
+
+// schema attributes are all strings -> accept any literal in its source representation
+fn lit_to_string(lit: Lit) -> syn::Result<String> {
+    Ok(match lit {
+        Lit::Str(lit) => lit.value(),
+        Lit::Int(lit) => lit.base10_digits().to_string(),
+        Lit::Float(lit) => lit.base10_digits().to_string(),
+        Lit::Bool(lit) => lit.value.to_string(),
+        lit => return Err(syn::Error::new(lit.span(), "unsupported literal")),
+    })
+}
+
+fn parse_element(item: &ItemStruct) -> syn::Result<Element> {
+    let attr = item
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("ebml_element"))
+        .ok_or_else(|| syn::Error::new(item.ident.span(), "missing `ebml_element` attribute"))?;
+
+    let mut id = None;
+    let mut path = None;
+    let mut r#type = None;
+    let mut setters: Vec<Box<dyn FnOnce(Element) -> syn::Result<Element>>> = Vec::new();
+    attr.parse_nested_meta(|meta| {
+        let key = meta
+            .path
+            .get_ident()
+            .ok_or_else(|| meta.error("expected an attribute name"))?
+            .to_string();
+        match key.as_str() {
+            "id" => id = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<u32>()?),
+            "path" => path = Some(meta.value()?.parse::<LitStr>()?.value()),
+            "type" => r#type = Some(parse_element_type(&meta.value()?.call(Ident::parse_any)?)?),
+            _ => {
+                let span = meta.path.get_ident().unwrap().span();
+                let value = lit_to_string(meta.value()?.parse()?)?;
+                let parse_err = move |e: std::num::ParseIntError| syn::Error::new(span, e);
+                let parse_bool = move |e: std::str::ParseBoolError| syn::Error::new(span, e);
+                setters.push(match key.as_str() {
+                    "min_occurs" => Box::new(move |elem: Element| {
+                        Ok(elem.with_min_occurs(value.parse().map_err(parse_err)?))
+                    }),
+                    "max_occurs" => Box::new(move |elem: Element| {
+                        Ok(elem.with_max_occurs(value.parse().map_err(parse_err)?))
+                    }),
+                    "range" => Box::new(move |elem: Element| Ok(elem.with_range(&value))),
+                    "length" => Box::new(move |elem: Element| Ok(elem.with_length(&value))),
+                    "default" => Box::new(move |elem: Element| Ok(elem.with_default(&value))),
+                    "unknownsizeallowed" => Box::new(move |elem: Element| {
+                        Ok(elem.with_unknownsizeallowed(value.parse().map_err(parse_bool)?))
+                    }),
+                    "recursive" => Box::new(move |elem: Element| {
+                        Ok(elem.with_recursive(value.parse().map_err(parse_bool)?))
+                    }),
+                    "recurring" => Box::new(move |elem: Element| {
+                        Ok(elem.with_recurring(value.parse().map_err(parse_bool)?))
+                    }),
+                    "minver" => Box::new(move |elem: Element| {
+                        Ok(elem.with_minver(value.parse().map_err(parse_err)?))
+                    }),
+                    "maxver" => Box::new(move |elem: Element| {
+                        Ok(elem.with_maxver(value.parse().map_err(parse_err)?))
+                    }),
+                    _ => return Err(meta.error("unknown element attribute")),
+                });
+            }
+        }
+        Ok(())
+    })?;
+
+    let missing = |name: &str| syn::Error::new(attr.path().span(), format!("missing `{}`", name));
+    setters.into_iter().try_fold(
+        Element::new(
+            &item.ident.to_string(),
+            &path.ok_or_else(|| missing("path"))?,
+            id.ok_or_else(|| missing("id"))?,
+            r#type.ok_or_else(|| missing("type"))?,
+        ),
+        |elem, setter| setter(elem),
+    )
+}
+
+fn expand(items: ElementDefs) -> syn::Result<proc_macro2::TokenStream> {
+    let schema = items
+        .0
+        .iter()
+        .map(parse_element)
+        .try_fold(EbmlSchema::new("", 1), |schema, element| {
+            Ok::<_, syn::Error>(schema.with_element(element?))
+        })?;
+
+    let call_site = proc_macro2::Span::call_site();
+    let parsers = Builder::from_schema(schema)
+        .generate()
+        .map_err(|e| syn::Error::new(call_site, e))?;
+    let mut source = Vec::new();
+    parsers
+        .write_element_defs(&mut source)
+        .map_err(|e| syn::Error::new(call_site, e))?;
+
+    String::from_utf8(source)
+        .map_err(|e| syn::Error::new(call_site, e))?
+        .parse()
+        .map_err(|e: proc_macro2::LexError| syn::Error::new(call_site, format!("{:?}", e)))
+}
+
+/**
+Defines EBML elements in Rust, as an alternative to an XML schema:
+
+```ignore
+ebml_schema! {
+    #[ebml_element(id = 0x1A45DFA3, path = r"\EBML", type = master)]
+    struct EBML;
+
+    #[ebml_element(id = 0x4286, path = r"\EBML\EBMLVersion", type = uinteger, default = 1)]
+    struct EBMLVersion;
+}
+```
+
+Each unit struct names an element. The definitions are validated like any schema read by
+`iron_ebmlem::parser_gen::Builder`, and expand to the same `{name}Def` element definitions as
+`Parsers::write_element_defs`; the calling crate must provide the `crate::base` template module.
+
+**/
+#[proc_macro]
+pub fn ebml_schema(input: TokenStream) -> TokenStream {
+    let items = parse_macro_input!(input as ElementDefs);
+    expand(items)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,38 @@
+use iron_ebmlem_derive::ebml_schema;
+
+// the generated definitions refer to the parser template's base module
+mod base {
+    pub use example_ebml_parser::base::element_defs;
+}
+
+mod element_defs {
+    use super::*;
+
+    ebml_schema! {
+        #[ebml_element(id = 0x1A45DFA3, path = r"\EBML", type = master, min_occurs = 1, max_occurs = 1)]
+        struct EBML;
+
+        #[ebml_element(id = 0x4286, path = r"\EBML\EBMLVersion", type = uinteger, range = "not 0", default = 1)]
+        struct EBMLVersion;
+
+        #[ebml_element(id = 0x4282, path = r"\EBML\DocType", type = string, length = ">0")]
+        struct DocType;
+    }
+}
+
+use base::element_defs::{ElementDef, Range, UIntElementDef};
+use element_defs::*;
+
+#[test]
+fn derived_element_defs() {
+    assert_eq!(EBMLDef::ID, 0x1A45DFA3);
+    assert_eq!(EBMLDef::PATH, r"\EBML");
+    assert_eq!(EBMLDef::MIN_OCCURS, 1);
+    assert_eq!(EBMLDef::MAX_OCCURS, Some(1));
+
+    assert_eq!(EBMLVersionDef::ID, 0x4286);
+    assert!(matches!(EBMLVersionDef::RANGE, Range::Excludes(0)));
+    assert_eq!(EBMLVersionDef::DEFAULT, Some(1));
+
+    assert_eq!(DocTypeDef::PATH, r"\EBML\DocType");
+}
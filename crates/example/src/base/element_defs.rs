@@ -6,8 +6,29 @@ pub enum Range<T> {
     IsWithin(Bound<T>, Bound<T>),
 }
 
+impl<T: PartialOrd> Range<T> {
+    // NaN lies outside of every bounded range, since it fails all comparisons
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            Self::IsExactly(x) => value == x,
+            Self::Excludes(x) => value != x,
+            Self::IsWithin(lower, upper) => {
+                (match lower {
+                    Bound::Included(x) => value >= x,
+                    Bound::Excluded(x) => value > x,
+                    Bound::Unbounded => true,
+                }) && (match upper {
+                    Bound::Included(x) => value <= x,
+                    Bound::Excluded(x) => value < x,
+                    Bound::Unbounded => true,
+                })
+            }
+        }
+    }
+}
+
 pub trait ElementDef {
-    // name
+    const NAME: &'static str;
     const ID: u32;
     const PATH: &'static str;
 
@@ -22,6 +43,8 @@ pub trait ElementDef {
 pub trait MasterElementDef: ElementDef {
     const UNKNOWN_SIZE_ALLOWED: bool; // defaults to false
     const RECURSIVE: bool; // defaults to false
+                           // (ID, MIN_OCCURS) of the children that can't be omitted, i.e. mandatory children w/o a default
+    const MANDATORY_CHILDREN: &'static [(u32, usize)];
 }
 
 pub trait UIntElementDef: ElementDef {
@@ -29,6 +52,25 @@ pub trait UIntElementDef: ElementDef {
     const DEFAULT: Option<u64>;
 }
 
+// uinteger elements restricted to a set of labeled values
+pub trait EnumElementDef: UIntElementDef {
+    const ENUMS: &'static [(&'static str, u64)];
+
+    fn value_of(label: &str) -> Option<u64> {
+        Self::ENUMS
+            .iter()
+            .find(|(enum_label, _)| *enum_label == label)
+            .map(|(_, value)| *value)
+    }
+
+    fn label_of(value: u64) -> Option<&'static str> {
+        Self::ENUMS
+            .iter()
+            .find(|(_, enum_value)| *enum_value == value)
+            .map(|(label, _)| *label)
+    }
+}
+
 pub trait IntElementDef: ElementDef {
     const RANGE: Range<i64>; // defaults to (Unbounded, Unbounded)
     const DEFAULT: Option<i64>;
@@ -55,3 +97,54 @@ pub trait Utf8ElementDef: ElementDef {
 pub trait BinaryElementDef: ElementDef {
     const DEFAULT: Option<&'static [u8]>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    #[case(Range::IsExactly(1.0), 1.0, true)]
+    #[case(Range::IsExactly(1.0), 1.5, false)]
+    #[case(Range::Excludes(0.0), 0.0, false)]
+    #[case(Range::Excludes(0.0), f64::NAN, true)]
+    #[case(Range::IsWithin(Bound::Excluded(0.0), Bound::Unbounded), 0.0, false)]
+    #[case(
+        Range::IsWithin(Bound::Excluded(0.0), Bound::Unbounded),
+        f64::MIN_POSITIVE,
+        true
+    )]
+    #[case(Range::IsWithin(Bound::Included(0.0), Bound::Included(1.0)), 1.0, true)]
+    #[case(
+        Range::IsWithin(Bound::Included(0.0), Bound::Excluded(1.0)),
+        1.0,
+        false
+    )]
+    #[case(
+        Range::IsWithin(Bound::Included(0.0), Bound::Unbounded),
+        f64::NAN,
+        false
+    )]
+    #[case(Range::IsWithin(Bound::Unbounded, Bound::Unbounded), f64::NAN, true)]
+    fn float_range_contains(#[case] range: Range<f64>, #[case] value: f64, #[case] expt: bool) {
+        assert_eq!(range.contains(&value), expt);
+    }
+
+    #[rstest]
+    #[case(Range::IsExactly(3), 3, true)]
+    #[case(Range::IsExactly(3), 4, false)]
+    #[case(Range::Excludes(0), 0, false)]
+    #[case(Range::Excludes(0), -1, true)]
+    #[case(Range::IsWithin(Bound::Included(-2), Bound::Included(2)), -2, true)]
+    #[case(Range::IsWithin(Bound::Included(-2), Bound::Included(2)), 2, true)]
+    #[case(Range::IsWithin(Bound::Included(-2), Bound::Included(2)), 3, false)]
+    #[case(Range::IsWithin(Bound::Excluded(-2), Bound::Excluded(2)), -2, false)]
+    #[case(Range::IsWithin(Bound::Excluded(-2), Bound::Excluded(2)), -1, true)]
+    #[case(Range::IsWithin(Bound::Excluded(-2), Bound::Excluded(2)), 2, false)]
+    #[case(Range::IsWithin(Bound::Unbounded, Bound::Excluded(0)), i64::MIN, true)]
+    #[case(Range::IsWithin(Bound::Included(0), Bound::Unbounded), i64::MAX, true)]
+    fn int_range_contains(#[case] range: Range<i64>, #[case] value: i64, #[case] expt: bool) {
+        assert_eq!(range.contains(&value), expt);
+    }
+}
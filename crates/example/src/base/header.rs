@@ -0,0 +1,360 @@
+#[cfg(feature = "std")]
+use crate::base::parser::{parse_buffered, ReaderError};
+use crate::base::stream::{parse, parse::ParseError, serialize};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::num::NonZeroU32;
+
+pub const EBML_ID: u32 = 0x1A45DFA3;
+pub const EBML_VERSION_ID: u32 = 0x4286;
+pub const EBML_READ_VERSION_ID: u32 = 0x42F7;
+pub const EBML_MAX_ID_LENGTH_ID: u32 = 0x42F2;
+pub const EBML_MAX_SIZE_LENGTH_ID: u32 = 0x42F3;
+pub const DOC_TYPE_ID: u32 = 0x4282;
+pub const DOC_TYPE_VERSION_ID: u32 = 0x4287;
+pub const DOC_TYPE_READ_VERSION_ID: u32 = 0x4285;
+
+// the EBML version implemented by this crate
+pub const EBML_VERSION: u64 = 1;
+
+// The `\EBML` header that starts every EBML document; absent fields take their RFC 8794 defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EbmlHeader {
+    pub version: u64,
+    pub read_version: u64,
+    pub max_id_length: u64,
+    pub max_size_length: u64,
+    pub doc_type: String,
+    pub doc_type_version: u64,
+    pub doc_type_read_version: u64,
+}
+
+impl Default for EbmlHeader {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            read_version: 1,
+            max_id_length: 4,
+            max_size_length: 8,
+            doc_type: String::new(),
+            doc_type_version: 1,
+            doc_type_read_version: 1,
+        }
+    }
+}
+
+impl EbmlHeader {
+    // a header for a new document of type `doc_type`, w/ all other fields at their defaults
+    pub fn new(doc_type: &str, doc_type_version: u64, doc_type_read_version: u64) -> Self {
+        Self {
+            doc_type: doc_type.to_string(),
+            doc_type_version,
+            doc_type_read_version,
+            ..Self::default()
+        }
+    }
+
+    pub fn parse(input: &[u8]) -> nom::IResult<&[u8], Self, ParseError> {
+        let (input, id) = parse::element_id(input)?;
+        if id != EBML_ID {
+            return Err(nom::Err::Error(ParseError::UnexpectedId(id)));
+        }
+        let (input, len) = parse::element_len(input)?;
+        let len = known_len(len)?;
+        let (rest, mut body) = nom::bytes::streaming::take(len)(input)?;
+
+        let mut header = Self::default();
+        while !body.is_empty() {
+            let (next_body, id) = parse::element_id(body).map_err(complete)?;
+            let (next_body, len) = parse::element_len(next_body).map_err(complete)?;
+            let len = known_len(len)?;
+
+            body = match id {
+                EBML_VERSION_ID => read_uint(next_body, len, &mut header.version)?,
+                EBML_READ_VERSION_ID => read_uint(next_body, len, &mut header.read_version)?,
+                EBML_MAX_ID_LENGTH_ID => read_uint(next_body, len, &mut header.max_id_length)?,
+                EBML_MAX_SIZE_LENGTH_ID => read_uint(next_body, len, &mut header.max_size_length)?,
+                DOC_TYPE_ID => {
+                    let (next_body, doc_type) =
+                        parse::ascii_str(next_body, len).map_err(complete)?;
+                    header.doc_type = doc_type.to_string();
+                    next_body
+                }
+                DOC_TYPE_VERSION_ID => read_uint(next_body, len, &mut header.doc_type_version)?,
+                DOC_TYPE_READ_VERSION_ID => {
+                    read_uint(next_body, len, &mut header.doc_type_read_version)?
+                }
+                // e.g. `DocTypeExtension`, `Void` or `CRC-32` elements
+                _ => {
+                    nom::bytes::streaming::take::<_, _, ParseError>(len)(next_body)
+                        .map_err(complete)?
+                        .0
+                }
+            };
+        }
+
+        Ok((rest, header))
+    }
+
+    // writes the whole `\EBML` element, w/ every field written explicitly (even at its default)
+    pub fn serialize<'a>(&self, output: &'a mut [u8]) -> nom::IResult<&'a mut [u8], usize, ()> {
+        let element = self.to_bytes()?;
+        let (output, _) = serialize::binary(output, &element)?;
+        Ok((output, element.len()))
+    }
+
+    // the serialized `\EBML` element, e.g. to prepend to a separately-written document body
+    pub fn to_bytes(&self) -> Result<Vec<u8>, nom::Err<()>> {
+        if !self.doc_type.is_ascii() {
+            return Err(nom::Err::Error(()));
+        }
+
+        let mut body = Vec::new();
+        push_uint(&mut body, EBML_VERSION_ID, self.version)?;
+        push_uint(&mut body, EBML_READ_VERSION_ID, self.read_version)?;
+        push_uint(&mut body, EBML_MAX_ID_LENGTH_ID, self.max_id_length)?;
+        push_uint(&mut body, EBML_MAX_SIZE_LENGTH_ID, self.max_size_length)?;
+        push_element(&mut body, DOC_TYPE_ID, self.doc_type.as_bytes())?;
+        push_uint(&mut body, DOC_TYPE_VERSION_ID, self.doc_type_version)?;
+        push_uint(
+            &mut body,
+            DOC_TYPE_READ_VERSION_ID,
+            self.doc_type_read_version,
+        )?;
+
+        let mut element = Vec::new();
+        push_element(&mut element, EBML_ID, &body)?;
+        Ok(element)
+    }
+
+    // Checks that a reader implementing `EBML_VERSION` can read the document.
+    #[cfg(feature = "std")]
+    pub fn check_version(&self) -> Result<(), ReaderError> {
+        if self.read_version > EBML_VERSION {
+            return Err(ReaderError::UnsupportedEbmlVersion(self.read_version));
+        }
+        Ok(())
+    }
+}
+
+// the header body is already fully buffered -> any missing data means the header is malformed
+fn complete(error: nom::Err<ParseError>) -> nom::Err<ParseError> {
+    match error {
+        nom::Err::Incomplete(_) => nom::Err::Failure(ParseError::UnexpectedEof),
+        error => error,
+    }
+}
+
+fn known_len(len: Option<u64>) -> Result<usize, nom::Err<ParseError>> {
+    let len = len.ok_or(nom::Err::Failure(ParseError::UnknownSize))?;
+    len.try_into()
+        .map_err(|_| nom::Err::Failure(ParseError::TooLarge(len)))
+}
+
+fn read_uint<'a>(
+    input: &'a [u8],
+    len: usize,
+    field: &mut u64,
+) -> Result<&'a [u8], nom::Err<ParseError>> {
+    let (input, value) = parse::uint(input, len).map_err(complete)?;
+    *field = value;
+    Ok(input)
+}
+
+// appends a whole element (i.e. its ID, length & data) to `output`
+fn push_element(output: &mut Vec<u8>, id: u32, data: &[u8]) -> Result<(), nom::Err<()>> {
+    let mut header = [0u8; 12]; // ID's take at most 4 octets, lengths at most 8
+    let id = NonZeroU32::new(id).ok_or(nom::Err::Error(()))?;
+    let (rest, id_len) = serialize::element_id(&mut header[..], id)?;
+    let (_, len_len) = serialize::element_len(rest, Some(data.len() as u64), None, 8)?;
+
+    output.extend_from_slice(&header[..id_len + len_len]);
+    output.extend_from_slice(data);
+    Ok(())
+}
+
+// appends a uinteger element, using as few octets as its value needs
+fn push_uint(output: &mut Vec<u8>, id: u32, value: u64) -> Result<(), nom::Err<()>> {
+    let bytes = value.to_be_bytes();
+    push_element(output, id, &bytes[(value.leading_zeros() / 8) as usize..])
+}
+
+// Document-wide limits on the VINT widths of element IDs & lengths, as declared by the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentConfig {
+    pub max_id_length: usize,
+    pub max_size_length: usize,
+    // the `DocTypeVersion` that elements' `minver`/`maxver` are checked against (if known)
+    pub doc_type_version: Option<u64>,
+    // whether `CRC-32` elements are checked against the data that they cover
+    pub verify_crc: bool,
+    // whether elements w/ IDs not in the schema are skipped, rather than rejected
+    pub lenient: bool,
+    // whether element lengths must use their smallest VINT encoding (which EBML itself doesn't
+    // require)
+    pub strict_lengths: bool,
+}
+
+impl Default for DocumentConfig {
+    fn default() -> Self {
+        // w/o a header, the document's version is unknown -> elements of any version are accepted
+        let mut config = Self::from(&EbmlHeader::default());
+        config.doc_type_version = None;
+        config
+    }
+}
+
+impl From<&EbmlHeader> for DocumentConfig {
+    fn from(header: &EbmlHeader) -> Self {
+        Self {
+            max_id_length: header.max_id_length.try_into().unwrap_or(usize::MAX),
+            max_size_length: header.max_size_length.try_into().unwrap_or(usize::MAX),
+            doc_type_version: Some(header.doc_type_version),
+            verify_crc: true,
+            lenient: false,
+            strict_lengths: false,
+        }
+    }
+}
+
+// Reads & validates the `\EBML` header at the start of `reader`, leaving the reader positioned at
+// the start of the document body.
+#[cfg(feature = "std")]
+pub fn read_ebml_header<R: std::io::BufRead>(reader: &mut R) -> Result<EbmlHeader, ReaderError> {
+    read_ebml_header_with_len(reader).map(|(header, _)| header)
+}
+
+// Like `read_ebml_header`, but also returns the header's length in octets (i.e. the stream
+// position of the document body).
+#[cfg(feature = "std")]
+pub fn read_ebml_header_with_len<R: std::io::BufRead>(
+    reader: &mut R,
+) -> Result<(EbmlHeader, u64), ReaderError> {
+    let mut pending = Vec::new();
+    let mut position = 0;
+    let header = parse_buffered(reader, &mut pending, &mut position, |stream| {
+        EbmlHeader::parse(stream).map_err(nom::Err::convert)
+    })?;
+
+    header.check_version()?;
+    Ok((header, position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: [u8; 20] = [
+        0x1A, 0x45, 0xDF, 0xA3, // EBML element ID
+        0x8F, // EBML length = 15
+        0x42, 0x86, 0x81, 0x01, // EBMLVersion = 1
+        0x42, 0xF7, 0x81, 0x01, // EBMLReadVersion = 1
+        0x42, 0x82, 0x84, 0x77, 0x65, 0x62, 0x6D, // DocType = "webm"
+    ];
+
+    #[test]
+    fn read_header() {
+        let mut stream = [&HEADER[..], &[0xEC, 0x80][..]].concat();
+        let mut reader = &stream[..];
+
+        let header = read_ebml_header(&mut reader).unwrap();
+        assert_eq!(
+            header,
+            EbmlHeader {
+                doc_type: "webm".to_string(),
+                ..EbmlHeader::default()
+            }
+        );
+        assert_eq!(reader, &[0xEC, 0x80]);
+
+        stream[4] = 0x80; // empty header -> all defaults
+        assert_eq!(
+            EbmlHeader::parse(&stream[..5]),
+            Ok((&[][..], EbmlHeader::default()))
+        );
+    }
+
+    #[test]
+    fn write_header() {
+        let header = EbmlHeader::new("webm", 4, 2);
+        let bytes = header.to_bytes().unwrap();
+        assert_eq!(
+            bytes,
+            [
+                0x1A, 0x45, 0xDF, 0xA3, // EBML element ID
+                0x9F, // EBML length = 31
+                0x42, 0x86, 0x81, 0x01, // EBMLVersion = 1
+                0x42, 0xF7, 0x81, 0x01, // EBMLReadVersion = 1
+                0x42, 0xF2, 0x81, 0x04, // EBMLMaxIDLength = 4
+                0x42, 0xF3, 0x81, 0x08, // EBMLMaxSizeLength = 8
+                0x42, 0x82, 0x84, 0x77, 0x65, 0x62, 0x6D, // DocType = "webm"
+                0x42, 0x87, 0x81, 0x04, // DocTypeVersion = 4
+                0x42, 0x85, 0x81, 0x02, // DocTypeReadVersion = 2
+            ]
+        );
+        assert_eq!(EbmlHeader::parse(&bytes), Ok((&[][..], header.clone())));
+
+        let mut output = [0xFFu8; 38];
+        let (rest, len) = header.serialize(&mut output[..]).unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(rest, &[0xFF; 2]);
+        assert_eq!(output[..len], bytes[..]);
+    }
+
+    #[test]
+    fn write_header_errors() {
+        assert_eq!(
+            EbmlHeader::new("wébm", 1, 1).to_bytes(),
+            Err(nom::Err::Error(()))
+        );
+        let mut output = [0u8; 8];
+        assert!(matches!(
+            EbmlHeader::new("webm", 1, 1).serialize(&mut output[..]),
+            Err(nom::Err::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn parse_header_errors() {
+        assert_eq!(
+            EbmlHeader::parse(&[0xEC, 0x80]),
+            Err(nom::Err::Error(ParseError::UnexpectedId(0xEC)))
+        );
+        assert_eq!(
+            EbmlHeader::parse(&[0x1A, 0x45, 0xDF, 0xA3, 0xFF]),
+            Err(nom::Err::Failure(ParseError::UnknownSize))
+        );
+        // the `EBMLVersion` element runs past the end of the header
+        assert_eq!(
+            EbmlHeader::parse(&[0x1A, 0x45, 0xDF, 0xA3, 0x83, 0x42, 0x86, 0x81]),
+            Err(nom::Err::Failure(ParseError::UnexpectedEof))
+        );
+    }
+
+    #[test]
+    fn read_header_small_buffer() {
+        let stream = [&HEADER[..], &[0xEC, 0x80][..]].concat();
+        let mut reader = std::io::BufReader::with_capacity(3, &stream[..]);
+
+        let (header, len) = read_ebml_header_with_len(&mut reader).unwrap();
+        assert_eq!(header.doc_type, "webm");
+        assert_eq!(len, HEADER.len() as u64);
+        let mut rest = Vec::new();
+        std::io::Read::read_to_end(&mut reader, &mut rest).unwrap();
+        assert_eq!(rest, [0xEC, 0x80]);
+    }
+
+    #[test]
+    fn read_header_unsupported_version() {
+        let mut stream = HEADER;
+        stream[12] = 0x02; // EBMLReadVersion = 2
+        let mut reader = &stream[..];
+
+        assert!(matches!(
+            read_ebml_header(&mut reader),
+            Err(ReaderError::UnsupportedEbmlVersion(2))
+        ));
+    }
+}
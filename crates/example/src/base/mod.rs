@@ -1,3 +1,12 @@
+#[cfg(feature = "async")]
+pub mod async_reader;
+#[cfg(all(feature = "serde", feature = "std"))]
+pub mod de;
 pub mod element_defs;
+pub mod header;
 pub mod parser;
 pub mod stream;
+#[cfg(feature = "tree")]
+pub mod tree;
+#[cfg(feature = "std")]
+pub mod writer;
@@ -11,10 +11,11 @@ use core::ops::Bound;
 pub struct VoidDef;
 
 impl ElementDef for VoidDef {
+    const NAME: &'static str = "Void";
     const ID: u32 = 0xEC;
     const PATH: &'static str = "\\(-\\)Void";
 
-    const MIN_OCCURS: usize = 1;
+    const MIN_OCCURS: usize = 0;
     const MAX_OCCURS: Option<usize> = None;
     const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
     const RECURRING: bool = false;
@@ -31,6 +32,7 @@ impl BinaryElementDef for VoidDef {
 pub struct FilesDef;
 
 impl ElementDef for FilesDef {
+    const NAME: &'static str = "Files";
     const ID: u32 = 0x1946696C;
     const PATH: &'static str = "\\Files";
 
@@ -45,6 +47,7 @@ impl ElementDef for FilesDef {
 impl MasterElementDef for FilesDef {
     const UNKNOWN_SIZE_ALLOWED: bool = false;
     const RECURSIVE: bool = false;
+    const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[(0x6146, 1)];
 }
 
 // parent: Files
@@ -52,6 +55,7 @@ impl MasterElementDef for FilesDef {
 pub struct FileDef;
 
 impl ElementDef for FileDef {
+    const NAME: &'static str = "File";
     const ID: u32 = 0x6146;
     const PATH: &'static str = "\\Files\\File";
 
@@ -66,6 +70,8 @@ impl ElementDef for FileDef {
 impl MasterElementDef for FileDef {
     const UNKNOWN_SIZE_ALLOWED: bool = false;
     const RECURSIVE: bool = false;
+    const MANDATORY_CHILDREN: &'static [(u32, usize)] =
+        &[(0x614E, 1), (0x464D, 1), (0x4654, 1), (0x4664, 1)];
 }
 
 // parent: File
@@ -73,6 +79,7 @@ impl MasterElementDef for FileDef {
 pub struct FileNameDef;
 
 impl ElementDef for FileNameDef {
+    const NAME: &'static str = "FileName";
     const ID: u32 = 0x614E;
     const PATH: &'static str = "\\Files\\File\\FileName";
 
@@ -93,6 +100,7 @@ impl Utf8ElementDef for FileNameDef {
 pub struct MimeTypeDef;
 
 impl ElementDef for MimeTypeDef {
+    const NAME: &'static str = "MimeType";
     const ID: u32 = 0x464D;
     const PATH: &'static str = "\\Files\\File\\MimeType";
 
@@ -113,6 +121,7 @@ impl StringElementDef for MimeTypeDef {
 pub struct ModificationTimestampDef;
 
 impl ElementDef for ModificationTimestampDef {
+    const NAME: &'static str = "ModificationTimestamp";
     const ID: u32 = 0x4654;
     const PATH: &'static str = "\\Files\\File\\ModificationTimestamp";
 
@@ -134,6 +143,7 @@ impl DateElementDef for ModificationTimestampDef {
 pub struct DataDef;
 
 impl ElementDef for DataDef {
+    const NAME: &'static str = "Data";
     const ID: u32 = 0x4664;
     const PATH: &'static str = "\\Files\\File\\Data";
 
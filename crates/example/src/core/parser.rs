@@ -1,22 +1,30 @@
 use crate::base::element_defs::ElementDef;
 #[allow(unused_imports)]
+use crate::base::header::DocumentConfig;
+#[allow(unused_imports)]
 use crate::base::parser::{
-    BoundTo, ElementReader, ElementState, IntoReader, NextStateNavigation, ReaderError,
-    SkipStateNavigation, StateDataParser, StateError,
+    check_length, check_path, check_version, skip_unknown_element, BoundTo, CrcAccumulator,
+    DocumentConfigured, ElementLength, ElementState, NextStateNavigation, SkipStateNavigation,
+    StateDataParser, StateError, CRC32_ID,
 };
+// the readers need `std::io`
+#[cfg(feature = "std")]
+use crate::base::parser::{ElementReader, IntoReader, ReaderError};
 #[allow(unused_imports)]
 use crate::base::stream::{parse, serialize, stream_diff};
 use crate::core::element_defs;
 #[allow(unused_imports)]
 use crate::{
-    impl_from_readers_for_states, impl_from_subreaders_for_readers, impl_from_substates_for_states,
-    impl_into_reader, impl_next_state_navigation, impl_skip_state_navigation,
+    impl_crc_accumulator, impl_document_configured, impl_from_readers_for_states,
+    impl_from_subreaders_for_readers, impl_from_substates_for_states, impl_into_reader,
+    impl_next_state_navigation, impl_skip_state_navigation,
 };
 
 use enum_dispatch::enum_dispatch;
 
-use core::convert::{From, TryInto};
+use core::convert::From;
 use core::marker::PhantomData;
+#[cfg(feature = "std")]
 use std::io::BufRead;
 
 // Top-Level Reader/State Enums #########################################################################
@@ -26,11 +34,12 @@ use std::io::BufRead;
 #[enum_dispatch(_DocumentNextStates)]
 #[enum_dispatch(FilesNextStates)]
 #[enum_dispatch(FileNextStates)]
-#[enum_dispatch(Readers<R>)]
-#[enum_dispatch(VoidPrevReaders<R>)]
-#[enum_dispatch(_DocumentNextReaders<R>)]
-#[enum_dispatch(FilesNextReaders<R>)]
-#[enum_dispatch(FileNextReaders<R>)]
+#[cfg_attr(feature = "std", enum_dispatch(Readers<R>))]
+#[cfg_attr(feature = "std", enum_dispatch(VoidPrevReaders<R>))]
+#[cfg_attr(feature = "std", enum_dispatch(_DocumentNextReaders<R>))]
+#[cfg_attr(feature = "std", enum_dispatch(FilesNextReaders<R>))]
+#[cfg_attr(feature = "std", enum_dispatch(FileNextReaders<R>))]
+#[allow(dead_code)]
 trait BlankTrait {}
 
 #[enum_dispatch]
@@ -45,6 +54,7 @@ pub enum States {
     Data(DataState),
 }
 
+#[cfg(feature = "std")]
 #[enum_dispatch]
 pub enum Readers<R> {
     _Document(_DocumentReader<R>),
@@ -57,6 +67,7 @@ pub enum Readers<R> {
     Data(DataReader<R>),
 }
 
+#[cfg(feature = "std")]
 impl_into_reader!(
     States,
     Readers,
@@ -72,6 +83,7 @@ impl_into_reader!(
     ]
 );
 
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(
     Readers,
     States,
@@ -87,10 +99,80 @@ impl_from_readers_for_states!(
     ]
 );
 
+impl States {
+    // the ID of the element being read, or `None` at the top level of the document
+    pub fn element_id(&self) -> Option<u32> {
+        match self {
+            Self::_Document(_) => None,
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::ID),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::ID),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::ID),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::ID),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::ID),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::ID)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::ID),
+        }
+    }
+
+    pub fn element_name(&self) -> Option<&'static str> {
+        match self {
+            Self::_Document(_) => None,
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::NAME),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::NAME),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::NAME),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::NAME),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::NAME),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::NAME)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::NAME),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> Readers<R> {
+    // the ID of the element being read, or `None` at the top level of the document
+    pub fn element_id(&self) -> Option<u32> {
+        match self {
+            Self::_Document(_) => None,
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::ID),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::ID),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::ID),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::ID),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::ID),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::ID)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::ID),
+        }
+    }
+
+    pub fn element_name(&self) -> Option<&'static str> {
+        match self {
+            Self::_Document(_) => None,
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::NAME),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::NAME),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::NAME),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::NAME),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::NAME),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::NAME)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::NAME),
+        }
+    }
+}
+
 // _Document Objects #########################################################################
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct _DocumentState;
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct _DocumentState {
+    pub config: DocumentConfig,
+}
+#[cfg(feature = "std")]
 pub type _DocumentReader<R> = ElementReader<R, _DocumentState>;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -100,6 +182,7 @@ pub enum _DocumentNextStates {
     Files(FilesState),
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum _DocumentNextReaders<R> {
@@ -108,9 +191,12 @@ pub enum _DocumentNextReaders<R> {
 }
 
 impl_from_substates_for_states!(_DocumentNextStates, States, [Void, Files]);
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!(_DocumentNextReaders, Readers, [Void, Files]);
 
+#[cfg(feature = "std")]
 impl_into_reader!(_DocumentNextStates, _DocumentNextReaders, [Void, Files]);
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(_DocumentNextReaders, _DocumentNextStates, [Void, Files]);
 
 impl_next_state_navigation!(
@@ -119,25 +205,68 @@ impl_next_state_navigation!(
     [(Void, VoidState), (Files, FilesState)]
 );
 
+#[cfg(feature = "std")]
 impl<R: BufRead> _DocumentReader<R> {
     pub fn new(reader: R) -> Self {
-        Self {
-            reader,
-            state: _DocumentState,
-        }
+        Self::with_config(reader, DocumentConfig::default())
+    }
+
+    pub fn with_config(reader: R, config: DocumentConfig) -> Self {
+        _DocumentState { config }.into_reader(reader, 0)
+    }
+
+    // the ID of the next top-level element, without moving into it -> `None` at the end of the
+    // stream
+    pub fn peek_id(&mut self) -> Result<Option<u32>, ReaderError> {
+        self.peek_next_id()
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> IntoReader<R> for _DocumentState {
     type Reader = _DocumentReader<R>;
-    fn into_reader(self, reader: R) -> _DocumentReader<R> {
-        _DocumentReader::new(reader)
+    fn into_reader(self, reader: R, position: u64) -> _DocumentReader<R> {
+        ElementReader {
+            reader,
+            state: self,
+            pending: Vec::new(),
+            position,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> From<_DocumentReader<R>> for _DocumentState {
+    fn from(reader: _DocumentReader<R>) -> Self {
+        reader.state
+    }
+}
+
+impl DocumentConfigured for _DocumentState {
+    fn document_config(&self) -> DocumentConfig {
+        self.config
+    }
+
+    fn document_config_mut(&mut self) -> &mut DocumentConfig {
+        &mut self.config
+    }
+}
+
+// the document itself has no `CRC-32` elements to check
+impl CrcAccumulator for _DocumentState {
+    fn update_crc(&mut self, _data: &[u8]) {}
+
+    fn start_crc(&mut self, _expected: u32) {}
+
+    fn crc_pending(&self) -> bool {
+        false
     }
 }
 
 // Files Objects #########################################################################
 
 pub type FilesState = ElementState<element_defs::FilesDef, _DocumentState>;
+#[cfg(feature = "std")]
 pub type FilesReader<R> = ElementReader<R, FilesState>;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -148,6 +277,7 @@ pub enum FilesNextStates {
     Parent(_DocumentState),
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum FilesNextReaders<R> {
@@ -157,16 +287,21 @@ pub enum FilesNextReaders<R> {
 }
 
 impl_from_substates_for_states!(FilesNextStates, States, [Void, File, Parent]);
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!(FilesNextReaders, Readers, [Void, File, Parent]);
 
+#[cfg(feature = "std")]
 impl_into_reader!(FilesNextStates, FilesNextReaders, [Void, File, Parent]);
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(FilesNextReaders, FilesNextStates, [Void, File, Parent]);
 
 impl FilesState {
-    pub fn new(bytes_left: usize, parent_state: _DocumentState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: _DocumentState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -179,15 +314,22 @@ impl_next_state_navigation!(
     [(Void, VoidState), (File, FileState)]
 );
 
+#[cfg(feature = "std")]
 impl<R: BufRead> FilesReader<R> {
     pub fn new(reader: R, state: FilesState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 // File Objects #########################################################################
 
 pub type FileState = ElementState<element_defs::FileDef, FilesState>;
+#[cfg(feature = "std")]
 pub type FileReader<R> = ElementReader<R, FileState>;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -201,6 +343,7 @@ pub enum FileNextStates {
     Parent(FilesState),
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum FileNextReaders<R> {
@@ -224,6 +367,7 @@ impl_from_substates_for_states!(
         Parent
     ]
 );
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!(
     FileNextReaders,
     Readers,
@@ -237,6 +381,7 @@ impl_from_subreaders_for_readers!(
     ]
 );
 
+#[cfg(feature = "std")]
 impl_into_reader!(
     FileNextStates,
     FileNextReaders,
@@ -249,6 +394,7 @@ impl_into_reader!(
         Parent
     ]
 );
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(
     FileNextReaders,
     FileNextStates,
@@ -263,10 +409,12 @@ impl_from_readers_for_states!(
 );
 
 impl FileState {
-    pub fn new(bytes_left: usize, parent_state: FilesState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FilesState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -285,22 +433,31 @@ impl_next_state_navigation!(
     ]
 );
 
+#[cfg(feature = "std")]
 impl<R: BufRead> FileReader<R> {
     pub fn new(reader: R, state: FileState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 // FileName Objects #########################################################################
 
 pub type FileNameState = ElementState<element_defs::FileNameDef, FileState>;
+#[cfg(feature = "std")]
 pub type FileNameReader<R> = ElementReader<R, FileNameState>;
 
 impl FileNameState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -309,22 +466,31 @@ impl FileNameState {
 impl_skip_state_navigation!(FileNameState, FileState);
 impl_next_state_navigation!(FileNameState, FileState, []);
 
+#[cfg(feature = "std")]
 impl<R: BufRead> FileNameReader<R> {
     pub fn new(reader: R, state: FileNameState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 // MimeType Objects #########################################################################
 
 pub type MimeTypeState = ElementState<element_defs::MimeTypeDef, FileState>;
+#[cfg(feature = "std")]
 pub type MimeTypeReader<R> = ElementReader<R, MimeTypeState>;
 
 impl MimeTypeState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -333,9 +499,15 @@ impl MimeTypeState {
 impl_skip_state_navigation!(MimeTypeState, FileState);
 impl_next_state_navigation!(MimeTypeState, FileState, []);
 
+#[cfg(feature = "std")]
 impl<R: BufRead> MimeTypeReader<R> {
     pub fn new(reader: R, state: MimeTypeState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
@@ -343,13 +515,16 @@ impl<R: BufRead> MimeTypeReader<R> {
 
 pub type ModificationTimestampState =
     ElementState<element_defs::ModificationTimestampDef, FileState>;
+#[cfg(feature = "std")]
 pub type ModificationTimestampReader<R> = ElementReader<R, ModificationTimestampState>;
 
 impl ModificationTimestampState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -358,22 +533,31 @@ impl ModificationTimestampState {
 impl_skip_state_navigation!(ModificationTimestampState, FileState);
 impl_next_state_navigation!(ModificationTimestampState, FileState, []);
 
+#[cfg(feature = "std")]
 impl<R: BufRead> ModificationTimestampReader<R> {
     pub fn new(reader: R, state: ModificationTimestampState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 // Data Objects #########################################################################
 
 pub type DataState = ElementState<element_defs::DataDef, FileState>;
+#[cfg(feature = "std")]
 pub type DataReader<R> = ElementReader<R, DataState>;
 
 impl DataState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -382,15 +566,22 @@ impl DataState {
 impl_skip_state_navigation!(DataState, FileState);
 impl_next_state_navigation!(DataState, FileState, []);
 
+#[cfg(feature = "std")]
 impl<R: BufRead> DataReader<R> {
     pub fn new(reader: R, state: DataState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 // Void Objects #########################################################################
 
 pub type VoidState = ElementState<element_defs::VoidDef, VoidPrevStates>;
+#[cfg(feature = "std")]
 pub type VoidReader<R> = ElementReader<R, VoidState>;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -400,6 +591,7 @@ pub enum VoidPrevStates {
     Files(FilesState),
     File(FileState),
 }
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum VoidPrevReaders<R> {
@@ -409,16 +601,23 @@ pub enum VoidPrevReaders<R> {
 }
 
 impl_from_substates_for_states!(VoidPrevStates, States, [_Document, Files, File]);
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!(VoidPrevReaders, Readers, [_Document, Files, File]);
 
+#[cfg(feature = "std")]
 impl_into_reader!(VoidPrevStates, VoidPrevReaders, [_Document, Files, File]);
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(VoidPrevReaders, VoidPrevStates, [_Document, Files, File]);
+impl_document_configured!(VoidPrevStates, [_Document, Files, File]);
+impl_crc_accumulator!(VoidPrevStates, [_Document, Files, File]);
 
 impl VoidState {
-    pub fn new(bytes_left: usize, parent_state: VoidPrevStates) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: VoidPrevStates) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
@@ -427,9 +626,15 @@ impl VoidState {
 impl_skip_state_navigation!(VoidState, VoidPrevStates);
 impl_next_state_navigation!(VoidState, VoidPrevStates, []);
 
+#[cfg(feature = "std")]
 impl<R: BufRead> VoidReader<R> {
     pub fn new(reader: R, state: VoidState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
@@ -440,14 +645,19 @@ mod tests {
     use super::*;
     use rstest::*;
 
+    // a single occurrence of each of the given child elements
+    fn occurrences(ids: &[u32]) -> std::collections::BTreeMap<u32, usize> {
+        ids.iter().map(|&id| (id, 1)).collect()
+    }
+
     mod document {
         use super::*;
 
         #[rstest(element, source, expt_result,
             case(
-                _DocumentState,
+                _DocumentState::default(),
                 &[0x19, 0x46, 0x69, 0x6C, 0x82, 0xFF, 0xFF, 0xFF],
-                (&[0xFF, 0xFF, 0xFF][..], _DocumentNextStates::Files(FilesState{bytes_left: 2, parent_state: _DocumentState, _phantom: PhantomData}))
+                (&[0xFF, 0xFF, 0xFF][..], _DocumentNextStates::Files(FilesState{bytes_left: ElementLength::Known(2), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
         )]
         fn state_next(
@@ -464,14 +674,14 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                FilesState{bytes_left: 5, parent_state: _DocumentState, _phantom: PhantomData},
+                FilesState{bytes_left: ElementLength::Known(5), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0x61, 0x46, 0x82, 0xFF, 0xFF, 0xFF],
-                (&[0xFF, 0xFF, 0xFF][..], FilesNextStates::File(FileState{bytes_left: 2, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}))
+                (&[0xFF, 0xFF, 0xFF][..], FilesNextStates::File(FileState{bytes_left: ElementLength::Known(2), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: occurrences(&[0x6146]), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
             case(
-                FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData},
+                FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: occurrences(&[0x6146]), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF],
-                (&[0xFF, 0xFF, 0xFF][..], FilesNextStates::Parent(_DocumentState))
+                (&[0xFF, 0xFF, 0xFF][..], FilesNextStates::Parent(_DocumentState::default()))
             ),
         )]
         fn state_next(
@@ -484,9 +694,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                FilesState{bytes_left: 5, parent_state: _DocumentState, _phantom: PhantomData},
+                FilesState{bytes_left: ElementLength::Known(5), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0x61, 0x4E, 0x82, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], _DocumentState)
+                (&[0xFF][..], _DocumentState::default())
             ),
         )]
         fn state_skip(
@@ -503,29 +713,29 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                FileState{bytes_left: 5, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData},
+                FileState{bytes_left: ElementLength::Known(5), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0x61, 0x4E, 0x82, 0xFF, 0xFF],
-                (&[0xFF, 0xFF][..], FileNextStates::FileName(FileNameState{bytes_left: 2, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData}))
+                (&[0xFF, 0xFF][..], FileNextStates::FileName(FileNameState{bytes_left: ElementLength::Known(2), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: occurrences(&[0x614E]), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
             case(
-                FileState{bytes_left: 5, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData},
+                FileState{bytes_left: ElementLength::Known(5), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0x46, 0x4D, 0x82, 0xFF, 0xFF],
-                (&[0xFF, 0xFF][..], FileNextStates::MimeType(MimeTypeState{bytes_left: 2, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData}))
+                (&[0xFF, 0xFF][..], FileNextStates::MimeType(MimeTypeState{bytes_left: ElementLength::Known(2), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: occurrences(&[0x464D]), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
             case(
-                FileState{bytes_left: 5, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData},
-                &[0x46, 0x54, 0x82, 0xFF, 0xFF],
-                (&[0xFF, 0xFF][..], FileNextStates::ModificationTimestamp(ModificationTimestampState{bytes_left: 2, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData}))
+                FileState{bytes_left: ElementLength::Known(11), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
+                &[0x46, 0x54, 0x88, 0xFF, 0xFF],
+                (&[0xFF, 0xFF][..], FileNextStates::ModificationTimestamp(ModificationTimestampState{bytes_left: ElementLength::Known(8), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: occurrences(&[0x4654]), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
             case(
-                FileState{bytes_left: 5, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData},
+                FileState{bytes_left: ElementLength::Known(5), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0x46, 0x64, 0x82, 0xFF, 0xFF],
-                (&[0xFF, 0xFF][..], FileNextStates::Data(DataState{bytes_left: 2, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData}))
+                (&[0xFF, 0xFF][..], FileNextStates::Data(DataState{bytes_left: ElementLength::Known(2), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: occurrences(&[0x4664]), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
             case(
-                FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData},
+                FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: occurrences(&[0x614E, 0x464D, 0x4654, 0x4664]), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF],
-                (&[0xFF, 0xFF][..], FileNextStates::Parent(FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}))
+                (&[0xFF, 0xFF][..], FileNextStates::Parent(FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}))
             ),
         )]
         fn state_next(
@@ -538,9 +748,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                FileState{bytes_left: 5, parent_state: FilesState{bytes_left: 1, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData},
+                FileState{bytes_left: ElementLength::Known(5), parent_state: FilesState{bytes_left: ElementLength::Known(1), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0x61, 0x4E, 0x82, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FilesState{bytes_left: 1, parent_state: _DocumentState, _phantom: PhantomData})
+                (&[0xFF][..], FilesState{bytes_left: ElementLength::Known(1), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData})
             ),
         )]
         fn state_skip(
@@ -557,9 +767,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                FileNameState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                FileNameState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}),
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}),
             ),
         )]
         fn state_next(
@@ -572,9 +782,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                FileNameState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                FileNameState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData})
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData})
             ),
         )]
         fn state_skip(
@@ -591,9 +801,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                MimeTypeState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                MimeTypeState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}),
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}),
             ),
         )]
         fn state_next(
@@ -606,9 +816,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                MimeTypeState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                MimeTypeState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData})
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData})
             ),
         )]
         fn state_skip(
@@ -625,9 +835,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                ModificationTimestampState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                ModificationTimestampState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}),
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}),
             ),
         )]
         fn state_next(
@@ -640,9 +850,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                ModificationTimestampState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                ModificationTimestampState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData})
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData})
             ),
         )]
         fn state_skip(
@@ -659,9 +869,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                DataState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                DataState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}),
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}),
             ),
         )]
         fn state_next(
@@ -674,9 +884,9 @@ mod tests {
 
         #[rstest(element, source, expt_result,
             case(
-                DataState{bytes_left: 3, parent_state: FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData}, _phantom: PhantomData},
+                DataState{bytes_left: ElementLength::Known(3), parent_state: FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData},
                 &[0xFF, 0xFF, 0xFF, 0xFF],
-                (&[0xFF][..], FileState{bytes_left: 0, parent_state: FilesState{bytes_left: 0, parent_state: _DocumentState, _phantom: PhantomData}, _phantom: PhantomData})
+                (&[0xFF][..], FileState{bytes_left: ElementLength::Known(0), parent_state: FilesState{bytes_left: ElementLength::Known(0), parent_state: _DocumentState::default(), occurrences: Default::default(), crc: None, _phantom: PhantomData}, occurrences: Default::default(), crc: None, _phantom: PhantomData})
             ),
         )]
         fn state_skip(
@@ -86,7 +86,7 @@ fn basic_traversal() {
         reader = match reader {
             parser::Readers::_Document(r) => match r.next() {
                 Ok(r_next) => r_next.into(),
-                Err(ReaderError::Parse(nom::Err::Incomplete(_))) => break,
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
                 Err(_) => panic!(), // in an actual function, this should return the error
             },
             parser::Readers::Void(r) => r.next().unwrap().into(),
@@ -123,7 +123,7 @@ fn find_all_element_instances() {
         reader = match reader {
             parser::Readers::_Document(r) => match r.next() {
                 Ok(r_next) => r_next.into(),
-                Err(ReaderError::Parse(nom::Err::Incomplete(_))) => break,
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
                 Err(_) => panic!(), // in an actual function, this should return the error
             },
             parser::Readers::Void(r) => r.next().unwrap().into(),
@@ -5,12 +5,8 @@ fn main() {
         .map(std::path::PathBuf::from)
         .expect("no env variable 'CARGO_MANIFEST_DIR'");
 
-    let schema_file = std::io::BufReader::new(
-        std::fs::File::open(cargo_path.join("eg_schema.xml")).expect("couldn't open schema file"),
-    );
-
-    Builder::new(schema_file)
-        .expect("couldn't parse schema file")
+    Builder::from_path(cargo_path.join("eg_schema.xml"))
+        .expect("couldn't read schema file")
         .generate()
         .expect("couldn't make parser writer")
         .write_package(cargo_path.join("parser"))
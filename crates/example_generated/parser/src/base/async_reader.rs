@@ -0,0 +1,214 @@
+use crate::base::element_defs::ElementDef;
+use crate::base::parser::{
+    incomplete_as_eof, ElementState, NextStateNavigation, ParserMarker, ReaderError,
+    SkipStateNavigation, StateDataParser, StateError,
+};
+use crate::base::stream::stream_diff;
+
+use core::convert::TryFrom;
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::{ready, Poll};
+use tokio::io::AsyncBufRead;
+
+// Navigates a document like `ElementReader`, through the same states, but awaits its data rather
+// than blocking on it.
+#[derive(Debug, PartialEq)]
+pub struct AsyncElementReader<R, S> {
+    pub reader: R,
+    pub state: S,
+    // data pulled out of `reader` but not yet parsed
+    pending: Vec<u8>,
+    // the number of octets parsed so far
+    position: u64,
+}
+
+impl<R, S> AsyncElementReader<R, S> {
+    // starts reading from `state`, e.g. a `_DocumentState` at the start of a document
+    pub fn new(reader: R, state: S) -> Self {
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
+    }
+
+    // the offset of the next octet to be parsed, from the start of the stream
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    // splits off the state, e.g. to match on the variants of a `NextStates` enum; the state (or
+    // one of its variants) is put back w/ `with_state`
+    pub fn into_parts(self) -> (S, AsyncElementReader<R, ()>) {
+        let Self {
+            reader,
+            state,
+            pending,
+            position,
+        } = self;
+        (
+            state,
+            AsyncElementReader {
+                reader,
+                state: (),
+                pending,
+                position,
+            },
+        )
+    }
+
+    pub fn with_state<T>(self, state: T) -> AsyncElementReader<R, T> {
+        AsyncElementReader {
+            reader: self.reader,
+            state,
+            pending: self.pending,
+            position: self.position,
+        }
+    }
+
+    // converts the state, e.g. from a `NextStates` enum into `States`
+    pub fn map_state<T>(self, f: impl FnOnce(S) -> T) -> AsyncElementReader<R, T> {
+        let (state, reader) = self.into_parts();
+        reader.with_state(f(state))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin, S> AsyncElementReader<R, S> {
+    // Moves data from the reader into `pending`; returns false once the reader is exhausted. Only
+    // takes what's needed (as with the sync `pull`), so that `pending` never holds data past the
+    // current parse.
+    async fn pull(&mut self, needed: nom::Needed) -> std::io::Result<bool> {
+        let reader = &mut self.reader;
+        let pending = &mut self.pending;
+        poll_fn(|cx| {
+            let buf = ready!(Pin::new(&mut *reader).poll_fill_buf(cx))?;
+            let len = match needed {
+                nom::Needed::Size(size) => size.get().min(buf.len()),
+                nom::Needed::Unknown => 1.min(buf.len()),
+            };
+            pending.extend_from_slice(&buf[..len]);
+            Pin::new(&mut *reader).consume(len);
+            Poll::Ready(Ok(len > 0))
+        })
+        .await
+    }
+
+    // moves `len` octets past the front of the stream, consuming them straight from the reader
+    // rather than buffering them
+    async fn skip_buffered(&mut self, mut len: u64) -> Result<(), ReaderError> {
+        let amt =
+            usize::try_from(len).map_or(self.pending.len(), |len| len.min(self.pending.len()));
+        self.pending.drain(..amt);
+        self.position += amt as u64;
+        len -= amt as u64;
+
+        while len > 0 {
+            let reader = &mut self.reader;
+            let amt = poll_fn(|cx| {
+                let buf = ready!(Pin::new(&mut *reader).poll_fill_buf(cx))?;
+                let amt = usize::try_from(len).map_or(buf.len(), |len| len.min(buf.len()));
+                Pin::new(&mut *reader).consume(amt);
+                Poll::Ready(Ok::<_, std::io::Error>(amt))
+            })
+            .await?;
+            if amt == 0 {
+                return Err(ReaderError::UnexpectedEof);
+            }
+            self.position += amt as u64;
+            len -= amt as u64;
+        }
+
+        Ok(())
+    }
+
+    // parses from the front of the pending data, pulling in more whenever `parse` reports that its
+    // input is incomplete
+    async fn parse_buffered<T>(
+        &mut self,
+        parse: impl Fn(&[u8]) -> nom::IResult<&[u8], T, StateError>,
+    ) -> Result<T, ReaderError> {
+        loop {
+            let needed = match parse(&self.pending) {
+                Err(nom::Err::Incomplete(needed)) => needed,
+                // skips an element too large to buffer, & then parses again from the element after it
+                Err(nom::Err::Failure(StateError::SkipTooLarge {
+                    len, stream_left, ..
+                })) => {
+                    let header_len = self.pending.len() - stream_left;
+                    self.skip_buffered(header_len as u64 + len).await?;
+                    continue;
+                }
+                _ => break,
+            };
+            if !self.pull(needed).await? {
+                break;
+            }
+        }
+
+        let (next_stream, value) =
+            parse(&self.pending).map_err(|error| ReaderError::Parse(error, self.position))?;
+        let stream_dist = stream_diff(&self.pending, next_stream);
+        self.position += stream_dist as u64;
+        self.pending.drain(..stream_dist);
+
+        Ok(value)
+    }
+}
+
+impl<R: AsyncBufRead + Unpin, S: NextStateNavigation + Clone> AsyncElementReader<R, S> {
+    pub async fn next(mut self) -> Result<AsyncElementReader<R, S::NextStates>, ReaderError> {
+        let state = self.state.clone();
+        let next_state = self
+            .parse_buffered(|stream| state.clone().next(stream))
+            .await?;
+        Ok(self.with_state(next_state))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin, S: SkipStateNavigation + Clone> AsyncElementReader<R, S> {
+    pub async fn skip(self) -> Result<AsyncElementReader<R, S::PrevStates>, ReaderError> {
+        let (state, mut reader) = self.into_parts();
+        let prev_state = match state.skip_unread() {
+            // the data isn't needed -> streamed past, rather than buffered all at once
+            Ok((len, prev_state)) => {
+                reader.skip_buffered(len).await?;
+                prev_state
+            }
+            Err(state) => {
+                reader
+                    .parse_buffered(|stream| state.clone().skip(stream))
+                    .await?
+            }
+        };
+        Ok(reader.with_state(prev_state))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin, E: ElementDef, S> AsyncElementReader<R, ElementState<E, S>> {
+    // reads the element's data, w/o moving past it (as with `ReaderDataParser::read`)
+    pub async fn read<'a, M: ParserMarker, T: 'a>(&'a mut self) -> Result<T, ReaderError>
+    where
+        ElementState<E, S>: StateDataParser<'a, M, T> + Clone,
+    {
+        let position = self.position;
+        let len = self
+            .state
+            .known_bytes_left()
+            .map_err(|error| ReaderError::Parse(error, position))?;
+        while self.pending.len() < len {
+            let needed = nom::Needed::new(len - self.pending.len());
+            if !self.pull(needed).await? {
+                return Err(ReaderError::UnexpectedEof);
+            }
+        }
+
+        let (_, (_, data)) = self
+            .state
+            .clone()
+            .read(&self.pending)
+            .map_err(incomplete_as_eof(position))?;
+        Ok(data)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::base::parser::{ElementEvent, ElementValue, ReaderError};
+
+// an element read in full, along with all of its children
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub id: u32,
+    pub name: &'static str,
+    pub content: ElementContent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementContent {
+    // master elements hold their children, in the order that they were read
+    Children(Vec<Element>),
+    Value(ElementValue),
+}
+
+impl Element {
+    // the element's children, or none for non-master elements
+    pub fn children(&self) -> &[Element] {
+        match &self.content {
+            ElementContent::Children(children) => children,
+            ElementContent::Value(_) => &[],
+        }
+    }
+
+    pub fn value(&self) -> Option<&ElementValue> {
+        match &self.content {
+            ElementContent::Children(_) => None,
+            ElementContent::Value(value) => Some(value),
+        }
+    }
+
+    // the children w/ the given name, e.g. `.children_named("File").nth(1)` for the second `File`
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children()
+            .iter()
+            .filter(move |child| child.name == name)
+    }
+}
+
+// an owned copy of a whole document, as its top-level elements
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ElementTree {
+    pub elements: Vec<Element>,
+}
+
+impl ElementTree {
+    // builds the tree from a document's events; `name_of` maps element IDs to their schema names
+    pub fn from_events<I: Iterator<Item = Result<ElementEvent, ReaderError>>>(
+        events: I,
+        name_of: fn(u32) -> Option<&'static str>,
+    ) -> Result<Self, ReaderError> {
+        let mut tree = Self::default();
+        // the master elements that have been entered, but not yet left
+        let mut open: Vec<Element> = Vec::new();
+
+        let add = |tree: &mut Self, open: &mut Vec<Element>, element: Element| match open.last_mut()
+        {
+            Some(Element {
+                content: ElementContent::Children(children),
+                ..
+            }) => children.push(element),
+            _ => tree.elements.push(element),
+        };
+
+        for event in events {
+            match event? {
+                ElementEvent::Enter(id) => open.push(Element {
+                    id,
+                    name: name_of(id).unwrap_or_default(),
+                    content: ElementContent::Children(Vec::new()),
+                }),
+                ElementEvent::Data(id, value) => {
+                    let element = Element {
+                        id,
+                        name: name_of(id).unwrap_or_default(),
+                        content: ElementContent::Value(value),
+                    };
+                    add(&mut tree, &mut open, element);
+                }
+                ElementEvent::Leave(_) => {
+                    if let Some(element) = open.pop() {
+                        add(&mut tree, &mut open, element);
+                    }
+                }
+            }
+        }
+        // the events of a complete document always leave every element they enter
+        while let Some(element) = open.pop() {
+            add(&mut tree, &mut open, element);
+        }
+
+        Ok(tree)
+    }
+
+    // all elements at a `/`-separated path of names, e.g. `Files/File`
+    pub fn find<'a>(&'a self, path: &str) -> Vec<&'a Element> {
+        let mut names = path.split('/');
+        let mut elements: Vec<&Element> = match names.next() {
+            Some(name) => self.elements.iter().filter(|e| e.name == name).collect(),
+            None => Vec::new(),
+        };
+        for name in names {
+            elements = elements
+                .into_iter()
+                .flat_map(|element| element.children().iter())
+                .filter(|child| child.name == name)
+                .collect();
+        }
+        elements
+    }
+
+    // the values of all non-master elements at a `/`-separated path of names, e.g.
+    // `Files/File/FileName`
+    pub fn get(&self, path: &str) -> Vec<&ElementValue> {
+        self.find(path)
+            .into_iter()
+            .filter_map(Element::value)
+            .collect()
+    }
+}
@@ -1,16 +1,42 @@
-
 #[allow(unused_imports)]
 use crate::base::element_defs::{
-    BinaryElementDef, DateElementDef, ElementDef, FloatElementDef, IntElementDef, MasterElementDef,
-    Range, StringElementDef, UIntElementDef, Utf8ElementDef,
+    BinaryElementDef, DateElementDef, ElementDef, EnumElementDef, FloatElementDef, IntElementDef,
+    MasterElementDef, Range, StringElementDef, UIntElementDef, Utf8ElementDef,
 };
 
+#[allow(unused_imports)]
 use core::ops::Bound;
-            
+
+// the EBML version that the schema's documents use, as declared by the schema's `ebml` attribute
+pub const EBML_VERSION: u32 = 1;
+
+/// The CRC is computed on all the data of the Master Element it's in, starting after the CRC element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CRC32Def;
+
+impl ElementDef for CRC32Def {
+    const NAME: &'static str = "CRC32";
+    const ID: u32 = 191;
+    const PATH: &'static str = r"\(1-\)CRC32";
+
+    const MIN_OCCURS: usize = 0;
+    const MAX_OCCURS: Option<usize> = Some(1);
+    const LENGTH: Range<usize> = Range::IsExactly(4);
+    const RECURRING: bool = false;
+    const MIN_VERSION: u64 = 1;
+    const MAX_VERSION: Option<u64> = None;
+}
+
+impl BinaryElementDef for CRC32Def {
+    const DEFAULT: Option<&'static [u8]> = None;
+}
+
+/// Used to void damaged data, to avoid unexpected behaviors when using damaged data. The content is discarded. Also used to reserve space in a sub-element for later use.
 #[derive(Debug, Clone, PartialEq)]
 pub struct VoidDef;
 
 impl ElementDef for VoidDef {
+    const NAME: &'static str = "Void";
     const ID: u32 = 236;
     const PATH: &'static str = r"\(-\)Void";
 
@@ -21,15 +47,38 @@ impl ElementDef for VoidDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl BinaryElementDef for VoidDef {
     const DEFAULT: Option<&'static [u8]> = None;
 }
-                    
+
+/// The name of the tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagNameDef;
+
+impl ElementDef for TagNameDef {
+    const NAME: &'static str = "TagName";
+    const ID: u32 = 17827;
+    const PATH: &'static str = r"\Files\File\Tag\TagName";
+
+    const MIN_OCCURS: usize = 1;
+    const MAX_OCCURS: Option<usize> = None;
+    const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const RECURRING: bool = false;
+    const MIN_VERSION: u64 = 1;
+    const MAX_VERSION: Option<u64> = None;
+}
+
+impl Utf8ElementDef for TagNameDef {
+    const DEFAULT: Option<&'static str> = None;
+}
+
+/// MIME type of the file.
 #[derive(Debug, Clone, PartialEq)]
 pub struct MimeTypeDef;
 
 impl ElementDef for MimeTypeDef {
+    const NAME: &'static str = "MimeType";
     const ID: u32 = 17997;
     const PATH: &'static str = r"\Files\File\MimeType";
 
@@ -40,15 +89,17 @@ impl ElementDef for MimeTypeDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl StringElementDef for MimeTypeDef {
     const DEFAULT: Option<&'static str> = None;
 }
-                    
+
+/// Modification timestamp of the file.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ModificationTimestampDef;
 
 impl ElementDef for ModificationTimestampDef {
+    const NAME: &'static str = "ModificationTimestamp";
     const ID: u32 = 18004;
     const PATH: &'static str = r"\Files\File\ModificationTimestamp";
 
@@ -59,16 +110,18 @@ impl ElementDef for ModificationTimestampDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl DateElementDef for ModificationTimestampDef {
     const RANGE: Range<i64> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
     const DEFAULT: Option<i64> = None;
 }
-                    
+
+/// The data of the file.
 #[derive(Debug, Clone, PartialEq)]
 pub struct DataDef;
 
 impl ElementDef for DataDef {
+    const NAME: &'static str = "Data";
     const ID: u32 = 18020;
     const PATH: &'static str = r"\Files\File\Data";
 
@@ -79,15 +132,17 @@ impl ElementDef for DataDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl BinaryElementDef for DataDef {
     const DEFAULT: Option<&'static [u8]> = None;
 }
-                    
+
+/// An attached file.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FileDef;
 
 impl ElementDef for FileDef {
+    const NAME: &'static str = "File";
     const ID: u32 = 24902;
     const PATH: &'static str = r"\Files\File";
 
@@ -98,16 +153,20 @@ impl ElementDef for FileDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl MasterElementDef for FileDef {
     const UNKNOWN_SIZE_ALLOWED: bool = false;
     const RECURSIVE: bool = false;
+    const MANDATORY_CHILDREN: &'static [(u32, usize)] =
+        &[(17997, 1), (18004, 1), (18020, 1), (24910, 1)];
 }
-                    
+
+/// Filename of the attached file.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FileNameDef;
 
 impl ElementDef for FileNameDef {
+    const NAME: &'static str = "FileName";
     const ID: u32 = 24910;
     const PATH: &'static str = r"\Files\File\FileName";
 
@@ -118,15 +177,40 @@ impl ElementDef for FileNameDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl Utf8ElementDef for FileNameDef {
     const DEFAULT: Option<&'static str> = None;
 }
-                    
+
+/// A tag describing the file; tags may be nested to refine their parent tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagDef;
+
+impl ElementDef for TagDef {
+    const NAME: &'static str = "Tag";
+    const ID: u32 = 26568;
+    const PATH: &'static str = r"\Files\File\Tag";
+
+    const MIN_OCCURS: usize = 0;
+    const MAX_OCCURS: Option<usize> = None;
+    const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const RECURRING: bool = false;
+    const MIN_VERSION: u64 = 1;
+    const MAX_VERSION: Option<u64> = None;
+}
+
+impl MasterElementDef for TagDef {
+    const UNKNOWN_SIZE_ALLOWED: bool = false;
+    const RECURSIVE: bool = true;
+    const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[(17827, 1)];
+}
+
+/// Container of data and attributes representing one or many files.
 #[derive(Debug, Clone, PartialEq)]
 pub struct FilesDef;
 
 impl ElementDef for FilesDef {
+    const NAME: &'static str = "Files";
     const ID: u32 = 424044908;
     const PATH: &'static str = r"\Files";
 
@@ -137,9 +221,145 @@ impl ElementDef for FilesDef {
     const MIN_VERSION: u64 = 1;
     const MAX_VERSION: Option<u64> = None;
 }
-                
+
 impl MasterElementDef for FilesDef {
     const UNKNOWN_SIZE_ALLOWED: bool = false;
     const RECURSIVE: bool = false;
+    const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[(24902, 1)];
+}
+
+// (ID, name) pairs of all schema elements, by ascending ID
+pub const ELEMENT_NAMES: &[(u32, &str)] = &[
+    (191, "CRC32"),
+    (236, "Void"),
+    (17827, "TagName"),
+    (17997, "MimeType"),
+    (18004, "ModificationTimestamp"),
+    (18020, "Data"),
+    (24902, "File"),
+    (24910, "FileName"),
+    (26568, "Tag"),
+    (424044908, "Files"),
+];
+
+pub fn name_of(id: u32) -> Option<&'static str> {
+    ELEMENT_NAMES
+        .binary_search_by_key(&id, |&(id, _name)| id)
+        .ok()
+        .map(|index| ELEMENT_NAMES[index].1)
+}
+
+pub fn id_of(name: &str) -> Option<u32> {
+    ELEMENT_NAMES
+        .iter()
+        .find(|&&(_id, element_name)| element_name == name)
+        .map(|&(id, _name)| id)
+}
+
+// The elements of the schema, e.g. to resolve the ID in an error to its element's name; these
+// convert from/to their ID's & names, via `TryFrom<u32>`/`u32::from` & `FromStr`/`Display`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementId {
+    CRC32,
+    Void,
+    TagName,
+    MimeType,
+    ModificationTimestamp,
+    Data,
+    File,
+    FileName,
+    Tag,
+    Files,
+}
+
+impl ElementId {
+    pub fn from_id(id: u32) -> Option<Self> {
+        match id {
+            191 => Some(Self::CRC32),
+            236 => Some(Self::Void),
+            17827 => Some(Self::TagName),
+            17997 => Some(Self::MimeType),
+            18004 => Some(Self::ModificationTimestamp),
+            18020 => Some(Self::Data),
+            24902 => Some(Self::File),
+            24910 => Some(Self::FileName),
+            26568 => Some(Self::Tag),
+            424044908 => Some(Self::Files),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "CRC32" => Some(Self::CRC32),
+            "Void" => Some(Self::Void),
+            "TagName" => Some(Self::TagName),
+            "MimeType" => Some(Self::MimeType),
+            "ModificationTimestamp" => Some(Self::ModificationTimestamp),
+            "Data" => Some(Self::Data),
+            "File" => Some(Self::File),
+            "FileName" => Some(Self::FileName),
+            "Tag" => Some(Self::Tag),
+            "Files" => Some(Self::Files),
+            _ => None,
+        }
+    }
+
+    pub fn id(self) -> u32 {
+        match self {
+            Self::CRC32 => 191,
+            Self::Void => 236,
+            Self::TagName => 17827,
+            Self::MimeType => 17997,
+            Self::ModificationTimestamp => 18004,
+            Self::Data => 18020,
+            Self::File => 24902,
+            Self::FileName => 24910,
+            Self::Tag => 26568,
+            Self::Files => 424044908,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CRC32 => "CRC32",
+            Self::Void => "Void",
+            Self::TagName => "TagName",
+            Self::MimeType => "MimeType",
+            Self::ModificationTimestamp => "ModificationTimestamp",
+            Self::Data => "Data",
+            Self::File => "File",
+            Self::FileName => "FileName",
+            Self::Tag => "Tag",
+            Self::Files => "Files",
+        }
+    }
+}
+
+impl core::convert::TryFrom<u32> for ElementId {
+    type Error = u32;
+
+    fn try_from(id: u32) -> Result<Self, u32> {
+        Self::from_id(id).ok_or(id)
+    }
+}
+
+impl From<ElementId> for u32 {
+    fn from(element: ElementId) -> u32 {
+        element.id()
+    }
+}
+
+impl core::str::FromStr for ElementId {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        Self::from_name(name).ok_or(())
+    }
+}
+
+impl core::fmt::Display for ElementId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
 }
-                    
\ No newline at end of file
@@ -1,2 +1,4 @@
-mod element_defs;
+pub mod element_defs;
 pub mod parser;
+#[cfg(feature = "std")]
+pub mod writer;
@@ -1,84 +1,733 @@
-
+#[allow(unused_imports)]
 use crate::base::element_defs::ElementDef;
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+use crate::base::header::read_ebml_header_with_len;
+#[allow(unused_imports)]
+use crate::base::header::{DocumentConfig, EbmlHeader};
 #[allow(unused_imports)]
 use crate::base::parser::{
-    BoundTo, ElementReader, ElementState, IntoReader, NextStateNavigation, ReaderError,
-    SkipStateNavigation, StateDataParser, StateError,
+    check_length, check_path, check_version, skip_unknown_element, BoundTo, CrcAccumulator,
+    DocumentConfigured, ElementData, ElementEvent, ElementLength, ElementState, HandlerRegistry,
+    NextStateNavigation, RemainingBytes, SkipStateNavigation, StateDataParser, StateError,
+    CRC32_ID, DEFAULT_BUFFER_CAPACITY,
+};
+// the readers (& everything built on them) need `std::io`
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+use crate::base::parser::{
+    ElementCollector, ElementReader, FromElementData, IntoReader, NextReaderNavigation,
+    ReaderDataParser, ReaderError,
 };
 #[allow(unused_imports)]
 use crate::base::stream::{parse, serialize, stream_diff};
+#[allow(unused_imports)]
 use crate::core::element_defs;
 #[allow(unused_imports)]
 use crate::{
-    impl_from_readers_for_states, impl_from_subreaders_for_readers, impl_from_substates_for_states,
-    impl_into_reader, impl_next_state_navigation, impl_skip_state_navigation,
+    impl_crc_accumulator, impl_document_configured, impl_from_readers_for_states,
+    impl_from_subreaders_for_readers, impl_from_substates_for_states, impl_into_reader,
+    impl_next_state_navigation, impl_remaining_bytes, impl_skip_state_navigation,
 };
 
 use enum_dispatch::enum_dispatch;
 
-use core::convert::{From, TryInto};
+#[allow(unused_imports)]
+use alloc::{boxed::Box, vec::Vec};
+#[allow(unused_imports)]
+use core::convert::From;
 use core::marker::PhantomData;
+#[cfg(feature = "std")]
 use std::io::BufRead;
 
 // Top-Level Reader/State Enums #########################################################################
-            
+
 #[enum_dispatch(FileNextStates)]
-#[enum_dispatch(FileNextReaders<R>)]
-                
+#[cfg_attr(feature = "std", enum_dispatch(FileNextReaders<R>))]
 #[enum_dispatch(FilesNextStates)]
-#[enum_dispatch(FilesNextReaders<R>)]
-                
+#[cfg_attr(feature = "std", enum_dispatch(FilesNextReaders<R>))]
+#[enum_dispatch(TagNextStates)]
+#[cfg_attr(feature = "std", enum_dispatch(TagNextReaders<R>))]
 #[enum_dispatch(_DocumentNextStates)]
-#[enum_dispatch(_DocumentNextReaders<R>)]
-                
+#[cfg_attr(feature = "std", enum_dispatch(_DocumentNextReaders<R>))]
+#[enum_dispatch(CRC32PrevStates)]
+#[cfg_attr(feature = "std", enum_dispatch(CRC32PrevReaders<R>))]
+#[enum_dispatch(TagPrevStates)]
+#[cfg_attr(feature = "std", enum_dispatch(TagPrevReaders<R>))]
 #[enum_dispatch(VoidPrevStates)]
-#[enum_dispatch(VoidPrevReaders<R>)]
-                
+#[cfg_attr(feature = "std", enum_dispatch(VoidPrevReaders<R>))]
 #[enum_dispatch(States)]
-#[enum_dispatch(Readers<R>)]
+#[cfg_attr(feature = "std", enum_dispatch(Readers<R>))]
+#[allow(dead_code)]
 trait BlankTrait {}
-            
+
 #[enum_dispatch]
 pub enum States {
-    Void(VoidState), MimeType(MimeTypeState), ModificationTimestamp(ModificationTimestampState), Data(DataState), File(FileState), FileName(FileNameState), Files(FilesState), _Document(_DocumentState), 
+    CRC32(CRC32State),
+    Void(VoidState),
+    TagName(TagNameState),
+    MimeType(MimeTypeState),
+    ModificationTimestamp(ModificationTimestampState),
+    Data(DataState),
+    File(FileState),
+    FileName(FileNameState),
+    Tag(TagState),
+    Files(FilesState),
+    _Document(_DocumentState),
 }
-            
+
+#[cfg(feature = "std")]
 #[enum_dispatch]
 pub enum Readers<R> {
-    Void(VoidReader<R>),MimeType(MimeTypeReader<R>),ModificationTimestamp(ModificationTimestampReader<R>),Data(DataReader<R>),File(FileReader<R>),FileName(FileNameReader<R>),Files(FilesReader<R>),_Document(_DocumentReader<R>),
+    CRC32(CRC32Reader<R>),
+    Void(VoidReader<R>),
+    TagName(TagNameReader<R>),
+    MimeType(MimeTypeReader<R>),
+    ModificationTimestamp(ModificationTimestampReader<R>),
+    Data(DataReader<R>),
+    File(FileReader<R>),
+    FileName(FileNameReader<R>),
+    Tag(TagReader<R>),
+    Files(FilesReader<R>),
+    _Document(_DocumentReader<R>),
 }
-            
+
+#[cfg(feature = "std")]
 impl_into_reader!(
     States,
     Readers,
-    [Void, MimeType, ModificationTimestamp, Data, File, FileName, Files, _Document]
+    [
+        CRC32,
+        Void,
+        TagName,
+        MimeType,
+        ModificationTimestamp,
+        Data,
+        File,
+        FileName,
+        Tag,
+        Files,
+        _Document
+    ]
 );
 
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(
     Readers,
     States,
-    [Void, MimeType, ModificationTimestamp, Data, File, FileName, Files, _Document]
+    [
+        CRC32,
+        Void,
+        TagName,
+        MimeType,
+        ModificationTimestamp,
+        Data,
+        File,
+        FileName,
+        Tag,
+        Files,
+        _Document
+    ]
 );
-            
+
+impl States {
+    // the ID of the element being read, or `None` at the top level of the document
+    pub fn element_id(&self) -> Option<u32> {
+        match self {
+            Self::_Document(_) => None,
+            Self::CRC32(_) => Some(<element_defs::CRC32Def as ElementDef>::ID),
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::ID),
+            Self::TagName(_) => Some(<element_defs::TagNameDef as ElementDef>::ID),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::ID),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::ID)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::ID),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::ID),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::ID),
+            Self::Tag(_) => Some(<element_defs::TagDef as ElementDef>::ID),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::ID),
+        }
+    }
+
+    pub fn element_name(&self) -> Option<&'static str> {
+        match self {
+            Self::_Document(_) => None,
+            Self::CRC32(_) => Some(<element_defs::CRC32Def as ElementDef>::NAME),
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::NAME),
+            Self::TagName(_) => Some(<element_defs::TagNameDef as ElementDef>::NAME),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::NAME),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::NAME)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::NAME),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::NAME),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::NAME),
+            Self::Tag(_) => Some(<element_defs::TagDef as ElementDef>::NAME),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::NAME),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> Readers<R> {
+    // the ID of the element being read, or `None` at the top level of the document
+    pub fn element_id(&self) -> Option<u32> {
+        match self {
+            Self::_Document(_) => None,
+            Self::CRC32(_) => Some(<element_defs::CRC32Def as ElementDef>::ID),
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::ID),
+            Self::TagName(_) => Some(<element_defs::TagNameDef as ElementDef>::ID),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::ID),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::ID)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::ID),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::ID),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::ID),
+            Self::Tag(_) => Some(<element_defs::TagDef as ElementDef>::ID),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::ID),
+        }
+    }
+
+    pub fn element_name(&self) -> Option<&'static str> {
+        match self {
+            Self::_Document(_) => None,
+            Self::CRC32(_) => Some(<element_defs::CRC32Def as ElementDef>::NAME),
+            Self::Void(_) => Some(<element_defs::VoidDef as ElementDef>::NAME),
+            Self::TagName(_) => Some(<element_defs::TagNameDef as ElementDef>::NAME),
+            Self::MimeType(_) => Some(<element_defs::MimeTypeDef as ElementDef>::NAME),
+            Self::ModificationTimestamp(_) => {
+                Some(<element_defs::ModificationTimestampDef as ElementDef>::NAME)
+            }
+            Self::Data(_) => Some(<element_defs::DataDef as ElementDef>::NAME),
+            Self::File(_) => Some(<element_defs::FileDef as ElementDef>::NAME),
+            Self::FileName(_) => Some(<element_defs::FileNameDef as ElementDef>::NAME),
+            Self::Tag(_) => Some(<element_defs::TagDef as ElementDef>::NAME),
+            Self::Files(_) => Some(<element_defs::FilesDef as ElementDef>::NAME),
+        }
+    }
+}
+
+// Walks the entire document, passing the data of every element with a registered handler to
+// that handler
+#[cfg(feature = "std")]
+#[allow(unused_variables)] // `registry` goes unused for schemas w/o any data elements
+pub fn walk_with_handlers<R: BufRead>(
+    reader: R,
+    registry: &mut HandlerRegistry,
+) -> Result<(), ReaderError> {
+    let mut reader: Readers<R> = _DocumentReader::new(reader).into();
+
+    loop {
+        reader = match reader {
+            Readers::_Document(r) => match r.next() {
+                Ok(r_next) => r_next.into(),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e),
+            },
+            Readers::CRC32(mut r) => {
+                let id = <element_defs::CRC32Def as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::Binary(data));
+                }
+                r.next()?.into()
+            }
+            Readers::Void(mut r) => {
+                let id = <element_defs::VoidDef as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::Binary(data));
+                }
+                r.next()?.into()
+            }
+            Readers::TagName(mut r) => {
+                let id = <element_defs::TagNameDef as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::Utf8(data));
+                }
+                r.next()?.into()
+            }
+            Readers::MimeType(mut r) => {
+                let id = <element_defs::MimeTypeDef as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::String(data));
+                }
+                r.next()?.into()
+            }
+            Readers::ModificationTimestamp(mut r) => {
+                let id = <element_defs::ModificationTimestampDef as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::Date(data));
+                }
+                r.next()?.into()
+            }
+            Readers::Data(mut r) => {
+                let id = <element_defs::DataDef as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::Binary(data));
+                }
+                r.next()?.into()
+            }
+            Readers::File(r) => r.next()?.into(),
+            Readers::FileName(mut r) => {
+                let id = <element_defs::FileNameDef as ElementDef>::ID;
+                if registry.is_registered(id) {
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::Utf8(data));
+                }
+                r.next()?.into()
+            }
+            Readers::Tag(r) => r.next()?.into(),
+            Readers::Files(r) => r.next()?.into(),
+        };
+    }
+}
+
+// Callbacks for each element of the schema, as it's entered by `drive`; each defaults to doing
+// nothing, i.e. skipping the element's data
+#[cfg(feature = "std")]
+pub trait Visitor<R> {
+    fn visit_crc32(&mut self, _reader: &mut CRC32Reader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_void(&mut self, _reader: &mut VoidReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_tag_name(&mut self, _reader: &mut TagNameReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_mime_type(&mut self, _reader: &mut MimeTypeReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_modification_timestamp(
+        &mut self,
+        _reader: &mut ModificationTimestampReader<R>,
+    ) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_data(&mut self, _reader: &mut DataReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_file(&mut self, _reader: &mut FileReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_file_name(&mut self, _reader: &mut FileNameReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_tag(&mut self, _reader: &mut TagReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+
+    fn visit_files(&mut self, _reader: &mut FilesReader<R>) -> Result<(), ReaderError> {
+        Ok(())
+    }
+}
+
+// Walks the entire document, passing each element's reader to the visitor as it's entered
+#[cfg(feature = "std")]
+pub fn drive<R: BufRead, V: Visitor<R> + ?Sized>(
+    reader: R,
+    visitor: &mut V,
+) -> Result<(), ReaderError> {
+    let mut reader: Readers<R> = _DocumentReader::new(reader).into();
+
+    loop {
+        reader = match reader {
+            Readers::_Document(r) => match r.next() {
+                Ok(r_next) => visit_entered(r_next.into(), visitor)?,
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e),
+            },
+            Readers::CRC32(r) => r.next()?.into(),
+            Readers::Void(r) => r.next()?.into(),
+            Readers::TagName(r) => r.next()?.into(),
+            Readers::MimeType(r) => r.next()?.into(),
+            Readers::ModificationTimestamp(r) => r.next()?.into(),
+            Readers::Data(r) => r.next()?.into(),
+            Readers::File(r) => match r.next()? {
+                FileNextReaders::Parent(r_prev) => r_prev.into(),
+                r_next => visit_entered(r_next.into(), visitor)?,
+            },
+            Readers::FileName(r) => r.next()?.into(),
+            Readers::Tag(r) => match r.next()? {
+                TagNextReaders::Parent(r_prev) => r_prev.into(),
+                r_next => visit_entered(r_next.into(), visitor)?,
+            },
+            Readers::Files(r) => match r.next()? {
+                FilesNextReaders::Parent(r_prev) => r_prev.into(),
+                r_next => visit_entered(r_next.into(), visitor)?,
+            },
+        };
+    }
+}
+
+// passes a newly-entered element's reader to the visitor
+#[cfg(feature = "std")]
+fn visit_entered<R: BufRead, V: Visitor<R> + ?Sized>(
+    reader: Readers<R>,
+    visitor: &mut V,
+) -> Result<Readers<R>, ReaderError> {
+    match reader {
+        Readers::_Document(_) => unreachable!("the document is never entered"),
+        Readers::CRC32(mut r) => {
+            visitor.visit_crc32(&mut r)?;
+            Ok(Readers::CRC32(r))
+        }
+        Readers::Void(mut r) => {
+            visitor.visit_void(&mut r)?;
+            Ok(Readers::Void(r))
+        }
+        Readers::TagName(mut r) => {
+            visitor.visit_tag_name(&mut r)?;
+            Ok(Readers::TagName(r))
+        }
+        Readers::MimeType(mut r) => {
+            visitor.visit_mime_type(&mut r)?;
+            Ok(Readers::MimeType(r))
+        }
+        Readers::ModificationTimestamp(mut r) => {
+            visitor.visit_modification_timestamp(&mut r)?;
+            Ok(Readers::ModificationTimestamp(r))
+        }
+        Readers::Data(mut r) => {
+            visitor.visit_data(&mut r)?;
+            Ok(Readers::Data(r))
+        }
+        Readers::File(mut r) => {
+            visitor.visit_file(&mut r)?;
+            Ok(Readers::File(r))
+        }
+        Readers::FileName(mut r) => {
+            visitor.visit_file_name(&mut r)?;
+            Ok(Readers::FileName(r))
+        }
+        Readers::Tag(mut r) => {
+            visitor.visit_tag(&mut r)?;
+            Ok(Readers::Tag(r))
+        }
+        Readers::Files(mut r) => {
+            visitor.visit_files(&mut r)?;
+            Ok(Readers::Files(r))
+        }
+    }
+}
+
+// Reads the data of every element w/ the given ID in the document as `T` (e.g. a `String` for a
+// string element), skipping everything else
+#[cfg(feature = "std")]
+pub fn collect_by_id<T: FromElementData, R: BufRead>(
+    reader: R,
+    id: u32,
+) -> Result<Vec<T>, ReaderError> {
+    let mut collector = ElementCollector::new();
+    let mut registry = HandlerRegistry::new();
+    registry.register_id(id, &mut collector);
+    walk_with_handlers(reader, &mut registry)?;
+    drop(registry);
+
+    collector.into_values()
+}
+
+// Like `collect_by_id`, for the element w/ the given name in the schema
+#[cfg(feature = "std")]
+pub fn collect_by_name<T: FromElementData, R: BufRead>(
+    reader: R,
+    name: &str,
+) -> Result<Vec<T>, ReaderError> {
+    let id = element_defs::id_of(name).ok_or_else(|| ReaderError::UnknownName(name.to_string()))?;
+    collect_by_id(reader, id)
+}
+
+// Iterates over the events of walking the entire document, ending at the end of the document or
+// after the first error
+#[cfg(feature = "std")]
+pub struct ElementEvents<R> {
+    reader: Option<Readers<R>>,
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> ElementEvents<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(_DocumentReader::new(reader).into()),
+        }
+    }
+
+    fn enter(&mut self, reader: Readers<R>) -> Result<ElementEvent, ReaderError> {
+        match reader {
+            Readers::_Document(_) => unreachable!("the document is never entered"),
+            Readers::CRC32(mut r) => {
+                let value = ElementData::Binary(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::CRC32Def as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::Void(mut r) => {
+                let value = ElementData::Binary(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::VoidDef as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::TagName(mut r) => {
+                let value = ElementData::Utf8(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::TagNameDef as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::MimeType(mut r) => {
+                let value = ElementData::String(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::MimeTypeDef as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::ModificationTimestamp(mut r) => {
+                let value = ElementData::Date(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::ModificationTimestampDef as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::Data(mut r) => {
+                let value = ElementData::Binary(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::DataDef as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::File(r) => {
+                self.reader = Some(r.into());
+                Ok(ElementEvent::Enter(
+                    <element_defs::FileDef as ElementDef>::ID,
+                ))
+            }
+            Readers::FileName(mut r) => {
+                let value = ElementData::Utf8(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(
+                    <element_defs::FileNameDef as ElementDef>::ID,
+                    value,
+                ))
+            }
+            Readers::Tag(r) => {
+                self.reader = Some(r.into());
+                Ok(ElementEvent::Enter(
+                    <element_defs::TagDef as ElementDef>::ID,
+                ))
+            }
+            Readers::Files(r) => {
+                self.reader = Some(r.into());
+                Ok(ElementEvent::Enter(
+                    <element_defs::FilesDef as ElementDef>::ID,
+                ))
+            }
+        }
+    }
+
+    fn step(&mut self, reader: Readers<R>) -> Result<Option<ElementEvent>, ReaderError> {
+        match reader {
+            Readers::_Document(r) => match r.next() {
+                Ok(r_next) => self.enter(r_next.into()).map(Some),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => Ok(None),
+                Err(e) => Err(e),
+            },
+            Readers::File(r) => match r.next()? {
+                FileNextReaders::Parent(r_prev) => {
+                    self.reader = Some(r_prev.into());
+                    Ok(Some(ElementEvent::Leave(
+                        <element_defs::FileDef as ElementDef>::ID,
+                    )))
+                }
+                r_next => self.enter(r_next.into()).map(Some),
+            },
+            Readers::Tag(r) => match r.next()? {
+                TagNextReaders::Parent(r_prev) => {
+                    self.reader = Some(r_prev.into());
+                    Ok(Some(ElementEvent::Leave(
+                        <element_defs::TagDef as ElementDef>::ID,
+                    )))
+                }
+                r_next => self.enter(r_next.into()).map(Some),
+            },
+            Readers::Files(r) => match r.next()? {
+                FilesNextReaders::Parent(r_prev) => {
+                    self.reader = Some(r_prev.into());
+                    Ok(Some(ElementEvent::Leave(
+                        <element_defs::FilesDef as ElementDef>::ID,
+                    )))
+                }
+                r_next => self.enter(r_next.into()).map(Some),
+            },
+            Readers::CRC32(_)
+            | Readers::Void(_)
+            | Readers::TagName(_)
+            | Readers::MimeType(_)
+            | Readers::ModificationTimestamp(_)
+            | Readers::Data(_)
+            | Readers::FileName(_) => {
+                unreachable!("data elements are left upon being entered")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for ElementEvents<R> {
+    type Item = Result<ElementEvent, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let reader = self.reader.take()?;
+        self.step(reader).transpose()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> core::iter::FusedIterator for ElementEvents<R> {}
+
+// Deserializes the first top-level element of a document into a user type, e.g. a struct derived
+// w/ `serde::Deserialize` whose fields are its children's names in snake case.
+#[cfg(all(feature = "serde", feature = "std"))]
+pub fn from_ebml<T: serde::de::DeserializeOwned, R: BufRead>(
+    reader: R,
+) -> Result<T, crate::base::de::DeError> {
+    crate::base::de::from_events(ElementEvents::new(reader), element_defs::name_of)
+}
+
+// Reads a whole document into an owned tree of its elements
+#[cfg(feature = "tree")]
+pub struct Document;
+
+#[cfg(feature = "tree")]
+impl Document {
+    pub fn parse<R: BufRead>(reader: R) -> Result<crate::base::tree::ElementTree, ReaderError> {
+        crate::base::tree::ElementTree::from_events(
+            ElementEvents::new(reader),
+            element_defs::name_of,
+        )
+    }
+}
+
 // _Document Objects #########################################################################
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct _DocumentState;
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct _DocumentState {
+    pub config: DocumentConfig,
+}
+#[cfg(feature = "std")]
 pub type _DocumentReader<R> = ElementReader<R, _DocumentState>;
 
+#[cfg(feature = "std")]
 impl<R: BufRead> _DocumentReader<R> {
     pub fn new(reader: R) -> Self {
-        Self {
-            reader,
-            state: _DocumentState,
-        }
+        Self::with_config(reader, DocumentConfig::default())
+    }
+
+    pub fn with_config(reader: R, config: DocumentConfig) -> Self {
+        _DocumentState { config }.into_reader(reader, 0)
+    }
+
+    // reads the document's EBML header, & limits the document's element ID's & lengths to the
+    // maximum widths that it declares
+    pub fn from_header(mut reader: R) -> Result<(EbmlHeader, Self), ReaderError> {
+        let (header, position) = read_ebml_header_with_len(&mut reader)?;
+        let config = DocumentConfig::from(&header);
+        Ok((
+            header,
+            _DocumentState { config }.into_reader(reader, position),
+        ))
+    }
+
+    // the ID of the next top-level element, without moving into it -> `None` at the end of the
+    // stream
+    pub fn peek_id(&mut self) -> Result<Option<u32>, ReaderError> {
+        self.peek_next_id()
+    }
+}
+
+// Any `Read` (e.g. a `File` or `TcpStream`) can be parsed by buffering it here. An element header or
+// payload that straddles the end of the read-ahead is gathered into a separate, growable buffer
+// before being parsed -> the capacity only limits how much is read at once, not the size of the
+// elements that can be parsed.
+#[cfg(feature = "std")]
+impl<I: std::io::Read> _DocumentReader<std::io::BufReader<I>> {
+    // buffers an unbuffered reader, reading ahead `DEFAULT_BUFFER_CAPACITY` bytes at a time
+    pub fn from_read(inner: I) -> Self {
+        Self::with_capacity(DEFAULT_BUFFER_CAPACITY, inner)
+    }
+
+    // buffers an unbuffered reader, reading ahead `capacity` bytes at a time
+    pub fn with_capacity(capacity: usize, inner: I) -> Self {
+        Self::new(std::io::BufReader::with_capacity(capacity, inner))
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> IntoReader<R> for _DocumentState {
     type Reader = _DocumentReader<R>;
-    fn into_reader(self, reader: R) -> _DocumentReader<R> {
-        _DocumentReader::new(reader)
+    fn into_reader(self, reader: R, position: u64) -> _DocumentReader<R> {
+        ElementReader {
+            reader,
+            state: self,
+            pending: Vec::new(),
+            position,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> From<_DocumentReader<R>> for _DocumentState {
+    fn from(reader: _DocumentReader<R>) -> Self {
+        reader.state
+    }
+}
+
+impl RemainingBytes for _DocumentState {
+    fn remaining_in_current_master(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl DocumentConfigured for _DocumentState {
+    fn document_config(&self) -> DocumentConfig {
+        self.config
+    }
+
+    fn document_config_mut(&mut self) -> &mut DocumentConfig {
+        &mut self.config
+    }
+}
+
+// the document itself has no `CRC-32` elements to check
+impl CrcAccumulator for _DocumentState {
+    fn update_crc(&mut self, _data: &[u8]) {}
+
+    fn start_crc(&mut self, _expected: u32) {}
+
+    fn crc_pending(&self) -> bool {
+        false
     }
 }
 
@@ -87,247 +736,662 @@ impl_next_state_navigation!(
     _DocumentNextStates,
     [(Files, FilesState), (Void, VoidState)]
 );
-            
+
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
 pub enum _DocumentNextStates {
-    Files(FilesState), Void(VoidState), 
+    Files(FilesState),
+    Void(VoidState),
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum _DocumentNextReaders<R> {
-    Files(FilesReader<R>), Void(VoidReader<R>), 
+    Files(FilesReader<R>),
+    Void(VoidReader<R>),
 }
 
 impl_from_substates_for_states!(_DocumentNextStates, States, [Files, Void]);
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!(_DocumentNextReaders, Readers, [Files, Void]);
 
+#[cfg(feature = "std")]
 impl_into_reader!(_DocumentNextStates, _DocumentNextReaders, [Files, Void]);
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(_DocumentNextReaders, _DocumentNextStates, [Files, Void]);
-            
+
+// CRC32 Objects #########################################################################
+
+pub type CRC32State = ElementState<element_defs::CRC32Def, CRC32PrevStates>;
+#[cfg(feature = "std")]
+pub type CRC32Reader<R> = ElementReader<R, CRC32State>;
+
+impl CRC32State {
+    pub fn new(bytes_left: ElementLength, parent_state: CRC32PrevStates) -> Self {
+        Self {
+            bytes_left,
+            parent_state,
+            occurrences: Default::default(),
+            crc: None,
+            _phantom: PhantomData::<_>,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> CRC32Reader<R> {
+    pub fn new(reader: R, state: CRC32State) -> Self {
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl_skip_state_navigation!(CRC32State, CRC32PrevStates);
+impl_next_state_navigation!(CRC32State, CRC32PrevStates, []);
+impl_remaining_bytes!(child CRC32State);
+
+#[derive(Debug, Clone, PartialEq)]
+#[enum_dispatch]
+pub enum CRC32PrevStates {
+    File(FileState),
+    Files(FilesState),
+    Tag(TagState),
+}
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+#[enum_dispatch]
+pub enum CRC32PrevReaders<R> {
+    File(FileReader<R>),
+    Files(FilesReader<R>),
+    Tag(TagReader<R>),
+}
+
+impl_from_substates_for_states!(CRC32PrevStates, States, [File, Files, Tag]);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!(CRC32PrevReaders, Readers, [File, Files, Tag]);
+
+#[cfg(feature = "std")]
+impl_into_reader!(CRC32PrevStates, CRC32PrevReaders, [File, Files, Tag]);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!(CRC32PrevReaders, CRC32PrevStates, [File, Files, Tag]);
+
+impl_remaining_bytes!(CRC32PrevStates, [File, Files, Tag]);
+impl_document_configured!(CRC32PrevStates, [File, Files, Tag]);
+impl_crc_accumulator!(CRC32PrevStates, [File, Files, Tag]);
+
 // Void Objects #########################################################################
 
 pub type VoidState = ElementState<element_defs::VoidDef, VoidPrevStates>;
+#[cfg(feature = "std")]
 pub type VoidReader<R> = ElementReader<R, VoidState>;
 
 impl VoidState {
-    pub fn new(bytes_left: usize, parent_state: VoidPrevStates) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: VoidPrevStates) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> VoidReader<R> {
     pub fn new(reader: R, state: VoidState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(VoidState, VoidPrevStates);
 impl_next_state_navigation!(VoidState, VoidPrevStates, []);
-                
+impl_remaining_bytes!(child VoidState);
+
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
 pub enum VoidPrevStates {
-    File(FileState),Files(FilesState),_Document(_DocumentState),
+    File(FileState),
+    Files(FilesState),
+    Tag(TagState),
+    _Document(_DocumentState),
 }
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum VoidPrevReaders<R> {
-    File(FileReader<R>),Files(FilesReader<R>),_Document(_DocumentReader<R>),
+    File(FileReader<R>),
+    Files(FilesReader<R>),
+    Tag(TagReader<R>),
+    _Document(_DocumentReader<R>),
+}
+
+impl_from_substates_for_states!(VoidPrevStates, States, [File, Files, Tag, _Document]);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!(VoidPrevReaders, Readers, [File, Files, Tag, _Document]);
+
+#[cfg(feature = "std")]
+impl_into_reader!(
+    VoidPrevStates,
+    VoidPrevReaders,
+    [File, Files, Tag, _Document]
+);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!(
+    VoidPrevReaders,
+    VoidPrevStates,
+    [File, Files, Tag, _Document]
+);
+
+impl_remaining_bytes!(VoidPrevStates, [File, Files, Tag, _Document]);
+impl_document_configured!(VoidPrevStates, [File, Files, Tag, _Document]);
+impl_crc_accumulator!(VoidPrevStates, [File, Files, Tag, _Document]);
+
+// TagName Objects #########################################################################
+
+pub type TagNameState = ElementState<element_defs::TagNameDef, TagState>;
+#[cfg(feature = "std")]
+pub type TagNameReader<R> = ElementReader<R, TagNameState>;
+
+impl TagNameState {
+    pub fn new(bytes_left: ElementLength, parent_state: TagState) -> Self {
+        Self {
+            bytes_left,
+            parent_state,
+            occurrences: Default::default(),
+            crc: None,
+            _phantom: PhantomData::<_>,
+        }
+    }
 }
 
-impl_from_substates_for_states!(VoidPrevStates, States, [_Document, Files, File]);
-impl_from_subreaders_for_readers!(VoidPrevReaders, Readers, [_Document, Files, File]);
+#[cfg(feature = "std")]
+impl<R: BufRead> TagNameReader<R> {
+    pub fn new(reader: R, state: TagNameState) -> Self {
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl_skip_state_navigation!(TagNameState, TagState);
+impl_next_state_navigation!(TagNameState, TagState, []);
+impl_remaining_bytes!(child TagNameState);
 
-impl_into_reader!(VoidPrevStates, VoidPrevReaders, [_Document, Files, File]);
-impl_from_readers_for_states!(VoidPrevReaders, VoidPrevStates, [_Document, Files, File]);
-                    
 // MimeType Objects #########################################################################
 
 pub type MimeTypeState = ElementState<element_defs::MimeTypeDef, FileState>;
+#[cfg(feature = "std")]
 pub type MimeTypeReader<R> = ElementReader<R, MimeTypeState>;
 
 impl MimeTypeState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> MimeTypeReader<R> {
     pub fn new(reader: R, state: MimeTypeState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(MimeTypeState, FileState);
 impl_next_state_navigation!(MimeTypeState, FileState, []);
-                
+impl_remaining_bytes!(child MimeTypeState);
+
 // ModificationTimestamp Objects #########################################################################
 
-pub type ModificationTimestampState = ElementState<element_defs::ModificationTimestampDef, FileState>;
+pub type ModificationTimestampState =
+    ElementState<element_defs::ModificationTimestampDef, FileState>;
+#[cfg(feature = "std")]
 pub type ModificationTimestampReader<R> = ElementReader<R, ModificationTimestampState>;
 
 impl ModificationTimestampState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> ModificationTimestampReader<R> {
     pub fn new(reader: R, state: ModificationTimestampState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(ModificationTimestampState, FileState);
 impl_next_state_navigation!(ModificationTimestampState, FileState, []);
-                
+impl_remaining_bytes!(child ModificationTimestampState);
+
 // Data Objects #########################################################################
 
 pub type DataState = ElementState<element_defs::DataDef, FileState>;
+#[cfg(feature = "std")]
 pub type DataReader<R> = ElementReader<R, DataState>;
 
 impl DataState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> DataReader<R> {
     pub fn new(reader: R, state: DataState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(DataState, FileState);
 impl_next_state_navigation!(DataState, FileState, []);
-                
+impl_remaining_bytes!(child DataState);
+
 // File Objects #########################################################################
 
 pub type FileState = ElementState<element_defs::FileDef, FilesState>;
+#[cfg(feature = "std")]
 pub type FileReader<R> = ElementReader<R, FileState>;
 
 impl FileState {
-    pub fn new(bytes_left: usize, parent_state: FilesState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FilesState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> FileReader<R> {
     pub fn new(reader: R, state: FileState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(FileState, FilesState);
-impl_next_state_navigation!(FileState, FileNextStates, [(Data, DataState), (FileName, FileNameState), (MimeType, MimeTypeState), (ModificationTimestamp, ModificationTimestampState), (Void, VoidState)]);
-                
+impl_next_state_navigation!(
+    FileState,
+    FileNextStates,
+    [
+        (CRC32, CRC32State),
+        (Data, DataState),
+        (FileName, FileNameState),
+        (MimeType, MimeTypeState),
+        (ModificationTimestamp, ModificationTimestampState),
+        (Tag, TagState),
+        (Void, VoidState)
+    ]
+);
+impl_remaining_bytes!(master FileState);
+
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
 pub enum FileNextStates {
-    Data(DataState), FileName(FileNameState), MimeType(MimeTypeState), ModificationTimestamp(ModificationTimestampState), Void(VoidState), 
+    CRC32(CRC32State),
+    Data(DataState),
+    FileName(FileNameState),
+    MimeType(MimeTypeState),
+    ModificationTimestamp(ModificationTimestampState),
+    Tag(TagState),
+    Void(VoidState),
     Parent(FilesState),
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum FileNextReaders<R> {
-    Data(DataReader<R>), FileName(FileNameReader<R>), MimeType(MimeTypeReader<R>), ModificationTimestamp(ModificationTimestampReader<R>), Void(VoidReader<R>), 
+    CRC32(CRC32Reader<R>),
+    Data(DataReader<R>),
+    FileName(FileNameReader<R>),
+    MimeType(MimeTypeReader<R>),
+    ModificationTimestamp(ModificationTimestampReader<R>),
+    Tag(TagReader<R>),
+    Void(VoidReader<R>),
     Parent(FilesReader<R>),
 }
 
-impl_from_substates_for_states!(FileNextStates, States, [Data, FileName, MimeType, ModificationTimestamp, Void, Parent]);
-impl_from_subreaders_for_readers!(FileNextReaders, Readers, [Data, FileName, MimeType, ModificationTimestamp, Void, Parent]);
+impl_from_substates_for_states!(
+    FileNextStates,
+    States,
+    [
+        CRC32,
+        Data,
+        FileName,
+        MimeType,
+        ModificationTimestamp,
+        Tag,
+        Void,
+        Parent
+    ]
+);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!(
+    FileNextReaders,
+    Readers,
+    [
+        CRC32,
+        Data,
+        FileName,
+        MimeType,
+        ModificationTimestamp,
+        Tag,
+        Void,
+        Parent
+    ]
+);
+
+#[cfg(feature = "std")]
+impl_into_reader!(
+    FileNextStates,
+    FileNextReaders,
+    [
+        CRC32,
+        Data,
+        FileName,
+        MimeType,
+        ModificationTimestamp,
+        Tag,
+        Void,
+        Parent
+    ]
+);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!(
+    FileNextReaders,
+    FileNextStates,
+    [
+        CRC32,
+        Data,
+        FileName,
+        MimeType,
+        ModificationTimestamp,
+        Tag,
+        Void,
+        Parent
+    ]
+);
 
-impl_into_reader!(FileNextStates, FileNextReaders, [Data, FileName, MimeType, ModificationTimestamp, Void, Parent]);
-impl_from_readers_for_states!(FileNextReaders, FileNextStates, [Data, FileName, MimeType, ModificationTimestamp, Void, Parent]);
-                    
 // FileName Objects #########################################################################
 
 pub type FileNameState = ElementState<element_defs::FileNameDef, FileState>;
+#[cfg(feature = "std")]
 pub type FileNameReader<R> = ElementReader<R, FileNameState>;
 
 impl FileNameState {
-    pub fn new(bytes_left: usize, parent_state: FileState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: FileState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> FileNameReader<R> {
     pub fn new(reader: R, state: FileNameState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(FileNameState, FileState);
 impl_next_state_navigation!(FileNameState, FileState, []);
-                
+impl_remaining_bytes!(child FileNameState);
+
+// Tag Objects #########################################################################
+
+pub type TagState = ElementState<element_defs::TagDef, TagPrevStates>;
+#[cfg(feature = "std")]
+pub type TagReader<R> = ElementReader<R, TagState>;
+
+impl TagState {
+    pub fn new(bytes_left: ElementLength, parent_state: TagPrevStates) -> Self {
+        Self {
+            bytes_left,
+            parent_state,
+            occurrences: Default::default(),
+            crc: None,
+            _phantom: PhantomData::<_>,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> TagReader<R> {
+    pub fn new(reader: R, state: TagState) -> Self {
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl_skip_state_navigation!(TagState, TagPrevStates);
+impl_next_state_navigation!(
+    TagState,
+    TagNextStates,
+    [
+        (CRC32, CRC32State),
+        (Tag, TagState),
+        (TagName, TagNameState),
+        (Void, VoidState)
+    ]
+);
+impl_remaining_bytes!(master TagState);
+
+#[derive(Debug, Clone, PartialEq)]
+#[enum_dispatch]
+pub enum TagNextStates {
+    CRC32(CRC32State),
+    Tag(TagState),
+    TagName(TagNameState),
+    Void(VoidState),
+    Parent(TagPrevStates),
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+#[enum_dispatch]
+pub enum TagNextReaders<R> {
+    CRC32(CRC32Reader<R>),
+    Tag(TagReader<R>),
+    TagName(TagNameReader<R>),
+    Void(VoidReader<R>),
+    Parent(TagPrevReaders<R>),
+}
+
+impl_from_substates_for_states!(TagNextStates, States, [CRC32, Tag, TagName, Void, Parent]);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!(TagNextReaders, Readers, [CRC32, Tag, TagName, Void, Parent]);
+
+#[cfg(feature = "std")]
+impl_into_reader!(
+    TagNextStates,
+    TagNextReaders,
+    [CRC32, Tag, TagName, Void, Parent]
+);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!(
+    TagNextReaders,
+    TagNextStates,
+    [CRC32, Tag, TagName, Void, Parent]
+);
+
+#[derive(Debug, Clone, PartialEq)]
+#[enum_dispatch]
+pub enum TagPrevStates {
+    File(FileState),
+    Tag(Box<TagState>),
+}
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+#[enum_dispatch]
+pub enum TagPrevReaders<R> {
+    File(FileReader<R>),
+    Tag(TagReader<R>),
+}
+
+impl_from_substates_for_states!(TagPrevStates, States, [File, Tag]);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!(TagPrevReaders, Readers, [File, Tag]);
+
+#[cfg(feature = "std")]
+impl_into_reader!(TagPrevStates, TagPrevReaders, [File, Tag]);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!(TagPrevReaders, TagPrevStates, [File, Tag]);
+
+impl_remaining_bytes!(TagPrevStates, [File, Tag]);
+impl_document_configured!(TagPrevStates, [File, Tag]);
+impl_crc_accumulator!(TagPrevStates, [File, Tag]);
+
+impl From<TagState> for TagPrevStates {
+    fn from(state: TagState) -> Self {
+        Self::Tag(Box::new(state))
+    }
+}
+
+impl From<Box<TagState>> for States {
+    fn from(state: Box<TagState>) -> Self {
+        (*state).into()
+    }
+}
+
 // Files Objects #########################################################################
 
 pub type FilesState = ElementState<element_defs::FilesDef, _DocumentState>;
+#[cfg(feature = "std")]
 pub type FilesReader<R> = ElementReader<R, FilesState>;
 
 impl FilesState {
-    pub fn new(bytes_left: usize, parent_state: _DocumentState) -> Self {
+    pub fn new(bytes_left: ElementLength, parent_state: _DocumentState) -> Self {
         Self {
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl<R: BufRead> FilesReader<R> {
     pub fn new(reader: R, state: FilesState) -> Self {
-        Self { reader, state }
+        Self {
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }
     }
 }
 
 impl_skip_state_navigation!(FilesState, _DocumentState);
-impl_next_state_navigation!(FilesState, FilesNextStates, [(File, FileState), (Void, VoidState)]);
-                
+impl_next_state_navigation!(
+    FilesState,
+    FilesNextStates,
+    [(CRC32, CRC32State), (File, FileState), (Void, VoidState)]
+);
+impl_remaining_bytes!(master FilesState);
+
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
 pub enum FilesNextStates {
-    File(FileState), Void(VoidState), 
+    CRC32(CRC32State),
+    File(FileState),
+    Void(VoidState),
     Parent(_DocumentState),
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
 pub enum FilesNextReaders<R> {
-    File(FileReader<R>), Void(VoidReader<R>), 
+    CRC32(CRC32Reader<R>),
+    File(FileReader<R>),
+    Void(VoidReader<R>),
     Parent(_DocumentReader<R>),
 }
 
-impl_from_substates_for_states!(FilesNextStates, States, [File, Void, Parent]);
-impl_from_subreaders_for_readers!(FilesNextReaders, Readers, [File, Void, Parent]);
+impl_from_substates_for_states!(FilesNextStates, States, [CRC32, File, Void, Parent]);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!(FilesNextReaders, Readers, [CRC32, File, Void, Parent]);
 
-impl_into_reader!(FilesNextStates, FilesNextReaders, [File, Void, Parent]);
-impl_from_readers_for_states!(FilesNextReaders, FilesNextStates, [File, Void, Parent]);
-                    
\ No newline at end of file
+#[cfg(feature = "std")]
+impl_into_reader!(
+    FilesNextStates,
+    FilesNextReaders,
+    [CRC32, File, Void, Parent]
+);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!(
+    FilesNextReaders,
+    FilesNextStates,
+    [CRC32, File, Void, Parent]
+);
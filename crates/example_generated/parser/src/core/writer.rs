@@ -0,0 +1,339 @@
+#[allow(unused_imports)]
+use crate::base::parser::{NextReaderNavigation, ReaderDataParser, ReaderError};
+use crate::base::writer::{ElementWriter, WriterError};
+#[allow(unused_imports)]
+use crate::core::element_defs;
+use crate::core::parser;
+
+use std::io::{BufRead, Write};
+
+// Copies the entire document into `writer`, re-computing the lengths of all master elements
+pub fn copy_document<R: BufRead, W: Write>(
+    reader: R,
+    writer: &mut ElementWriter<W>,
+) -> Result<(), WriterError> {
+    let mut reader: parser::Readers<R> = parser::_DocumentReader::new(reader).into();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Ok(r_next) => start_master(writer, r_next.into()),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            },
+            parser::Readers::CRC32(mut r) => {
+                writer.write_binary::<element_defs::CRC32Def>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::Void(mut r) => {
+                writer.write_binary::<element_defs::VoidDef>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::TagName(mut r) => {
+                writer.write_utf8::<element_defs::TagNameDef>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::MimeType(mut r) => {
+                writer.write_string::<element_defs::MimeTypeDef>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::ModificationTimestamp(mut r) => {
+                writer.write_date::<element_defs::ModificationTimestampDef>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::Data(mut r) => {
+                writer.write_binary::<element_defs::DataDef>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::File(r) => match r.next()? {
+                parser::FileNextReaders::Parent(r) => {
+                    writer.end::<element_defs::FileDef>()?;
+                    r.into()
+                }
+                r => start_master(writer, r.into()),
+            },
+            parser::Readers::FileName(mut r) => {
+                writer.write_utf8::<element_defs::FileNameDef>(r.read()?)?;
+                r.next()?.into()
+            }
+            parser::Readers::Tag(r) => match r.next()? {
+                parser::TagNextReaders::Parent(r) => {
+                    writer.end::<element_defs::TagDef>()?;
+                    r.into()
+                }
+                r => start_master(writer, r.into()),
+            },
+            parser::Readers::Files(r) => match r.next()? {
+                parser::FilesNextReaders::Parent(r) => {
+                    writer.end::<element_defs::FilesDef>()?;
+                    r.into()
+                }
+                r => start_master(writer, r.into()),
+            },
+        };
+    }
+}
+
+// Intercepts the data elements of a document being copied by `transcode`, e.g. to rewrite them; by
+// default, each element's data is passed through as-is.
+pub trait Transcoder<R: BufRead, W: Write> {
+    fn transcode_crc32(
+        &mut self,
+        reader: &mut parser::CRC32Reader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::CRC32Def>(reader.read_raw()?)
+    }
+
+    fn transcode_void(
+        &mut self,
+        reader: &mut parser::VoidReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::VoidDef>(reader.read_raw()?)
+    }
+
+    fn transcode_tag_name(
+        &mut self,
+        reader: &mut parser::TagNameReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::TagNameDef>(reader.read_raw()?)
+    }
+
+    fn transcode_mime_type(
+        &mut self,
+        reader: &mut parser::MimeTypeReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::MimeTypeDef>(reader.read_raw()?)
+    }
+
+    fn transcode_modification_timestamp(
+        &mut self,
+        reader: &mut parser::ModificationTimestampReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::ModificationTimestampDef>(reader.read_raw()?)
+    }
+
+    fn transcode_data(
+        &mut self,
+        reader: &mut parser::DataReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::DataDef>(reader.read_raw()?)
+    }
+
+    fn transcode_file_name(
+        &mut self,
+        reader: &mut parser::FileNameReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        writer.write_raw::<element_defs::FileNameDef>(reader.read_raw()?)
+    }
+}
+
+// Copies the entire document into `writer` like `copy_document`, except that each data element is
+// written by `transcoder`
+pub fn transcode<R: BufRead, W: Write, T: Transcoder<R, W> + ?Sized>(
+    reader: R,
+    writer: &mut ElementWriter<W>,
+    transcoder: &mut T,
+) -> Result<(), WriterError> {
+    let mut reader: parser::Readers<R> = parser::_DocumentReader::new(reader).into();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Ok(r_next) => start_master(writer, r_next.into()),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            },
+            parser::Readers::CRC32(mut r) => {
+                transcoder.transcode_crc32(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::Void(mut r) => {
+                transcoder.transcode_void(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::TagName(mut r) => {
+                transcoder.transcode_tag_name(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::MimeType(mut r) => {
+                transcoder.transcode_mime_type(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::ModificationTimestamp(mut r) => {
+                transcoder.transcode_modification_timestamp(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::Data(mut r) => {
+                transcoder.transcode_data(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::File(r) => match r.next()? {
+                parser::FileNextReaders::Parent(r) => {
+                    writer.end::<element_defs::FileDef>()?;
+                    r.into()
+                }
+                r => start_master(writer, r.into()),
+            },
+            parser::Readers::FileName(mut r) => {
+                transcoder.transcode_file_name(&mut r, writer)?;
+                r.next()?.into()
+            }
+            parser::Readers::Tag(r) => match r.next()? {
+                parser::TagNextReaders::Parent(r) => {
+                    writer.end::<element_defs::TagDef>()?;
+                    r.into()
+                }
+                r => start_master(writer, r.into()),
+            },
+            parser::Readers::Files(r) => match r.next()? {
+                parser::FilesNextReaders::Parent(r) => {
+                    writer.end::<element_defs::FilesDef>()?;
+                    r.into()
+                }
+                r => start_master(writer, r.into()),
+            },
+        };
+    }
+}
+
+// starts writing a master element when the reader has just entered one
+#[allow(unused_variables)]
+fn start_master<R, W: Write>(
+    writer: &mut ElementWriter<W>,
+    reader: parser::Readers<R>,
+) -> parser::Readers<R> {
+    match &reader {
+        parser::Readers::File(_) => writer.start::<element_defs::FileDef>(),
+        parser::Readers::Tag(_) => writer.start::<element_defs::TagDef>(),
+        parser::Readers::Files(_) => writer.start::<element_defs::FilesDef>(),
+        _ => {}
+    }
+    reader
+}
+
+// Writes a `File` element; its children are written in the order that their methods are called.
+// The element's header is only written on `finish`, once its length is known.
+pub struct FileBuilder<'a, W: Write> {
+    writer: &'a mut ElementWriter<W>,
+}
+
+impl<'a, W: Write> FileBuilder<'a, W> {
+    pub fn new(writer: &'a mut ElementWriter<W>) -> Self {
+        writer.start::<element_defs::FileDef>();
+        Self { writer }
+    }
+
+    pub fn crc32(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::CRC32Def>(value)?;
+        Ok(self)
+    }
+
+    pub fn void(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::VoidDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn mime_type(&mut self, value: &str) -> Result<&mut Self, WriterError> {
+        self.writer
+            .write_string::<element_defs::MimeTypeDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn modification_timestamp(&mut self, value: i64) -> Result<&mut Self, WriterError> {
+        self.writer
+            .write_date::<element_defs::ModificationTimestampDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn data(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::DataDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn file_name(&mut self, value: &str) -> Result<&mut Self, WriterError> {
+        self.writer.write_utf8::<element_defs::FileNameDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn tag(&mut self) -> TagBuilder<'_, W> {
+        TagBuilder::new(self.writer)
+    }
+
+    pub fn finish(self) -> Result<(), WriterError> {
+        self.writer.end::<element_defs::FileDef>()
+    }
+}
+
+// Writes a `Tag` element; its children are written in the order that their methods are called.
+// The element's header is only written on `finish`, once its length is known.
+pub struct TagBuilder<'a, W: Write> {
+    writer: &'a mut ElementWriter<W>,
+}
+
+impl<'a, W: Write> TagBuilder<'a, W> {
+    pub fn new(writer: &'a mut ElementWriter<W>) -> Self {
+        writer.start::<element_defs::TagDef>();
+        Self { writer }
+    }
+
+    pub fn crc32(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::CRC32Def>(value)?;
+        Ok(self)
+    }
+
+    pub fn void(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::VoidDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn tag_name(&mut self, value: &str) -> Result<&mut Self, WriterError> {
+        self.writer.write_utf8::<element_defs::TagNameDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn tag(&mut self) -> TagBuilder<'_, W> {
+        TagBuilder::new(self.writer)
+    }
+
+    pub fn finish(self) -> Result<(), WriterError> {
+        self.writer.end::<element_defs::TagDef>()
+    }
+}
+
+// Writes a `Files` element; its children are written in the order that their methods are called.
+// The element's header is only written on `finish`, once its length is known.
+pub struct FilesBuilder<'a, W: Write> {
+    writer: &'a mut ElementWriter<W>,
+}
+
+impl<'a, W: Write> FilesBuilder<'a, W> {
+    pub fn new(writer: &'a mut ElementWriter<W>) -> Self {
+        writer.start::<element_defs::FilesDef>();
+        Self { writer }
+    }
+
+    pub fn crc32(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::CRC32Def>(value)?;
+        Ok(self)
+    }
+
+    pub fn void(&mut self, value: &[u8]) -> Result<&mut Self, WriterError> {
+        self.writer.write_binary::<element_defs::VoidDef>(value)?;
+        Ok(self)
+    }
+
+    pub fn file(&mut self) -> FileBuilder<'_, W> {
+        FileBuilder::new(self.writer)
+    }
+
+    pub fn finish(self) -> Result<(), WriterError> {
+        self.writer.end::<element_defs::FilesDef>()
+    }
+}
@@ -1,7 +1,20 @@
+use iron_ebmlem_parser::base::async_reader::AsyncElementReader;
+use iron_ebmlem_parser::base::de::DeError;
+use iron_ebmlem_parser::base::element_defs::ElementDef;
+use iron_ebmlem_parser::base::header::EbmlHeader;
 use iron_ebmlem_parser::base::parser::{
-    NextReaderNavigation, ReaderDataParser, ReaderError, SkipReaderNavigation,
+    BoundTo, ElementData, ElementEvent, ElementHandler, ElementLength, ElementValue,
+    HandlerRegistry, NextReaderNavigation, ReaderDataParser, ReaderError, SkipReaderNavigation,
+    StateError, DEFAULT_BUFFER_CAPACITY,
 };
-use iron_ebmlem_parser::core::parser;
+use iron_ebmlem_parser::base::stream::{crc32, serialize};
+use iron_ebmlem_parser::base::writer::{ElementWriter, WriterError};
+use iron_ebmlem_parser::core::{element_defs, parser, writer};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
 
 const BYTE_STREAM: [u8; 150] = [
     // ### Files 1 ###
@@ -75,27 +88,33 @@ fn basic_traversal() {
         match reader {
             parser::Readers::_Document(_) => result.push("(None)"),
             parser::Readers::Void(_) => result.push("Void"),
+            parser::Readers::CRC32(_) => result.push("CRC32"),
             parser::Readers::Files(_) => result.push("Files"),
             parser::Readers::File(_) => result.push("File"),
             parser::Readers::FileName(_) => result.push("FileName"),
             parser::Readers::MimeType(_) => result.push("MimeType"),
             parser::Readers::ModificationTimestamp(_) => result.push("ModTime"),
             parser::Readers::Data(_) => result.push("Data"),
+            parser::Readers::Tag(_) => result.push("Tag"),
+            parser::Readers::TagName(_) => result.push("TagName"),
         }
 
         reader = match reader {
             parser::Readers::_Document(r) => match r.next() {
                 Ok(r_next) => r_next.into(),
-                Err(ReaderError::Parse(nom::Err::Incomplete(_))) => break,
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
                 Err(_) => panic!(), // in an actual function, this should return the error
             },
             parser::Readers::Void(r) => r.next().unwrap().into(),
+            parser::Readers::CRC32(r) => r.next().unwrap().into(),
             parser::Readers::Files(r) => r.next().unwrap().into(),
             parser::Readers::File(r) => r.next().unwrap().into(),
             parser::Readers::FileName(r) => r.next().unwrap().into(),
             parser::Readers::MimeType(r) => r.next().unwrap().into(),
             parser::Readers::ModificationTimestamp(r) => r.next().unwrap().into(),
             parser::Readers::Data(r) => r.next().unwrap().into(),
+            parser::Readers::Tag(r) => r.next().unwrap().into(),
+            parser::Readers::TagName(r) => r.next().unwrap().into(),
         };
     }
 
@@ -110,6 +129,527 @@ fn basic_traversal() {
     );
 }
 
+#[test]
+fn read_raw_data() {
+    let mut stream = BYTE_STREAM.to_vec();
+    stream[42] = 0x01; // the first ModificationTimestamp data = 0x0100
+    let mut reader: parser::Readers<_> = parser::_DocumentReader::new(&stream[..]).into();
+
+    let mut timestamps = Vec::new();
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Ok(r_next) => r_next.into(),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
+                Err(e) => panic!("{}", e),
+            },
+            parser::Readers::ModificationTimestamp(mut r) => {
+                let date: i64 = r.read().unwrap();
+                let raw = r.read_raw().unwrap().to_vec();
+                timestamps.push((date, raw));
+                r.next().unwrap().into()
+            }
+            parser::Readers::CRC32(r) => r.next().unwrap().into(),
+            parser::Readers::Void(r) => r.next().unwrap().into(),
+            parser::Readers::TagName(r) => r.next().unwrap().into(),
+            parser::Readers::MimeType(r) => r.next().unwrap().into(),
+            parser::Readers::Data(r) => r.next().unwrap().into(),
+            parser::Readers::File(r) => r.next().unwrap().into(),
+            parser::Readers::FileName(r) => r.next().unwrap().into(),
+            parser::Readers::Tag(r) => r.next().unwrap().into(),
+            parser::Readers::Files(r) => r.next().unwrap().into(),
+        };
+    }
+
+    assert_eq!(
+        timestamps,
+        vec![
+            (0x0100, vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00]),
+            (0, vec![0x00; 8]),
+            (0, vec![0x00; 8]),
+        ]
+    );
+}
+
+#[test]
+fn element_ids_and_names() {
+    let reader = parser::_DocumentReader::new(&BYTE_STREAM[..]);
+    let readers: parser::Readers<_> = reader.into();
+    assert_eq!(readers.element_id(), None);
+    assert_eq!(readers.element_name(), None);
+
+    let reader = match readers {
+        parser::Readers::_Document(r) => r.next().unwrap(),
+        _ => panic!(),
+    };
+    let files_reader = match reader {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!(),
+    };
+    assert_eq!(files_reader.element_id(), 0x1946696C);
+    assert_eq!(files_reader.element_name(), "Files");
+
+    let readers: parser::Readers<_> = files_reader.next().unwrap().into();
+    assert_eq!(readers.element_id(), Some(element_defs::FileDef::ID));
+    assert_eq!(readers.element_name(), Some("File"));
+}
+
+// traverses the whole stream, stopping at the first error
+#[test]
+fn schema_ebml_version() {
+    // the schema doesn't declare its EBML version -> the default of 1
+    assert_eq!(element_defs::EBML_VERSION, 1);
+    assert!(
+        u64::from(element_defs::EBML_VERSION) <= iron_ebmlem_parser::base::header::EBML_VERSION
+    );
+}
+
+#[test]
+fn resolve_element_ids() {
+    use std::convert::TryFrom;
+
+    // a `File` element can't be at the top-level
+    let error = traverse(parser::_DocumentReader::new(&FILE[..])).unwrap_err();
+    let id = match error {
+        ReaderError::Parse(nom::Err::Failure(StateError::InvalidChildId(None, id)), 0) => id,
+        error => panic!("unexpected error: {}", error),
+    };
+    let element = element_defs::ElementId::try_from(id).unwrap();
+    assert_eq!(element, element_defs::ElementId::File);
+    assert_eq!(element.to_string(), "File");
+    assert_eq!("File".parse(), Ok(element));
+    assert_eq!(u32::from(element), element_defs::FileDef::ID);
+
+    assert_eq!(element_defs::ElementId::try_from(0x4286), Err(0x4286));
+    assert_eq!("EBMLVersion".parse::<element_defs::ElementId>(), Err(()));
+}
+
+fn traverse<R: std::io::BufRead>(reader: parser::_DocumentReader<R>) -> Result<(), ReaderError> {
+    let mut reader: parser::Readers<_> = reader.into();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                result => result?.into(),
+            },
+            parser::Readers::Void(r) => r.next()?.into(),
+            parser::Readers::CRC32(r) => r.next()?.into(),
+            parser::Readers::Files(r) => r.next()?.into(),
+            parser::Readers::File(r) => r.next()?.into(),
+            parser::Readers::FileName(r) => r.next()?.into(),
+            parser::Readers::MimeType(r) => r.next()?.into(),
+            parser::Readers::ModificationTimestamp(r) => r.next()?.into(),
+            parser::Readers::Data(r) => r.next()?.into(),
+            parser::Readers::Tag(r) => r.next()?.into(),
+            parser::Readers::TagName(r) => r.next()?.into(),
+        };
+    }
+}
+
+// traverses the whole stream, logging each element along with its data
+fn read_all<R: std::io::BufRead>(reader: R) -> Vec<String> {
+    let mut reader: parser::Readers<_> = parser::_DocumentReader::new(reader).into();
+    let mut result = Vec::new();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Ok(r_next) => r_next.into(),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
+                Err(e) => panic!("{}", e),
+            },
+            parser::Readers::Void(r) => {
+                result.push("Void".to_string());
+                r.skip().unwrap().into()
+            }
+            parser::Readers::CRC32(r) => {
+                result.push("CRC32".to_string());
+                r.next().unwrap().into()
+            }
+            parser::Readers::Files(r) => {
+                result.push("Files".to_string());
+                r.next().unwrap().into()
+            }
+            parser::Readers::File(r) => {
+                result.push("File".to_string());
+                r.next().unwrap().into()
+            }
+            parser::Readers::FileName(mut r) => {
+                result.push(format!("FileName: {}", r.read().unwrap()));
+                r.next().unwrap().into()
+            }
+            parser::Readers::MimeType(mut r) => {
+                result.push(format!("MimeType: {}", r.read().unwrap()));
+                r.next().unwrap().into()
+            }
+            parser::Readers::ModificationTimestamp(mut r) => {
+                result.push(format!("ModTime: {}", r.read().unwrap()));
+                r.next().unwrap().into()
+            }
+            parser::Readers::Data(mut r) => {
+                let data: &[u8] = r.read().unwrap();
+                result.push(format!("Data: {:?}", data));
+                r.next().unwrap().into()
+            }
+            parser::Readers::Tag(r) => {
+                result.push("Tag".to_string());
+                r.next().unwrap().into()
+            }
+            parser::Readers::TagName(mut r) => {
+                result.push(format!("TagName: {}", r.read().unwrap()));
+                r.next().unwrap().into()
+            }
+        };
+    }
+
+    result
+}
+
+#[test]
+fn read_across_buffer_refills() {
+    let expected = read_all(&BYTE_STREAM[..]);
+    assert_eq!(expected.len(), 34);
+
+    // most headers & payloads straddle the end of the reader's buffer
+    let result = read_all(std::io::BufReader::with_capacity(3, &BYTE_STREAM[..]));
+    assert_eq!(result, expected);
+}
+
+// a complete File element, with all of its mandatory children
+const FILE: [u8; 29] = [
+    0x61, 0x46, // File element ID
+    0x9A, // File length = 26
+    0x61, 0x4E, // FileName element ID
+    0x81, // FileName length = 1
+    0x61, // FileName data = "a"
+    0x46, 0x4D, // MimeType element ID
+    0x81, // MimeType length = 1
+    0x62, // MimeType data = "b"
+    0x46, 0x54, // ModificationTimestamp element ID
+    0x88, // ModificationTimestamp length = 8
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ModificationTimestamp data
+    0x46, 0x64, // Data element ID
+    0x84, // Data length = 4
+    0x01, 0x02, 0x03, 0x04, // Data data
+];
+
+#[test]
+fn read_unknown_size_element() {
+    let stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0xFF, // Files length = unknown
+        ][..],
+        &FILE[..],
+        &[
+            0x19, 0x46, 0x69,
+            0x6C, // Files element ID -> not a child, ends the unknown-size Files
+            0x9D, // Files length = 29
+        ][..],
+        &FILE[..],
+    ]
+    .concat();
+
+    let file_log = [
+        "File",
+        "FileName: a",
+        "File",
+        "MimeType: b",
+        "File",
+        "ModTime: 0",
+        "File",
+        "Data: [1, 2, 3, 4]",
+        "File",
+    ];
+    let expt_result: Vec<_> = [
+        &["Files"][..],
+        &file_log[..],
+        &["Files", "Files"][..],
+        &file_log[..],
+        &["Files"][..],
+    ]
+    .concat();
+
+    assert_eq!(read_all(&stream[..]), expt_result);
+}
+
+#[test]
+fn read_missing_mandatory_child() {
+    // a File without its mandatory Data element
+    let stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0x96, // Files length = 22
+            0x61, 0x46, // File element ID
+            0x93, // File length = 19
+        ][..],
+        &FILE[3..22],
+    ]
+    .concat();
+
+    let error = traverse(parser::_DocumentReader::new(&stream[..])).unwrap_err();
+    assert!(matches!(
+        error,
+        ReaderError::Parse(
+            nom::Err::Failure(StateError::OccurrenceViolation {
+                parent_id: 0x6146,
+                child_id: 0x4664,
+                count: 0,
+            }),
+            _
+        )
+    ));
+}
+
+#[test]
+fn read_crc() {
+    let crc = crc32::update(0, &FILE);
+    let with_crc = |crc: u32| {
+        [
+            &[
+                0x19, 0x46, 0x69, 0x6C, // Files element ID
+                0xA3, // Files length = 35
+                0xBF, // CRC32 element ID
+                0x84, // CRC32 length = 4
+            ][..],
+            &crc.to_le_bytes()[..], // CRC32 data -> covers the File element following it
+            &FILE[..],
+        ]
+        .concat()
+    };
+
+    assert!(traverse(parser::_DocumentReader::new(&with_crc(crc)[..])).is_ok());
+
+    let stream = with_crc(crc ^ 1);
+    let error = traverse(parser::_DocumentReader::new(&stream[..])).unwrap_err();
+    assert!(matches!(
+        error,
+        ReaderError::Parse(nom::Err::Failure(StateError::CrcMismatch {
+            id: 0x1946696C,
+            expected,
+            actual,
+        }), _) if expected == crc ^ 1 && actual == crc
+    ));
+
+    // w/o verification, the mismatch goes unnoticed
+    assert!(traverse(parser::_DocumentReader::new(&stream[..]).verify_crc(false)).is_ok());
+}
+
+#[test]
+fn read_written_crc() {
+    let mut crc_element = [0u8; 6];
+    serialize::crc32_element(&mut crc_element[..], &FILE).unwrap();
+    let stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0xA3, // Files length = 35
+        ][..],
+        &crc_element[..],
+        &FILE[..],
+    ]
+    .concat();
+
+    assert!(traverse(parser::_DocumentReader::new(&stream[..])).is_ok());
+}
+
+#[test]
+fn write_with_header() {
+    let mut writer = ElementWriter::new(Vec::new());
+    writer
+        .write_header(&EbmlHeader::new("files-in-ebml-demo", 1, 1))
+        .unwrap();
+    writer::copy_document(&BYTE_STREAM[..], &mut writer).unwrap();
+    let stream = writer.finish().unwrap();
+
+    let (header, reader) = parser::_DocumentReader::from_header(&stream[..]).unwrap();
+    assert_eq!(header.doc_type, "files-in-ebml-demo");
+    assert_eq!(header.max_size_length, 8);
+    assert_eq!(&stream[reader.position() as usize..], &BYTE_STREAM[..]);
+    assert!(traverse(reader).is_ok());
+}
+
+#[test]
+fn write_invalid_length() {
+    let mut writer = ElementWriter::new(Vec::new());
+    assert!(matches!(
+        writer.write_binary::<element_defs::CRC32Def>(&[0x01, 0x02, 0x03]),
+        Err(WriterError::LengthOutOfRange { id: 0xBF, len: 3 })
+    ));
+    writer
+        .write_binary::<element_defs::CRC32Def>(&[0x01, 0x02, 0x03, 0x04])
+        .unwrap();
+    assert_eq!(
+        writer.finish().unwrap(),
+        &[0xBF, 0x84, 0x01, 0x02, 0x03, 0x04][..]
+    );
+}
+
+#[test]
+fn read_lenient() {
+    let unknown = [
+        0x4A, 0xBC, // unknown element ID
+        0x82, // unknown length = 2
+        0xAA, 0xBB, // unknown data
+    ];
+    let stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0xA7, // Files length = 39
+        ][..],
+        &unknown[..],
+        &[
+            0x61, 0x46, // File element ID
+            0x9F, // File length = 31
+        ][..],
+        &FILE[3..7], // FileName
+        &unknown[..],
+        &FILE[7..], // MimeType, ModificationTimestamp, Data
+        &unknown[..],
+    ]
+    .concat();
+
+    let error = traverse(parser::_DocumentReader::new(&stream[..])).unwrap_err();
+    assert_eq!(
+        error,
+        ReaderError::Parse(
+            nom::Err::Failure(StateError::InvalidChildId(Some(0x1946696C), 0x4ABC)),
+            5
+        )
+    );
+
+    // unknown elements are skipped, both within other elements & at the top-level
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(&stream[..])).lenient(true);
+    assert_eq!(seek_file_names(reader).unwrap(), vec!["a"]);
+}
+
+#[test]
+fn read_child_exceeding_parent() {
+    // a Files element that ends before its File child does
+    let stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0x85, // Files length = 5
+        ][..],
+        &FILE[..],
+    ]
+    .concat();
+
+    let error = traverse(parser::_DocumentReader::new(&stream[..])).unwrap_err();
+    assert_eq!(
+        error,
+        ReaderError::Parse(
+            nom::Err::Failure(StateError::ExceedsParent {
+                parent_id: 0x1946696C,
+                id: 0x6146,
+                len: 26,
+            }),
+            5
+        )
+    );
+
+    // the same goes for unknown elements, which can't be skipped past the end of their parent
+    let stream = [
+        0x19, 0x46, 0x69, 0x6C, // Files element ID
+        0x84, // Files length = 4
+        0x4A, 0xBC, // unknown element ID
+        0x82, // unknown length = 2
+        0xAA, 0xBB, // unknown data
+    ];
+    let reader = parser::_DocumentReader::new(&stream[..]).lenient(true);
+    assert_eq!(
+        traverse(reader).unwrap_err(),
+        ReaderError::Parse(
+            nom::Err::Failure(StateError::ExceedsParent {
+                parent_id: 0x1946696C,
+                id: 0x4ABC,
+                len: 2,
+            }),
+            5
+        )
+    );
+}
+
+#[test]
+fn read_with_header_limits() {
+    // a header that only allows element IDs of up to `max_id_length` octets
+    let with_header = |max_id_length: u8| {
+        [
+            &[
+                0x1A,
+                0x45,
+                0xDF,
+                0xA3, // EBML element ID
+                0x84, // EBML length = 4
+                0x42,
+                0xF2, // EBMLMaxIDLength element ID
+                0x81, // EBMLMaxIDLength length = 1
+                max_id_length,
+            ][..],
+            &BYTE_STREAM[..],
+        ]
+        .concat()
+    };
+
+    let stream = with_header(4);
+    let (header, reader) = parser::_DocumentReader::from_header(&stream[..]).unwrap();
+    assert_eq!(header.max_id_length, 4);
+    assert_eq!(reader.position(), 9);
+    assert!(matches!(
+        reader.next().unwrap(),
+        parser::_DocumentNextReaders::Files(_)
+    ));
+
+    // the `Files` element ID is 4 octets wide
+    let stream = with_header(3);
+    let (header, reader) = parser::_DocumentReader::from_header(&stream[..]).unwrap();
+    assert_eq!(reader.state.config.max_id_length, 3);
+    assert_eq!(header.max_id_length, 3);
+    let error = reader.next().unwrap_err();
+    assert!(matches!(error, ReaderError::Parse(nom::Err::Error(_), 9)));
+    assert!(error.to_string().starts_with("ParseError at offset 0x9:"));
+}
+
+#[test]
+fn read_with_capacity() {
+    let reader = parser::_DocumentReader::with_capacity(1, &BYTE_STREAM[..]);
+    assert_eq!(reader.reader.capacity(), 1);
+    let reader = parser::_DocumentReader::from_read(&BYTE_STREAM[..]);
+    assert_eq!(reader.reader.capacity(), DEFAULT_BUFFER_CAPACITY);
+
+    let reader = parser::_DocumentReader::with_capacity(1, &BYTE_STREAM[..]);
+    let files = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    assert_eq!(files.state.bytes_left, ElementLength::Known(90));
+}
+
+// a reader that yields at most one octet per read, like a slow network stream
+struct TrickleReader<'a>(&'a [u8]);
+
+impl std::io::Read for TrickleReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(1);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn read_from_unbuffered() {
+    // every element header straddles the end of the read-ahead
+    let result = read_all(std::io::BufReader::with_capacity(
+        2,
+        TrickleReader(&BYTE_STREAM[..]),
+    ));
+    assert_eq!(result, read_all(&BYTE_STREAM[..]));
+
+    let reader = parser::_DocumentReader::from_read(TrickleReader(&BYTE_STREAM[..]));
+    assert!(traverse(reader).is_ok());
+}
+
 #[test]
 fn find_all_element_instances() {
     let mut reader: parser::Readers<_> = parser::_DocumentReader::new(&BYTE_STREAM[..]).into();
@@ -123,10 +663,11 @@ fn find_all_element_instances() {
         reader = match reader {
             parser::Readers::_Document(r) => match r.next() {
                 Ok(r_next) => r_next.into(),
-                Err(ReaderError::Parse(nom::Err::Incomplete(_))) => break,
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
                 Err(_) => panic!(), // in an actual function, this should return the error
             },
             parser::Readers::Void(r) => r.next().unwrap().into(),
+            parser::Readers::CRC32(r) => r.next().unwrap().into(),
             parser::Readers::Files(r) => r.next().unwrap().into(),
             parser::Readers::File(r) => r.next().unwrap().into(),
             parser::Readers::FileName(mut r) => {
@@ -136,6 +677,8 @@ fn find_all_element_instances() {
             parser::Readers::MimeType(r) => r.skip().unwrap().into(),
             parser::Readers::ModificationTimestamp(r) => r.skip().unwrap().into(),
             parser::Readers::Data(r) => r.skip().unwrap().into(),
+            parser::Readers::Tag(r) => r.skip().unwrap().into(),
+            parser::Readers::TagName(r) => r.skip().unwrap().into(),
         };
     }
 
@@ -148,3 +691,1170 @@ fn find_all_element_instances() {
         ]
     );
 }
+
+#[test]
+fn collect_elements() {
+    let file_names: Vec<String> =
+        parser::collect_by_id(&BYTE_STREAM[..], element_defs::FileNameDef::ID).unwrap();
+    assert_eq!(file_names, vec!["file3.html", "file2.csv", "file1.txt"]);
+
+    let mime_types: Vec<String> = parser::collect_by_name(&BYTE_STREAM[..], "MimeType").unwrap();
+    assert_eq!(mime_types, vec!["text/html", "text/csv", "text/plain"]);
+    let data: Vec<ElementValue> = parser::collect_by_name(&BYTE_STREAM[..], "Data").unwrap();
+    assert!(data
+        .iter()
+        .all(|value| matches!(value, ElementValue::Binary(_))));
+
+    assert_eq!(
+        parser::collect_by_name::<u64, _>(&BYTE_STREAM[..], "FileName"),
+        Err(ReaderError::DataType(element_defs::FileNameDef::ID))
+    );
+    assert_eq!(
+        parser::collect_by_name::<String, _>(&BYTE_STREAM[..], "Nope"),
+        Err(ReaderError::UnknownName("Nope".to_string()))
+    );
+}
+
+// collects all file names, seeking past the data of each file
+fn seek_file_names<R: std::io::BufRead + std::io::Seek>(
+    reader: parser::_DocumentReader<R>,
+) -> Result<Vec<String>, ReaderError> {
+    let mut reader: parser::Readers<_> = reader.into();
+    let mut result = Vec::new();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(result),
+                r_next => r_next?.into(),
+            },
+            parser::Readers::Void(r) => r.seek_skip()?.into(),
+            parser::Readers::CRC32(r) => r.seek_skip()?.into(),
+            parser::Readers::Files(r) => r.next()?.into(),
+            parser::Readers::File(r) => r.next()?.into(),
+            parser::Readers::FileName(mut r) => {
+                result.push(r.read()?.to_string());
+                r.next()?.into()
+            }
+            parser::Readers::MimeType(r) => r.seek_skip()?.into(),
+            parser::Readers::ModificationTimestamp(r) => r.seek_skip()?.into(),
+            parser::Readers::Data(mut r) => {
+                // peek at the data first, so that some of it may be buffered
+                assert!(!r.read()?.is_empty());
+                r.seek_skip()?.into()
+            }
+            parser::Readers::Tag(r) => r.seek_skip()?.into(),
+            parser::Readers::TagName(r) => r.seek_skip()?.into(),
+        };
+    }
+}
+
+#[test]
+fn seek_skip() {
+    let expt_result = vec!["file3.html", "file2.csv", "file1.txt"];
+
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(&BYTE_STREAM[..]));
+    assert_eq!(seek_file_names(reader).unwrap(), expt_result);
+
+    // element data straddles the end of the reader's buffer
+    let reader = parser::_DocumentReader::new(std::io::BufReader::with_capacity(
+        3,
+        std::io::Cursor::new(&BYTE_STREAM[..]),
+    ));
+    assert_eq!(seek_file_names(reader).unwrap(), expt_result);
+}
+
+#[test]
+fn seek_skip_with_crc() {
+    let mut stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0xA3, // Files length = 35
+            0xBF, // CRC32 element ID
+            0x84, // CRC32 length = 4
+        ][..],
+        &crc32::update(0, &FILE).to_le_bytes()[..],
+        &FILE[..],
+    ]
+    .concat();
+    let last = stream.len() - 1;
+    stream[last] ^= 1; // corrupts the Data element
+
+    // the skipped Data is still read through, to check the CRC
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(&stream[..]));
+    assert!(matches!(
+        seek_file_names(reader),
+        Err(ReaderError::Parse(
+            nom::Err::Failure(StateError::CrcMismatch { .. }),
+            _
+        ))
+    ));
+
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(&stream[..])).verify_crc(false);
+    assert_eq!(seek_file_names(reader).unwrap(), vec!["a"]);
+}
+
+// compares skipping a large element by reading vs. by seeking
+// -> run w/ `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_skip_large_element() {
+    const DATA_LEN: u64 = 100 * 1024 * 1024;
+    let stream = [
+        &[
+            0x46, 0x64, // Data element ID
+            0x01, // Data length (8 octets)
+        ][..],
+        &DATA_LEN.to_be_bytes()[1..],
+        &vec![0u8; DATA_LEN as usize][..],
+    ]
+    .concat();
+    let data_state = |reader| -> parser::DataReader<_> {
+        let file_state = parser::FileState::new(
+            ElementLength::Unknown,
+            parser::FilesState::new(ElementLength::Unknown, Default::default()),
+        );
+        match parser::FileReader::new(reader, file_state).next().unwrap() {
+            parser::FileNextReaders::Data(r) => r,
+            _ => panic!("expected Data element"),
+        }
+    };
+
+    let start = std::time::Instant::now();
+    let reader = data_state(std::io::BufReader::new(std::io::Cursor::new(&stream[..])));
+    let read_file = reader.skip().unwrap();
+    let read_time = start.elapsed();
+
+    let start = std::time::Instant::now();
+    let reader = data_state(std::io::BufReader::new(std::io::Cursor::new(&stream[..])));
+    let seek_file = reader.seek_skip().unwrap();
+    let seek_time = start.elapsed();
+
+    println!(
+        "skipping {} octets: read = {:?}, seek = {:?}",
+        DATA_LEN, read_time, seek_time
+    );
+    assert_eq!(read_file.state, seek_file.state);
+    assert!(seek_time < read_time);
+}
+
+static ZEROS: [u8; 1 << 20] = [0; 1 << 20];
+
+// yields the given number of zeros, w/o ever holding more than a chunk of them in memory
+struct Zeros(u64);
+
+impl Zeros {
+    fn chunk(&self) -> &'static [u8] {
+        &ZEROS[..self.0.min(ZEROS.len() as u64) as usize]
+    }
+}
+
+impl std::io::Read for Zeros {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.chunk().len().min(buf.len());
+        buf[..len].fill(0);
+        self.0 -= len as u64;
+        Ok(len)
+    }
+}
+
+impl std::io::BufRead for Zeros {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.chunk())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0 -= amt as u64;
+    }
+}
+
+impl AsyncRead for Zeros {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let len = std::io::Read::read(self.get_mut(), buf.initialize_unfilled())?;
+        buf.advance(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncBufRead for Zeros {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(Ok(self.get_mut().chunk()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        std::io::BufRead::consume(self.get_mut(), amt);
+    }
+}
+
+#[test]
+fn skip_large_element_unbuffered() {
+    // far more data than could be buffered -> skipping must stream past it
+    const DATA_LEN: u64 = 1 << 36;
+    let header = [
+        &[
+            0x46, 0x64, // Data element ID
+            0x01, // Data length (8 octets)
+        ][..],
+        &DATA_LEN.to_be_bytes()[1..],
+    ]
+    .concat();
+    let file_state = || {
+        parser::FileState::new(
+            ElementLength::Unknown,
+            parser::FilesState::new(ElementLength::Unknown, Default::default()),
+        )
+    };
+
+    let reader = std::io::Read::chain(&header[..], Zeros(DATA_LEN));
+    let file = parser::FileReader::new(reader, file_state());
+    let data = match file.next().unwrap() {
+        parser::FileNextReaders::Data(r) => r,
+        _ => panic!("expected Data element"),
+    };
+    let file = data.skip().unwrap();
+    assert_eq!(file.position(), header.len() as u64 + DATA_LEN);
+    assert_eq!(file.reader.into_inner().1 .0, 0);
+
+    let reader = tokio::io::AsyncReadExt::chain(&header[..], Zeros(DATA_LEN));
+    let (state, r) = block_on(AsyncElementReader::new(reader, file_state()).next())
+        .unwrap()
+        .into_parts();
+    let data = match state {
+        parser::FileNextStates::Data(s) => r.with_state(s),
+        _ => panic!("expected Data element"),
+    };
+    let file = block_on(data.skip()).unwrap();
+    assert_eq!(file.position(), header.len() as u64 + DATA_LEN);
+    assert_eq!(file.reader.into_inner().1 .0, 0);
+}
+
+#[test]
+fn skip_element_beyond_32_bits() {
+    // a known-length File that can be entered & skipped, even where its data can't be addressed
+    const FILE_LEN: u64 = 1 << 32;
+    let file_header = [
+        &[
+            0x61, 0x46, // File element ID
+            0x01, // File length (8 octets)
+        ][..],
+        &FILE_LEN.to_be_bytes()[1..],
+    ]
+    .concat();
+    let files_len = file_header.len() as u64 + FILE_LEN;
+    let header = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0x01, // Files length (8 octets)
+        ][..],
+        &files_len.to_be_bytes()[1..],
+        &file_header[..],
+    ]
+    .concat();
+
+    let reader = std::io::Read::chain(&header[..], Zeros(FILE_LEN));
+    let files = match parser::_DocumentReader::new(reader).next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    let file = match files.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    assert_eq!(file.state.bytes_left, ElementLength::Known(FILE_LEN));
+
+    let files = file.skip().unwrap();
+    assert_eq!(files.state.bytes_left, ElementLength::Known(0));
+    assert!(matches!(
+        files.next().unwrap(),
+        parser::FilesNextReaders::Parent(r) if r.position() == header.len() as u64 + FILE_LEN
+    ));
+}
+
+#[test]
+fn read_data_into_bytes() {
+    let mut reader: parser::Readers<_> = parser::_DocumentReader::new(&BYTE_STREAM[..]).into();
+    let mut result = Vec::new();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Ok(r_next) => r_next.into(),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
+                Err(_) => panic!(),
+            },
+            parser::Readers::Void(r) => r.next().unwrap().into(),
+            parser::Readers::CRC32(r) => r.next().unwrap().into(),
+            parser::Readers::Files(r) => r.next().unwrap().into(),
+            parser::Readers::File(r) => r.next().unwrap().into(),
+            parser::Readers::FileName(r) => r.skip().unwrap().into(),
+            parser::Readers::MimeType(r) => r.skip().unwrap().into(),
+            parser::Readers::ModificationTimestamp(r) => r.skip().unwrap().into(),
+            parser::Readers::Data(mut r) => {
+                result.push(r.copy_to_bytes().unwrap());
+                r.next().unwrap().into()
+            }
+            parser::Readers::Tag(r) => r.skip().unwrap().into(),
+            parser::Readers::TagName(r) => r.skip().unwrap().into(),
+        };
+    }
+
+    assert_eq!(result.len(), 3);
+    for payload in result {
+        assert_eq!(&payload[..], &[0x01, 0x02, 0x03, 0x04]);
+    }
+}
+
+#[test]
+fn read_data_as_shared_bytes() {
+    let stream = bytes::Bytes::from_static(&BYTE_STREAM);
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(stream.clone()));
+    let reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        r => panic!("unexpected reader {:?}", r),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        r => panic!("unexpected reader {:?}", r),
+    };
+    let mut reader = reader.next().unwrap();
+    let mut reader = loop {
+        reader = match reader {
+            parser::FileNextReaders::Data(r) => break r,
+            parser::FileNextReaders::FileName(r) => r.next().unwrap().next().unwrap(),
+            parser::FileNextReaders::MimeType(r) => r.next().unwrap().next().unwrap(),
+            parser::FileNextReaders::ModificationTimestamp(r) => r.next().unwrap().next().unwrap(),
+            r => panic!("unexpected reader {:?}", r),
+        };
+    };
+
+    // the payload is a view into the stream, rather than a copy of it
+    let payload = reader.read_bytes().unwrap();
+    assert_eq!(&payload[..], &[0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(payload.as_ptr(), stream[47..].as_ptr());
+    assert_eq!(reader.copy_to_bytes().unwrap(), payload);
+}
+
+#[test]
+fn read_data_in_chunks() {
+    let stream = std::io::BufReader::with_capacity(3, &BYTE_STREAM[..]);
+    let mut reader: parser::Readers<_> = parser::_DocumentReader::new(stream).into();
+    let mut chunks = Vec::new();
+    let mut copied = Vec::new();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => match r.next() {
+                Ok(r_next) => r_next.into(),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => break,
+                Err(_) => panic!(),
+            },
+            parser::Readers::Files(r) => r.next().unwrap().into(),
+            parser::Readers::File(r) => r.next().unwrap().into(),
+            parser::Readers::Data(mut r) if chunks.is_empty() => {
+                let mut data_chunks = r.binary_chunks();
+                while let Some(chunk) = data_chunks.next_chunk() {
+                    chunks.push(chunk.unwrap().to_vec());
+                }
+                r.next().unwrap().into()
+            }
+            parser::Readers::Data(mut r) => {
+                std::io::copy(&mut r.binary_chunks(), &mut copied).unwrap();
+                r.next().unwrap().into()
+            }
+            parser::Readers::Void(r) => r.skip().unwrap().into(),
+            parser::Readers::CRC32(r) => r.skip().unwrap().into(),
+            parser::Readers::FileName(r) => r.skip().unwrap().into(),
+            parser::Readers::MimeType(r) => r.skip().unwrap().into(),
+            parser::Readers::ModificationTimestamp(r) => r.skip().unwrap().into(),
+            parser::Readers::Tag(r) => r.skip().unwrap().into(),
+            parser::Readers::TagName(r) => r.skip().unwrap().into(),
+        };
+    }
+
+    // the payload never has to fit in the reader's 3-octet buffer all at once
+    assert!(chunks.len() > 1 && chunks.iter().all(|chunk| chunk.len() <= 3));
+    assert_eq!(chunks.concat(), &[0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(copied, [[0x01, 0x02, 0x03, 0x04]; 2].concat());
+}
+
+#[test]
+fn read_truncated_data() {
+    // cut the stream off halfway through the first `Data` element's payload
+    let mut reader: parser::Readers<_> = parser::_DocumentReader::new(&BYTE_STREAM[..49]).into();
+
+    loop {
+        reader = match reader {
+            parser::Readers::_Document(r) => r.next().unwrap().into(),
+            parser::Readers::Files(r) => r.next().unwrap().into(),
+            parser::Readers::File(r) => r.next().unwrap().into(),
+            parser::Readers::Data(mut r) => {
+                assert_eq!(r.read().err(), Some(ReaderError::UnexpectedEof));
+                break;
+            }
+            parser::Readers::Void(r) => r.skip().unwrap().into(),
+            parser::Readers::CRC32(r) => r.skip().unwrap().into(),
+            parser::Readers::FileName(r) => r.skip().unwrap().into(),
+            parser::Readers::MimeType(r) => r.skip().unwrap().into(),
+            parser::Readers::ModificationTimestamp(r) => r.skip().unwrap().into(),
+            parser::Readers::Tag(r) => r.skip().unwrap().into(),
+            parser::Readers::TagName(r) => r.skip().unwrap().into(),
+        };
+    }
+}
+
+#[derive(Default)]
+struct FileNameCollector(Vec<String>);
+
+impl ElementHandler for FileNameCollector {
+    fn handle(&mut self, _id: u32, data: ElementData) {
+        if let ElementData::Utf8(name) = data {
+            self.0.push(name.to_string());
+        }
+    }
+}
+
+#[derive(Default)]
+struct DataByteCounter(usize);
+
+impl ElementHandler for DataByteCounter {
+    fn handle(&mut self, _id: u32, data: ElementData) {
+        if let ElementData::Binary(bytes) = data {
+            self.0 += bytes.len();
+        }
+    }
+}
+
+#[test]
+fn walk_with_handlers() {
+    let mut file_names = FileNameCollector::default();
+    let mut data_bytes = DataByteCounter::default();
+
+    let mut registry = HandlerRegistry::new();
+    registry
+        .register::<element_defs::FileNameDef, _>(&mut file_names)
+        .register::<element_defs::DataDef, _>(&mut data_bytes);
+    parser::walk_with_handlers(&BYTE_STREAM[..], &mut registry).unwrap();
+    drop(registry);
+
+    assert_eq!(file_names.0, vec!["file3.html", "file2.csv", "file1.txt"]);
+    assert_eq!(data_bytes.0, 12);
+}
+
+#[derive(Default)]
+struct FileVisitor {
+    files: usize,
+    file_names: Vec<String>,
+}
+
+impl<R: std::io::BufRead> parser::Visitor<R> for FileVisitor {
+    fn visit_file(&mut self, _reader: &mut parser::FileReader<R>) -> Result<(), ReaderError> {
+        self.files += 1;
+        Ok(())
+    }
+
+    fn visit_file_name(
+        &mut self,
+        reader: &mut parser::FileNameReader<R>,
+    ) -> Result<(), ReaderError> {
+        self.file_names.push(reader.read()?.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn drive_visitor() {
+    let mut visitor = FileVisitor::default();
+    parser::drive(&BYTE_STREAM[..], &mut visitor).unwrap();
+
+    assert_eq!(visitor.files, 3);
+    assert_eq!(
+        visitor.file_names,
+        vec!["file3.html", "file2.csv", "file1.txt"]
+    );
+}
+
+#[test]
+fn element_events() {
+    let file_names: Vec<_> = parser::ElementEvents::new(&BYTE_STREAM[..])
+        .filter_map(|event| match event.unwrap() {
+            ElementEvent::Data(id, ElementValue::Utf8(name))
+                if id == element_defs::FileNameDef::ID =>
+            {
+                Some(name)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(file_names, vec!["file3.html", "file2.csv", "file1.txt"]);
+
+    let stream = [&[0x19, 0x46, 0x69, 0x6C, 0x9D][..], &FILE[..]].concat();
+    let events: Vec<_> = parser::ElementEvents::new(&stream[..])
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ElementEvent::Enter(element_defs::FilesDef::ID),
+            ElementEvent::Enter(element_defs::FileDef::ID),
+            ElementEvent::Data(
+                element_defs::FileNameDef::ID,
+                ElementValue::Utf8("a".to_string())
+            ),
+            ElementEvent::Data(
+                element_defs::MimeTypeDef::ID,
+                ElementValue::String("b".to_string())
+            ),
+            ElementEvent::Data(
+                element_defs::ModificationTimestampDef::ID,
+                ElementValue::Date(0)
+            ),
+            ElementEvent::Data(
+                element_defs::DataDef::ID,
+                ElementValue::Binary(vec![1, 2, 3, 4])
+            ),
+            ElementEvent::Leave(element_defs::FileDef::ID),
+            ElementEvent::Leave(element_defs::FilesDef::ID),
+        ]
+    );
+}
+
+#[test]
+fn read_recursive_element() {
+    let stream = [
+        &[
+            0x19, 0x46, 0x69, 0x6C, // Files element ID
+            0xAB, // Files length = 43
+            0x61, 0x46, // File element ID
+            0xA8, // File length = 40
+        ][..],
+        &FILE[3..],
+        &[
+            0x67, 0xC8, // Tag element ID
+            0x8B, // Tag length = 11
+            0x45, 0xA3, // TagName element ID
+            0x81, // TagName length = 1
+            0x61, // TagName data = "a"
+            0x67, 0xC8, // (nested) Tag element ID
+            0x84, // Tag length = 4
+            0x45, 0xA3, // TagName element ID
+            0x81, // TagName length = 1
+            0x62, // TagName data = "b"
+        ][..],
+    ]
+    .concat();
+
+    let events: Vec<_> = parser::ElementEvents::new(&stream[..])
+        .map(Result::unwrap)
+        .skip(6) // the `Files` & `File` elements' headers & data
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            ElementEvent::Enter(element_defs::TagDef::ID),
+            ElementEvent::Data(
+                element_defs::TagNameDef::ID,
+                ElementValue::Utf8("a".to_string())
+            ),
+            ElementEvent::Enter(element_defs::TagDef::ID),
+            ElementEvent::Data(
+                element_defs::TagNameDef::ID,
+                ElementValue::Utf8("b".to_string())
+            ),
+            ElementEvent::Leave(element_defs::TagDef::ID),
+            ElementEvent::Leave(element_defs::TagDef::ID),
+            ElementEvent::Leave(element_defs::FileDef::ID),
+            ElementEvent::Leave(element_defs::FilesDef::ID),
+        ]
+    );
+
+    // the nested `Tag` is a child of its parent `Tag`, & returns to it once done
+    let mut reader: parser::Readers<_> = parser::_DocumentReader::new(&stream[..]).into();
+    let outer_tag = loop {
+        reader = match reader {
+            parser::Readers::Tag(r) => break r,
+            parser::Readers::_Document(r) => r.next().unwrap().into(),
+            parser::Readers::Files(r) => r.next().unwrap().into(),
+            parser::Readers::File(r) => r.next().unwrap().into(),
+            parser::Readers::FileName(r) => r.next().unwrap().into(),
+            parser::Readers::MimeType(r) => r.next().unwrap().into(),
+            parser::Readers::ModificationTimestamp(r) => r.next().unwrap().into(),
+            parser::Readers::Data(r) => r.next().unwrap().into(),
+            _ => panic!("unexpected element"),
+        };
+    };
+    let outer_tag = match outer_tag.next().unwrap() {
+        parser::TagNextReaders::TagName(r) => r.next().unwrap(),
+        _ => panic!("expected TagName element"),
+    };
+    let inner_tag = match outer_tag.next().unwrap() {
+        parser::TagNextReaders::Tag(r) => r,
+        _ => panic!("expected nested Tag element"),
+    };
+    assert!(matches!(
+        inner_tag.skip().unwrap(),
+        parser::TagPrevReaders::Tag(_)
+    ));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Files {
+    file: Vec<File>,
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct File {
+    file_name: String,
+    mime_type: String,
+    modification_timestamp: i64,
+    data: Vec<u8>,
+}
+
+#[test]
+fn deserialize_document() {
+    let files: Files = parser::from_ebml(&BYTE_STREAM[..]).unwrap();
+    assert_eq!(
+        files,
+        Files {
+            file: vec![
+                File {
+                    file_name: "file3.html".to_string(),
+                    mime_type: "text/html".to_string(),
+                    modification_timestamp: 0,
+                    data: vec![1, 2, 3, 4],
+                },
+                File {
+                    file_name: "file2.csv".to_string(),
+                    mime_type: "text/csv".to_string(),
+                    modification_timestamp: 0,
+                    data: vec![1, 2, 3, 4],
+                },
+            ]
+        }
+    );
+
+    // a single `File` field can't hold both of the `File` elements
+    #[derive(Debug, serde::Deserialize)]
+    struct SingleFile {
+        #[allow(dead_code)]
+        file: File,
+    }
+    assert!(matches!(
+        parser::from_ebml::<SingleFile, _>(&BYTE_STREAM[..]),
+        Err(DeError::MultipleOccurrences(0x6146, 2))
+    ));
+}
+
+#[test]
+fn parse_document_tree() {
+    let tree = parser::Document::parse(&BYTE_STREAM[..]).unwrap();
+    assert_eq!(tree.elements.len(), 2);
+    assert_eq!(
+        tree.get("Files/File/FileName"),
+        vec![
+            &ElementValue::Utf8("file3.html".to_string()),
+            &ElementValue::Utf8("file2.csv".to_string()),
+            &ElementValue::Utf8("file1.txt".to_string()),
+        ]
+    );
+
+    // the second `Files` element starts w/ a `Void`
+    let files = &tree.elements[1];
+    assert_eq!(files.id, element_defs::FilesDef::ID);
+    assert_eq!(files.children()[0].name, "Void");
+    let file = files.children_named("File").next().unwrap();
+    assert_eq!(
+        file.children_named("MimeType").next().unwrap().value(),
+        Some(&ElementValue::String("text/plain".to_string()))
+    );
+    assert!(tree.get("Files/File").is_empty());
+
+    assert!(matches!(
+        parser::Document::parse(&BYTE_STREAM[..49]),
+        Err(ReaderError::UnexpectedEof)
+    ));
+}
+
+#[test]
+fn element_events_error() {
+    // a File at the top-level of the document is invalid -> the iterator ends after the error
+    let stream = [&[0x19, 0x46, 0x69, 0x6C, 0x9D][..], &FILE[..], &FILE[..]].concat();
+    let mut events = parser::ElementEvents::new(&stream[..]);
+
+    assert_eq!(events.by_ref().take_while(Result::is_ok).count(), 8);
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn skip_to_parent_end() {
+    let reader = match parser::_DocumentReader::new(&BYTE_STREAM[..])
+        .next()
+        .unwrap()
+    {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FileNextReaders::FileName(r) => r,
+        _ => panic!("expected FileName element"),
+    };
+
+    // skips the rest of the first `File`, landing right before the second `File`
+    let reader: parser::FilesReader<_> = reader.skip_to_parent_end().unwrap();
+    assert_eq!(reader.state.bytes_left, ElementLength::Known(44));
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    assert_eq!(reader.state.bytes_left, ElementLength::Known(41));
+    assert!(matches!(
+        reader.next().unwrap(),
+        parser::FileNextReaders::ModificationTimestamp(_)
+    ));
+}
+
+#[test]
+fn read_then_skip_rest_of_parent() {
+    let first_file = || {
+        let reader = match parser::_DocumentReader::new(&BYTE_STREAM[..])
+            .next()
+            .unwrap()
+        {
+            parser::_DocumentNextReaders::Files(r) => r,
+            _ => panic!("expected Files element"),
+        };
+        match reader.next().unwrap() {
+            parser::FilesNextReaders::File(r) => r,
+            _ => panic!("expected File element"),
+        }
+    };
+    // the second `File` starts w/ its `ModificationTimestamp`
+    let is_at_second_file = |reader: parser::FilesReader<_>| {
+        let reader = match reader.next().unwrap() {
+            parser::FilesNextReaders::File(r) => r,
+            _ => panic!("expected File element"),
+        };
+        matches!(
+            reader.next().unwrap(),
+            parser::FileNextReaders::ModificationTimestamp(_)
+        )
+    };
+
+    // from the child that was read
+    let mut reader = match first_file().next().unwrap() {
+        parser::FileNextReaders::FileName(r) => r,
+        _ => panic!("expected FileName element"),
+    };
+    let name: &str = reader.read().unwrap();
+    assert_eq!(name, "file3.html");
+    let reader: parser::FilesReader<_> = reader.skip_to_parent_end().unwrap();
+    assert!(is_at_second_file(reader));
+
+    // from the parent, after returning to it
+    let mut reader = match first_file().next().unwrap() {
+        parser::FileNextReaders::FileName(r) => r,
+        _ => panic!("expected FileName element"),
+    };
+    let name: &str = reader.read().unwrap();
+    assert_eq!(name, "file3.html");
+    let reader: parser::FileReader<_> = reader.next().unwrap();
+    let reader: parser::FilesReader<_> = reader.skip().unwrap();
+    assert_eq!(reader.position(), 51);
+    assert!(is_at_second_file(reader));
+}
+
+#[test]
+fn reader_position() {
+    let reader = parser::_DocumentReader::new(&BYTE_STREAM[..]);
+    assert_eq!(reader.position(), 0);
+    let reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    assert_eq!(reader.position(), 5);
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FileNextReaders::FileName(r) => r,
+        _ => panic!("expected FileName element"),
+    };
+    assert_eq!(reader.position(), 11);
+
+    let reader: parser::FilesReader<_> = reader.skip_to_parent_end().unwrap();
+    assert_eq!(reader.position(), 51);
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    assert_eq!(reader.position(), 54);
+    let reader: parser::FilesReader<_> = reader.skip().unwrap();
+    assert_eq!(reader.position(), 95);
+}
+
+#[test]
+fn peek_id() {
+    // a 1-octet read-ahead splits every element ID across reads
+    let mut reader = parser::_DocumentReader::with_capacity(1, &BYTE_STREAM[..]);
+    assert_eq!(reader.peek_id().unwrap(), Some(0x1946696C));
+    let mut reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    assert_eq!(reader.peek_id().unwrap(), Some(0x6146));
+    assert_eq!(reader.peek_id().unwrap(), Some(0x6146));
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    let reader: parser::FilesReader<_> = reader.skip().unwrap();
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    let mut reader: parser::FilesReader<_> = reader.skip().unwrap();
+    assert_eq!(reader.peek_id().unwrap(), None);
+
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::Parent(r) => r,
+        _ => panic!("expected the end of the Files element"),
+    };
+    let mut reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    assert_eq!(reader.peek_id().unwrap(), Some(0xEC));
+    let mut reader: parser::_DocumentReader<_> = reader.skip().unwrap();
+    assert_eq!(reader.peek_id().unwrap(), None);
+}
+
+#[test]
+fn remaining_bytes() {
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(&BYTE_STREAM[..]));
+    assert_eq!(reader.remaining_in_current_master(), None);
+    assert_eq!(reader.total_remaining(), 150);
+
+    let reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    assert_eq!(reader.remaining_in_current_master(), Some(90));
+    assert_eq!(reader.total_remaining(), 145);
+
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        _ => panic!("expected File element"),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FileNextReaders::FileName(r) => r,
+        _ => panic!("expected FileName element"),
+    };
+    // `FileName` isn't a master -> reports the rest of its parent `File`
+    assert_eq!(reader.remaining_in_current_master(), Some(30));
+    assert_eq!(reader.total_remaining(), 139);
+}
+
+#[test]
+fn into_inner() {
+    let reader = parser::_DocumentReader::new(std::io::Cursor::new(&BYTE_STREAM[..]));
+    let reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        _ => panic!("expected Files element"),
+    };
+    let reader: parser::_DocumentReader<_> = reader.skip().unwrap();
+
+    let cursor = reader.into_inner();
+    assert_eq!(cursor.position(), 95);
+}
+
+#[test]
+fn element_names() {
+    assert_eq!(element_defs::id_of("File"), Some(0x6146));
+    assert_eq!(
+        element_defs::name_of(element_defs::id_of("File").unwrap()),
+        Some("File")
+    );
+    assert_eq!(element_defs::name_of(0x1A45DFA3), None);
+    assert_eq!(element_defs::id_of("EBML"), None);
+}
+
+#[test]
+fn copy_document_round_trip() {
+    let mut output = ElementWriter::new(Vec::new());
+    writer::copy_document(&BYTE_STREAM[..], &mut output).unwrap();
+
+    assert_eq!(output.finish().unwrap(), &BYTE_STREAM[..]);
+}
+
+struct PassThrough;
+
+impl<R: std::io::BufRead, W: std::io::Write> writer::Transcoder<R, W> for PassThrough {}
+
+// rewrites every `MimeType`, passing through everything else
+struct RewriteMimeTypes;
+
+impl<R: std::io::BufRead, W: std::io::Write> writer::Transcoder<R, W> for RewriteMimeTypes {
+    fn transcode_mime_type(
+        &mut self,
+        reader: &mut parser::MimeTypeReader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {
+        let mime_type: &str = reader.read()?;
+        let mime_type = mime_type.replace("text/", "application/");
+        writer.write_string::<element_defs::MimeTypeDef>(&mime_type)
+    }
+}
+
+#[test]
+fn transcode_document() {
+    let mut output = ElementWriter::new(Vec::new());
+    writer::transcode(&BYTE_STREAM[..], &mut output, &mut PassThrough).unwrap();
+    assert_eq!(output.finish().unwrap(), &BYTE_STREAM[..]);
+
+    let mut output = ElementWriter::new(Vec::new());
+    writer::transcode(&BYTE_STREAM[..], &mut output, &mut RewriteMimeTypes).unwrap();
+    let stream = output.finish().unwrap();
+
+    let mime_types = element_defs::ElementId::MimeType.id();
+    let mime_types: Vec<String> = parser::collect_by_id(&stream[..], mime_types).unwrap();
+    assert_eq!(
+        mime_types,
+        vec!["application/html", "application/csv", "application/plain"]
+    );
+    let data: Vec<Vec<u8>> = parser::collect_by_id(&stream[..], element_defs::DataDef::ID).unwrap();
+    let expt_data: Vec<Vec<u8>> =
+        parser::collect_by_id(&BYTE_STREAM[..], element_defs::DataDef::ID).unwrap();
+    assert_eq!(data, expt_data);
+}
+
+#[test]
+fn build_document() {
+    let mut output = ElementWriter::new(Vec::new());
+
+    let mut files = writer::FilesBuilder::new(&mut output);
+    let mut file = files.file();
+    file.file_name("file3.html")
+        .unwrap()
+        .mime_type("text/html")
+        .unwrap()
+        .modification_timestamp(0)
+        .unwrap()
+        .data(&[1, 2, 3, 4])
+        .unwrap();
+    file.finish().unwrap();
+    let mut file = files.file();
+    file.modification_timestamp(0)
+        .unwrap()
+        .data(&[1, 2, 3, 4])
+        .unwrap()
+        .mime_type("text/csv")
+        .unwrap()
+        .file_name("file2.csv")
+        .unwrap();
+    file.finish().unwrap();
+    files.finish().unwrap();
+
+    let mut files = writer::FilesBuilder::new(&mut output);
+    files.void(&[0xFF, 0xFF]).unwrap();
+    let mut file = files.file();
+    file.file_name("file1.txt")
+        .unwrap()
+        .mime_type("text/plain")
+        .unwrap()
+        .modification_timestamp(0)
+        .unwrap()
+        .data(&[1, 2, 3, 4])
+        .unwrap();
+    file.finish().unwrap();
+    files.finish().unwrap();
+
+    assert_eq!(output.finish().unwrap(), &BYTE_STREAM[..]);
+}
+
+#[test]
+fn read_strict_lengths() {
+    let stream = [
+        0xEC, // Void element ID
+        0x40, 0x02, // Void length = 2, in 2 octets
+        0xFF, 0xFF, // Void data
+    ];
+
+    // EBML allows non-minimal length encodings...
+    traverse(parser::_DocumentReader::new(&stream[..])).unwrap();
+
+    // ...but strict profiles don't
+    let reader = parser::_DocumentReader::new(&stream[..]).strict_lengths(true);
+    let error = traverse(reader).unwrap_err();
+    assert!(matches!(error, ReaderError::Parse(nom::Err::Error(_), 0)));
+}
+
+#[test]
+fn read_write_system_time() {
+    // 2021-01-01T00:00:00.5 UTC
+    let time = std::time::UNIX_EPOCH + std::time::Duration::new(1_609_459_200, 500_000_000);
+
+    let mut output = ElementWriter::new(Vec::new());
+    output.start::<element_defs::FilesDef>();
+    output.start::<element_defs::FileDef>();
+    output.write_utf8::<element_defs::FileNameDef>("a").unwrap();
+    output
+        .write_system_time::<element_defs::ModificationTimestampDef>(time)
+        .unwrap();
+    output.end::<element_defs::FileDef>().unwrap();
+    output.end::<element_defs::FilesDef>().unwrap();
+    let stream = output.finish().unwrap();
+
+    let reader = parser::_DocumentReader::new(&stream[..]);
+    let reader = match reader.next().unwrap() {
+        parser::_DocumentNextReaders::Files(r) => r,
+        r => panic!("unexpected reader {:?}", r),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FilesNextReaders::File(r) => r,
+        r => panic!("unexpected reader {:?}", r),
+    };
+    let reader = match reader.next().unwrap() {
+        parser::FileNextReaders::FileName(r) => r,
+        r => panic!("unexpected reader {:?}", r),
+    };
+    let mut reader = match reader.next().unwrap().next().unwrap() {
+        parser::FileNextReaders::ModificationTimestamp(r) => r,
+        r => panic!("unexpected reader {:?}", r),
+    };
+
+    // nanoseconds since 2001-01-01T00:00:00 UTC
+    assert_eq!(reader.read().unwrap(), 631_152_000_500_000_000);
+    assert_eq!(reader.read_system_time().unwrap(), time);
+}
+
+// yields its data one octet at a time, & only on every other poll
+struct PendingReader<'a> {
+    data: &'a [u8],
+    ready: bool,
+}
+
+impl AsyncRead for PendingReader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let data = match self.as_mut().poll_fill_buf(cx)? {
+            Poll::Ready(data) => data,
+            Poll::Pending => return Poll::Pending,
+        };
+        let len = data.len().min(buf.remaining());
+        buf.put_slice(&data[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncBufRead for PendingReader<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        this.ready = !this.ready;
+        if !this.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        Poll::Ready(Ok(&this.data[..this.data.len().min(1)]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.data = &this.data[amt..];
+    }
+}
+
+// polls the future until it's ready, w/o an async runtime
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+// collects all file names, awaiting the data of each element
+async fn read_file_names_async<R: AsyncBufRead + Unpin>(
+    reader: R,
+) -> Result<Vec<String>, ReaderError> {
+    let mut reader = AsyncElementReader::new(
+        reader,
+        parser::States::from(parser::_DocumentState::default()),
+    );
+    let mut result = Vec::new();
+
+    loop {
+        let (state, r) = reader.into_parts();
+        reader = match state {
+            parser::States::_Document(s) => match r.with_state(s).next().await {
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(result),
+                r_next => r_next?.map_state(Into::into),
+            },
+            parser::States::Void(s) => r.with_state(s).next().await?.map_state(Into::into),
+            parser::States::CRC32(s) => r.with_state(s).next().await?.map_state(Into::into),
+            parser::States::Files(s) => r.with_state(s).next().await?.map_state(Into::into),
+            parser::States::File(s) => r.with_state(s).next().await?.map_state(Into::into),
+            parser::States::FileName(s) => {
+                let mut r = r.with_state(s);
+                result.push(r.read().await?.to_string());
+                r.next().await?.map_state(Into::into)
+            }
+            parser::States::MimeType(s) => r.with_state(s).next().await?.map_state(Into::into),
+            parser::States::ModificationTimestamp(s) => {
+                r.with_state(s).next().await?.map_state(Into::into)
+            }
+            parser::States::Data(s) => r.with_state(s).skip().await?.map_state(Into::into),
+            parser::States::Tag(s) => r.with_state(s).skip().await?.map_state(Into::into),
+            parser::States::TagName(s) => r.with_state(s).next().await?.map_state(Into::into),
+        };
+    }
+}
+
+#[test]
+fn read_async() {
+    let expt_result = vec!["file3.html", "file2.csv", "file1.txt"];
+
+    assert_eq!(
+        block_on(read_file_names_async(&BYTE_STREAM[..])).unwrap(),
+        expt_result
+    );
+    let reader = PendingReader {
+        data: &BYTE_STREAM[..],
+        ready: false,
+    };
+    assert_eq!(
+        block_on(read_file_names_async(reader)).unwrap(),
+        expt_result
+    );
+    // any of tokio's own readers can be read from directly
+    let reader = tokio::io::BufReader::with_capacity(3, &BYTE_STREAM[..]);
+    assert_eq!(
+        block_on(read_file_names_async(reader)).unwrap(),
+        expt_result
+    );
+
+    // only the data that's been parsed is taken from the reader
+    let reader = AsyncElementReader::new(&BYTE_STREAM[..], parser::_DocumentState::default());
+    let reader = block_on(reader.next()).unwrap();
+    assert_eq!(reader.position(), 5);
+    assert_eq!(reader.reader, &BYTE_STREAM[5..]);
+
+    // the stream ends within the first file name
+    assert!(matches!(
+        block_on(read_file_names_async(&BYTE_STREAM[..15])),
+        Err(ReaderError::UnexpectedEof)
+    ));
+}
@@ -1,22 +1,19 @@
 // interface loosely based on that of bindgen: https://crates.io/crates/bindgen
 
-use crate::serde_schema::{from_reader, EbmlSchema, Element, ElementType};
+use crate::serde_schema::{
+    DocumentationPurpose, EbmlSchema, Element, ElementType, ElementValue, Enum, ReadSchemaError,
+    WriteSchemaError,
+};
 use crate::trie::Trie;
 
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use core::convert::TryInto;
 use core::ops::{Bound, RangeBounds};
 use core::str::FromStr;
 
-/**
-The `Builder` object has the following responsibilities:
-
-- validate the schema objects read directly from the schema
-- perform all pre-processing in advance required to write the source routines for parsing
-
-**/
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GlobalPlaceholder {
     lower_bound: u64,
@@ -98,7 +95,7 @@ impl FromStr for PathAtoms {
             s.replace("\\)", ")") // global parent occurrence also uses '\' -> remove...
                 .strip_prefix('\\') // each path atom starts with \ -> remove the first...
                 .ok_or(Self::Err::MissingPathDivider)?
-                .split(|c| c == '\\') // ...then split on '\'
+                .split('\\') // ...then split on '\'
                 .map(|s| {
                     let divider = s.find(')').map_or(0, |i| i + 1);
                     let (s1, s2) = s.split_at(divider);
@@ -120,16 +117,456 @@ pub enum PathAtomsParserError {
     InvalidGlobalPlaceholder(<GlobalPlaceholder as FromStr>::Err),
 }
 
+pub trait RangeValue: Sized + PartialOrd + core::fmt::Debug {
+    fn parse_value(s: &str) -> Option<Self>;
+}
+
+impl RangeValue for u64 {
+    fn parse_value(s: &str) -> Option<Self> {
+        match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(digits) => u64::from_str_radix(digits, 16).ok(),
+            None => s.parse().ok(),
+        }
+    }
+}
+
+impl RangeValue for usize {
+    fn parse_value(s: &str) -> Option<Self> {
+        u64::parse_value(s).and_then(|value| value.try_into().ok())
+    }
+}
+
+impl RangeValue for i64 {
+    fn parse_value(s: &str) -> Option<Self> {
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(s) => ("-", s),
+            None => ("", s.strip_prefix('+').unwrap_or(s)),
+        };
+        if !s.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        // keep the sign w/ the digits, s.t. `i64::MIN` doesn't overflow
+        match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(digits) => i64::from_str_radix(&format!("{sign}{digits}"), 16).ok(),
+            None => format!("{sign}{s}").parse().ok(),
+        }
+    }
+}
+
+impl RangeValue for f64 {
+    fn parse_value(s: &str) -> Option<Self> {
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(s) => (-1.0, s),
+            None => (1.0, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let value = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            // hexadecimal floats, e.g. `0x1.8p+1`; not supported by `f64::from_str`
+            Some(s) => {
+                let (mantissa, exponent) = s.split_once(['p', 'P'])?;
+                let (int_digits, frac_digits) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+                let digits = format!("{}{}", int_digits, frac_digits);
+                if digits.is_empty() || digits.len() > 16 {
+                    return None;
+                }
+                let mantissa = u64::from_str_radix(&digits, 16).ok()? as f64;
+                let exponent = exponent.parse::<i32>().ok()? - 4 * frac_digits.len() as i32;
+                // split the scaling in two, s.t. subnormal results don't underflow to zero
+                mantissa * 2f64.powi(exponent / 2) * 2f64.powi(exponent - exponent / 2)
+            }
+            None if s.starts_with(|c: char| c.is_ascii_digit() || c == '.') => s.parse().ok()?,
+            None => return None,
+        };
+        if !value.is_finite() {
+            return None;
+        }
+        Some(sign * value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeDef<T> {
+    IsExactly(T),
+    Excludes(T),
+    IsWithin(Bound<T>, Bound<T>),
+}
+
+impl<T: RangeValue> RangeDef<T> {
+    fn parse_value(s: &str) -> Result<T, RangeDefParserError> {
+        T::parse_value(s.trim()).ok_or_else(|| RangeDefParserError::InvalidValue(s.to_string()))
+    }
+
+    fn parse_one_sided(s: &str) -> Result<Self, RangeDefParserError> {
+        let s = s.trim();
+        Ok(if let Some(s) = s.strip_prefix(">=") {
+            Self::IsWithin(Bound::Included(Self::parse_value(s)?), Bound::Unbounded)
+        } else if let Some(s) = s.strip_prefix('>') {
+            Self::IsWithin(Bound::Excluded(Self::parse_value(s)?), Bound::Unbounded)
+        } else if let Some(s) = s.strip_prefix("<=") {
+            Self::IsWithin(Bound::Unbounded, Bound::Included(Self::parse_value(s)?))
+        } else if let Some(s) = s.strip_prefix('<') {
+            Self::IsWithin(Bound::Unbounded, Bound::Excluded(Self::parse_value(s)?))
+        } else {
+            return Err(RangeDefParserError::InvalidBound(s.to_string()));
+        })
+    }
+
+    // a lower bound above the upper bound leaves no valid values
+    fn within(lower: Bound<T>, upper: Bound<T>, s: &str) -> Result<Self, RangeDefParserError> {
+        match (&lower, &upper) {
+            (
+                Bound::Included(lower) | Bound::Excluded(lower),
+                Bound::Included(upper) | Bound::Excluded(upper),
+            ) if lower > upper => Err(RangeDefParserError::InvertedBounds(s.to_string())),
+            _ => Ok(Self::IsWithin(lower, upper)),
+        }
+    }
+
+    pub fn to_rust(&self) -> String {
+        fn bound_to_rust<T: core::fmt::Debug>(bound: &Bound<T>) -> String {
+            match bound {
+                Bound::Included(value) => format!("Bound::Included({:?})", value),
+                Bound::Excluded(value) => format!("Bound::Excluded({:?})", value),
+                Bound::Unbounded => "Bound::Unbounded".to_string(),
+            }
+        }
+
+        match self {
+            Self::IsExactly(value) => format!("Range::IsExactly({:?})", value),
+            Self::Excludes(value) => format!("Range::Excludes({:?})", value),
+            Self::IsWithin(lower, upper) => format!(
+                "Range::IsWithin({}, {})",
+                bound_to_rust(lower),
+                bound_to_rust(upper)
+            ),
+        }
+    }
+}
+
+impl<T> Default for RangeDef<T> {
+    fn default() -> Self {
+        Self::IsWithin(Bound::Unbounded, Bound::Unbounded)
+    }
+}
+
+impl<T: RangeValue> FromStr for RangeDef<T> {
+    type Err = RangeDefParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // schemas copied from the spec's prose may keep their comparisons XML-escaped
+        // v `&amp;` goes last, s.t. e.g. `&amp;gt;` becomes `&gt;` rather than `>`
+        let unescaped = s
+            .replace("&gt;", ">")
+            .replace("&lt;", "<")
+            .replace("&amp;", "&");
+        let s = unescaped.trim();
+
+        if let Some(s) = s.strip_prefix("not ") {
+            return Ok(Self::Excludes(Self::parse_value(s)?));
+        }
+        if let Some((s1, s2)) = s.split_once(',') {
+            return match (Self::parse_one_sided(s1)?, Self::parse_one_sided(s2)?) {
+                (
+                    Self::IsWithin(lower, Bound::Unbounded),
+                    Self::IsWithin(Bound::Unbounded, upper),
+                )
+                | (
+                    Self::IsWithin(Bound::Unbounded, upper),
+                    Self::IsWithin(lower, Bound::Unbounded),
+                ) if lower != Bound::Unbounded && upper != Bound::Unbounded => {
+                    Self::within(lower, upper, s)
+                }
+                _ => Err(Self::Err::InvalidBound(s.to_string())),
+            };
+        }
+        if s.starts_with(['<', '>']) {
+            return Self::parse_one_sided(s);
+        }
+        if let Some(value) = T::parse_value(s) {
+            return Ok(Self::IsExactly(value));
+        }
+        // inclusive ranges, e.g. `1-10`; the leading value may itself be negative
+        s.match_indices('-')
+            .filter(|(i, _)| *i > 0)
+            .find_map(|(i, _)| Some((T::parse_value(&s[..i])?, T::parse_value(&s[i + 1..])?)))
+            .ok_or_else(|| Self::Err::InvalidValue(s.to_string()))
+            .and_then(|(lower, upper)| {
+                Self::within(Bound::Included(lower), Bound::Included(upper), s)
+            })
+    }
+}
+
+fn range_def<T: RangeValue>(element: &Element) -> Result<RangeDef<T>, RangeDefParserError> {
+    element
+        .range
+        .as_deref()
+        .map_or_else(|| Ok(RangeDef::default()), str::parse)
+}
+
+// lengths share the range grammar, but are always octet counts
+fn length_def(element: &Element) -> Result<RangeDef<usize>, RangeDefParserError> {
+    element
+        .length
+        .as_deref()
+        .map_or_else(|| Ok(RangeDef::default()), str::parse)
+}
+
+// renders an element's schema default as the value of its `DEFAULT` constant, or `None` if the
+// default isn't valid for the element's type
+fn default_to_rust(element: &Element) -> Option<String> {
+    let default = match element.default.as_deref() {
+        Some(default) => default,
+        None => return Some("None".to_string()),
+    };
+    let value = match element.r#type {
+        ElementType::Master => return None,
+        ElementType::UnsignedInteger => format!("{:?}", u64::parse_value(default.trim())?),
+        ElementType::SignedInteger | ElementType::Date => {
+            format!("{:?}", i64::parse_value(default.trim())?)
+        }
+        ElementType::Float => format!("{:?}", f64::parse_value(default.trim())?),
+        ElementType::String if !default.is_ascii() => return None,
+        ElementType::String | ElementType::Utf8 => format!("{:?}", default),
+        // binary defaults are written as hex digits, e.g. `0x0A0B`
+        ElementType::Binary => {
+            let digits = default.trim();
+            let digits = digits
+                .strip_prefix("0x")
+                .or_else(|| digits.strip_prefix("0X"))
+                .unwrap_or(digits);
+            if digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            let bytes = (0..digits.len())
+                .step_by(2)
+                .map(|i| format!("0x{}", &digits[i..i + 2]))
+                .collect::<Vec<_>>();
+            format!("&[{}]", bytes.join(", "))
+        }
+    };
+    Some(format!("Some({})", value))
+}
+
+// the labeled values that an element is restricted to
+fn enums_of(element: &Element) -> Vec<&Enum> {
+    element
+        .metadata
+        .iter()
+        .flatten()
+        .filter_map(|value| match value {
+            ElementValue::Restriction(restriction) => Some(&restriction.enums),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+// the integer type of elements whose restricted values get their own Rust enum
+fn enum_int_type(element: &Element) -> Option<&'static str> {
+    match element.r#type {
+        ElementType::UnsignedInteger => Some("u64"),
+        ElementType::SignedInteger => Some("i64"),
+        _ => None,
+    }
+}
+
+// converts enum labels (e.g. `side by side (left eye first)`) into unique CamelCase variant names
+fn enum_variant_names(enums: &[&Enum]) -> Vec<String> {
+    let mut names = Vec::<String>::new();
+    for enum_ in enums {
+        let mut name = enum_
+            .label
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_uppercase().to_string() + chars.as_str()
+                })
+            })
+            .collect::<String>();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            name.insert(0, 'V');
+        }
+        // (the suffixed name may itself be taken, e.g. by labels `a`, `a` & `a1`)
+        while names.contains(&name) {
+            name = format!("{}{}", name, enum_.value);
+        }
+        names.push(name);
+    }
+    names
+}
+
+// keywords that can't be used as plain identifiers, but can be used as raw identifiers
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "else", "enum", "extern", "false", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "type", "unsafe", "use", "where", "while", "async", "await", "dyn",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof", "unsized",
+    "virtual", "yield", "try",
+];
+
+// Names that are valid identifiers, but whose generated items would clash w/ the generated code's
+// own: the `Parent` variant of each `{name}NextStates`/`{name}NextReaders` enum, & the base items
+// that the generated modules import (e.g. `ElementDef` & `ElementState`, for an element named
+// `Element`).
+const RESERVED_NAMES: &[&str] = &[
+    "Parent",
+    "Element",
+    "BinaryElement",
+    "DateElement",
+    "EnumElement",
+    "FloatElement",
+    "IntElement",
+    "MasterElement",
+    "StringElement",
+    "UIntElement",
+    "Utf8Element",
+];
+
+// converts element names (e.g. `FileName`, `CRC32`) into snake-case method names (e.g. `file_name`,
+// `crc32`)
+fn method_name(element_name: &str) -> String {
+    let name = snake_case(element_name);
+    match name.as_str() {
+        // these would clash w/ the builders' own methods, & can't be raw identifiers
+        "new" | "finish" | "self" | "super" | "crate" => name + "_",
+        keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{}", name),
+        _ => name,
+    }
+}
+
+// Element names are used as-is in the generated identifiers (e.g. `{name}Def` & the `Readers::{name}`
+// variant), so they must be valid identifiers themselves, other than the `RESERVED_NAMES`. A
+// leading underscore is disallowed too, since the generated `_Document` & `_Never` items use it.
+fn is_valid_name(element_name: &str) -> bool {
+    element_name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && element_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RUST_KEYWORDS.contains(&element_name)
+        && !matches!(element_name, "self" | "super" | "crate" | "Self")
+        && !RESERVED_NAMES.contains(&element_name)
+}
+
+fn snake_case(element_name: &str) -> String {
+    let chars: Vec<char> = element_name.chars().collect();
+    let mut name = String::with_capacity(chars.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                name.push('_');
+            }
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum RangeDefParserError {
+    #[error("invalid range value {0}")]
+    InvalidValue(String),
+    #[error("invalid range bound {0}")]
+    InvalidBound(String),
+    #[error("range {0} has its lower bound above its upper bound")]
+    InvertedBounds(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    #[default]
+    Pub,
+    Crate,
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pub => write!(f, "pub"),
+            Self::Crate => write!(f, "pub(crate)"),
+        }
+    }
+}
+
+/**
+The `Builder` object has the following responsibilities:
+
+- validate the schema objects read directly from the schema
+- perform all pre-processing in advance required to write the source routines for parsing
+
+**/
 #[derive(Debug)]
 pub struct Builder {
     schema: EbmlSchema,
+    visibility: Visibility,
+    doc_lang: String,
+    base_path: String,
+    core_module: String,
+    require_root: bool,
 }
 
 impl Builder {
-    pub fn new<R: std::io::Read>(schema: R) -> Result<Self, serde_xml_rs::Error> {
-        Ok(Self {
-            schema: from_reader(schema)?,
-        })
+    pub fn new<R: std::io::Read>(schema: R) -> Result<Self, ReadSchemaError> {
+        Ok(Self::from_schema(EbmlSchema::from_reader(schema)?))
+    }
+
+    // reads the schema from a file, e.g. from a build script
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BuilderError> {
+        let schema = std::fs::File::open(path)?;
+        Ok(Self::new(std::io::BufReader::new(schema))?)
+    }
+
+    pub fn from_schema(schema: EbmlSchema) -> Self {
+        Self {
+            schema,
+            visibility: Visibility::default(),
+            doc_lang: "en".to_string(),
+            base_path: "crate::base".to_string(),
+            core_module: "crate::core".to_string(),
+            require_root: false,
+        }
+    }
+
+    // sets the visibility of all generated items (defaults to `pub`)
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    // sets the language of the schema documentation copied into doc comments (defaults to `en`)
+    pub fn doc_lang(mut self, lang: &str) -> Self {
+        self.doc_lang = lang.to_string();
+        self
+    }
+
+    // sets the module path that the generated code imports the base template from (defaults to
+    // `crate::base`); the base's exported macros are imported from the root of the same crate
+    pub fn with_base_path(mut self, path: &str) -> Self {
+        self.base_path = path.to_string();
+        self
+    }
+
+    // sets the module path that the generated modules are placed in (defaults to `crate::core`)
+    pub fn with_core_module(mut self, path: &str) -> Self {
+        self.core_module = path.to_string();
+        self
+    }
+
+    // fails generation if no element can be placed at the document's root (defaults to `false`,
+    // i.e. an empty schema generates an empty-but-valid parser)
+    pub fn require_root(mut self, require_root: bool) -> Self {
+        self.require_root = require_root;
+        self
+    }
+
+    // writes the builder's schema back out as EBML schema XML
+    pub fn write_schema<W: std::io::Write>(&self, writer: W) -> Result<(), WriteSchemaError> {
+        self.schema.write_xml(writer)
     }
 
     pub fn generate(self) -> Result<Parsers, BuilderGenerateError> {
@@ -138,13 +575,56 @@ impl Builder {
         // Return `Parsers` object
 
         //
-        let elems: BTreeMap<u32, Element> = self
-            .schema
-            .elements
-            .unwrap_or_else(Vec::new)
-            .into_iter()
-            .map(|elem| (elem.id, elem))
-            .collect();
+        let mut elems: BTreeMap<u32, Element> = BTreeMap::new();
+        for elem in self.schema.elements.unwrap_or_default() {
+            if let Some(prev_elem) = elems.get(&elem.id) {
+                return Err(BuilderGenerateError::DuplicateId(
+                    elem.id,
+                    prev_elem.name.clone(),
+                    elem.name,
+                ));
+            }
+            elems.insert(elem.id, elem);
+        }
+
+        // element names are used as-is in the generated identifiers -> they must be unique as well
+        let mut names: BTreeMap<&str, u32> = BTreeMap::new();
+        for elem in elems.values() {
+            if let Some(prev_id) = names.insert(&elem.name, elem.id) {
+                return Err(BuilderGenerateError::DuplicateName(
+                    elem.name.clone(),
+                    prev_id,
+                    elem.id,
+                ));
+            }
+            if !is_valid_name(&elem.name) {
+                return Err(BuilderGenerateError::InvalidName(elem.name.clone()));
+            }
+            match elem.r#type {
+                ElementType::UnsignedInteger => range_def::<u64>(elem).map(drop),
+                ElementType::SignedInteger | ElementType::Date => range_def::<i64>(elem).map(drop),
+                ElementType::Float => range_def::<f64>(elem).map(drop),
+                _ => Ok(()),
+            }
+            .map_err(|e| BuilderGenerateError::InvalidRange(elem.name.clone(), e))?;
+            length_def(elem)
+                .map_err(|e| BuilderGenerateError::InvalidLength(elem.name.clone(), e))?;
+            if let (Some(min), Some(max)) = (elem.min_occurs, elem.max_occurs) {
+                if max < min {
+                    return Err(BuilderGenerateError::InvalidOccurrenceBounds {
+                        name: elem.name.clone(),
+                        min,
+                        max,
+                    });
+                }
+            }
+            if default_to_rust(elem).is_none() {
+                return Err(BuilderGenerateError::InvalidDefault(
+                    elem.name.clone(),
+                    elem.default.clone().unwrap_or_default(),
+                ));
+            }
+        }
 
         let pathed_elems: Trie<(GlobalPlaceholder, String), &Element> = elems
             .values()
@@ -175,19 +655,36 @@ impl Builder {
                     ));
                 }
 
+                // every ancestor in the path must be a declared master element, not only the
+                // direct parent
+                for depth in 1..=parent_path_atoms.len() {
+                    match pathed_elems.get(parent_path_atoms[..depth].iter().copied()) {
+                        None => {
+                            return Err(BuilderGenerateError::MissingAncestor {
+                                element: elem.name.clone(),
+                                missing_ancestor: parent_path_atoms[depth - 1].1.clone(),
+                            })
+                        }
+                        Some(ancestor) if ancestor.r#type != ElementType::Master => {
+                            return Err(BuilderGenerateError::NonMasterParent {
+                                element: elem.name.clone(),
+                                parent: ancestor.name.clone(),
+                            })
+                        }
+                        Some(_) => {}
+                    }
+                }
+
                 let parent_trie = pathed_elems
                     .subtrie(parent_path_atoms.iter().copied())
                     .expect("path of parent must necessarily exist for a given child");
-                if !parent_path_atoms.is_empty() && parent_trie.get([]).is_none() {
-                    return Err(BuilderGenerateError::NoDirectParent(elem.name.clone()));
-                }
                 let mut parent_ids: BTreeSet<Option<u32>> = parent_trie
                     .iter_depths()
                     .skip_while(|(depth, _elem)| depth < &(global_span.lower_bound as usize))
                     .take_while(|(depth, _elem)| {
                         global_span
                             .upper_bound
-                            .map_or(true, |ubnd| depth <= &(ubnd as usize))
+                            .is_none_or(|ubnd| depth <= &(ubnd as usize))
                     })
                     .filter(|(_depth, elem)| elem.r#type == ElementType::Master)
                     // v the root trie will have *no* leaf -> treat this as id = None
@@ -196,12 +693,17 @@ impl Builder {
                 if parent_path_atoms.is_empty() && global_span.contains(&0) {
                     parent_ids.insert(None);
                 }
+                // a recursive element may also be nested directly within itself
+                if elem.recursive.unwrap_or(false) && elem.r#type == ElementType::Master {
+                    parent_ids.insert(Some(elem.id));
+                }
 
                 Ok((elem.id, parent_ids))
             })
             .collect::<Result<_, _>>()?;
 
         let mut elem_children = BTreeMap::new();
+        elem_children.insert(None, BTreeSet::new()); // the root always exists, even w/o children
         for (elem_id, parent_ids) in elem_parents.iter() {
             for parent_id in parent_ids.iter() {
                 elem_children
@@ -216,26 +718,123 @@ impl Builder {
                 .or_insert_with(BTreeSet::new);
         }
 
+        if self.require_root && elem_children.get(&None).is_none_or(BTreeSet::is_empty) {
+            return Err(BuilderGenerateError::NoElements);
+        }
+
+        if let Some(cycle) = find_cycle(&elems, &elem_children) {
+            return Err(BuilderGenerateError::UnexpectedCycle(
+                cycle
+                    .iter()
+                    .map(|id| elems.get(id).unwrap().name.clone())
+                    .collect(),
+            ));
+        }
+
         Ok(Parsers {
+            ebml_version: self.schema.ebml.unwrap_or(1),
             elements: elems,
             parents: elem_parents,
             children: elem_children,
+            visibility: self.visibility,
+            doc_lang: self.doc_lang,
+            base_path: self.base_path,
+            core_module: self.core_module,
+            format: true,
         })
     }
 }
 
+// Finds a cycle in the element hierarchy, ignoring elements that are marked as recursive being
+// their own children. Any such cycle would make the generated (nested) state types infinitely sized.
+fn find_cycle(
+    elements: &BTreeMap<u32, Element>,
+    children: &BTreeMap<Option<u32>, BTreeSet<u32>>,
+) -> Option<Vec<u32>> {
+    fn visit(
+        id: u32,
+        elements: &BTreeMap<u32, Element>,
+        children: &BTreeMap<Option<u32>, BTreeSet<u32>>,
+        path: &mut Vec<u32>,
+        visited: &mut BTreeSet<u32>,
+    ) -> Option<Vec<u32>> {
+        if let Some(i) = path.iter().position(|&path_id| path_id == id) {
+            let mut cycle = path[i..].to_vec();
+            cycle.push(id);
+            return Some(cycle);
+        }
+        if !visited.insert(id) {
+            return None;
+        }
+
+        path.push(id);
+        let is_recursive = elements.get(&id).unwrap().recursive.unwrap_or(false);
+        for &child_id in children.get(&Some(id)).into_iter().flatten() {
+            if child_id == id && is_recursive {
+                continue;
+            }
+            if let Some(cycle) = visit(child_id, elements, children, path, visited) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+
+        None
+    }
+
+    let mut visited = BTreeSet::new();
+    elements
+        .keys()
+        .find_map(|&id| visit(id, elements, children, &mut Vec::new(), &mut visited))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BuilderError {
+    #[error("couldn't read schema file: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("couldn't parse schema: {0}")]
+    SchemaError(#[from] ReadSchemaError),
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum BuilderGenerateError {
     #[error("invalid path: {0}")]
     InvalidPath(<PathAtoms as FromStr>::Err),
+    #[error("duplicate element ID {0:#X}: shared by elements {1} & {2}")]
+    DuplicateId(u32, String, String),
+    #[error("duplicate element name {0}: shared by elements {1:#X} & {2:#X}")]
+    DuplicateName(String, u32, u32),
     #[error("empty path for element name {0}")]
     EmptyPath(String),
+    #[error("element name {0:?} isn't a valid Rust identifier (or is reserved)")]
+    InvalidName(String),
+    #[error("schema has no elements at the document's root")]
+    NoElements,
     #[error("inconsistent element name: element labeled {0}, but path terminated with {1}")]
     MismatchedPathName(String, String),
-    #[error("no direct parent element in path {0}")]
-    NoDirectParent(String),
+    #[error("element {element} is nested under {missing_ancestor}, which isn't declared")]
+    MissingAncestor {
+        element: String,
+        missing_ancestor: String,
+    },
+    #[error("element {element} is nested under {parent}, which isn't a master element")]
+    NonMasterParent { element: String, parent: String },
     #[error("expected a null prefix in path {0}")]
     NonNullPathPrefix(String),
+    #[error("invalid range for element {0}: {1}")]
+    InvalidRange(String, RangeDefParserError),
+    #[error("invalid length for element {0}: {1}")]
+    InvalidLength(String, RangeDefParserError),
+    #[error("invalid default value for element {0}: {1}")]
+    InvalidDefault(String, String),
+    #[error("element {name} has maxOccurs = {max}, less than its minOccurs = {min}")]
+    InvalidOccurrenceBounds {
+        name: String,
+        min: usize,
+        max: usize,
+    },
+    #[error("unexpected cycle in element hierarchy: {}", .0.join(" -> "))]
+    UnexpectedCycle(Vec<String>),
 }
 
 /**
@@ -244,49 +843,113 @@ Everything else (reading the schema, validating the element definitions & hierar
 should be done elsewhere.
 
 **/
-
 pub struct Parsers {
     // u32's are the element ID's
     // ID = `None` -> root document
     elements: BTreeMap<u32, Element>, // the root doesn't have a schema config
     parents: BTreeMap<u32, BTreeSet<Option<u32>>>, // the root can BE a parent, but will not HAVE a parent
     children: BTreeMap<Option<u32>, BTreeSet<u32>>, // the root can HAVE children, but will not BE a child
+    visibility: Visibility,
+    doc_lang: String,
+    base_path: String,
+    core_module: String,
+    // the EBML version of the schema's documents
+    ebml_version: u32,
+    format: bool,
 }
 
 impl Parsers {
+    // whether `write_package` runs the generated code through `rustfmt` (the default); w/o
+    // `rustfmt` installed, the code is written unformatted either way
+    pub fn format(mut self, format: bool) -> Self {
+        self.format = format;
+        self
+    }
+
+    // yields elements by ascending ID, s.t. generated code is identical across runs
+    pub fn elements_sorted(&self) -> impl Iterator<Item = (&u32, &Element)> {
+        self.elements.iter()
+    }
+
+    // the root of the crate holding the base template, where its `#[macro_export]` macros live
+    fn macro_root(&self) -> &str {
+        self.base_path.split("::").next().unwrap_or("crate")
+    }
+
+    // the element's schema documentation, w/ its definition first, as `///` lines
+    fn doc_comments(&self, element: &Element) -> String {
+        let mut docs = element
+            .metadata
+            .iter()
+            .flatten()
+            .filter_map(|value| match value {
+                ElementValue::Documentation(doc) => Some(doc),
+                _ => None,
+            })
+            .filter(|doc| doc.lang.as_deref().is_none_or(|lang| lang == self.doc_lang))
+            .collect::<Vec<_>>();
+        docs.sort_by_key(|doc| doc.purpose != DocumentationPurpose::Definition);
+
+        let paragraphs = docs
+            .iter()
+            .map(|doc| doc.value.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| format!("/// {}\n", paragraph));
+        itertools::intersperse(paragraphs, "///\n".to_string()).collect()
+    }
+
+    // crate-private items needn't all be used by the crate that they're generated into
+    fn allow_dead_code(&self) -> &'static str {
+        match self.visibility {
+            Visibility::Pub => "",
+            Visibility::Crate => "#![allow(dead_code)]\n",
+        }
+    }
+
     pub fn write_element_defs<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        writer.write_all(
+        write!(
+            writer,
             r#"
-#[allow(unused_imports)]
-use crate::base::element_defs::{
-    BinaryElementDef, DateElementDef, ElementDef, FloatElementDef, IntElementDef, MasterElementDef,
-    Range, StringElementDef, UIntElementDef, Utf8ElementDef,
-};
+{allow_dead_code}#[allow(unused_imports)]
+use {base}::element_defs::{{
+    BinaryElementDef, DateElementDef, ElementDef, EnumElementDef, FloatElementDef, IntElementDef,
+    MasterElementDef, Range, StringElementDef, UIntElementDef, Utf8ElementDef,
+}};
 
+#[allow(unused_imports)]
 use core::ops::Bound;
-            "#
-            .as_bytes(),
+
+// the EBML version that the schema's documents use, as declared by the schema's `ebml` attribute
+{vis} const EBML_VERSION: u32 = {ebml_version};
+            "#,
+            allow_dead_code = self.allow_dead_code(),
+            base = self.base_path,
+            vis = self.visibility,
+            ebml_version = self.ebml_version,
         )?;
 
-        for element in self.elements.values() {
+        for (_id, element) in self.elements_sorted() {
             write!(
                 writer,
                 r#"
-#[derive(Debug, Clone, PartialEq)]
-pub struct {name}Def;
+{docs}#[derive(Debug, Clone, PartialEq)]
+{vis} struct {name}Def;
 
 impl ElementDef for {name}Def {{
+    const NAME: &'static str = "{name}";
     const ID: u32 = {id};
     const PATH: &'static str = r"{path}";
 
     const MIN_OCCURS: usize = {min_occurs};
     const MAX_OCCURS: Option<usize> = {max_occurs};
-    const LENGTH: Range<usize> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const LENGTH: Range<usize> = {length};
     const RECURRING: bool = {recurring};
     const MIN_VERSION: u64 = {minver};
     const MAX_VERSION: Option<u64> = {maxver};
 }}
                 "#,
+                vis = self.visibility,
+                docs = self.doc_comments(element),
                 name = element.name,
                 id = element.id,
                 path = element.path,
@@ -295,6 +958,7 @@ impl ElementDef for {name}Def {{
                     .max_occurs
                     .map(|value| format!("Some({value})"))
                     .unwrap_or_else(|| "None".to_string()),
+                length = length_def(element).expect(LENGTH_VALIDATED).to_rust(),
                 recurring = element.recurring.unwrap_or(false),
                 minver = element.minver.unwrap_or(1),
                 maxver = element
@@ -310,55 +974,76 @@ impl ElementDef for {name}Def {{
 impl MasterElementDef for {name}Def {{
     const UNKNOWN_SIZE_ALLOWED: bool = {unknown_size_allowed};
     const RECURSIVE: bool = {recursive};
+    const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[{mandatory_children}];
 }}
                     "#,
                     name = element.name,
                     unknown_size_allowed = element.unknownsizeallowed.unwrap_or(false),
                     recursive = element.recursive.unwrap_or(false),
+                    mandatory_children = itertools::intersperse(
+                        self.children
+                            .get(&Some(element.id))
+                            .into_iter()
+                            .flatten()
+                            .filter(|&&child_id| child_id != element.id)
+                            .map(|child_id| self.elements.get(child_id).unwrap())
+                            .filter(|child| child.default.is_none())
+                            .filter_map(|child| child
+                                .min_occurs
+                                .filter(|&n| n > 0)
+                                .map(|n| (child.id, n)))
+                            .map(|(id, min_occurs)| format!("({}, {})", id, min_occurs)),
+                        ", ".to_string()
+                    )
+                    .collect::<String>(),
                 ),
                 ElementType::SignedInteger => write!(
                     writer,
                     r#"
 impl IntElementDef for {name}Def {{
-    const RANGE: Range<i64> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const RANGE: Range<i64> = {range};
     const DEFAULT: Option<i64> = {default};
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
+                    range = range_def::<i64>(element).expect(RANGE_VALIDATED).to_rust(),
                 ),
                 ElementType::UnsignedInteger => write!(
                     writer,
                     r#"
 impl UIntElementDef for {name}Def {{
-    const RANGE: Range<u64> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const RANGE: Range<u64> = {range};
     const DEFAULT: Option<u64> = {default};
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
+                    range = range_def::<u64>(element).expect(RANGE_VALIDATED).to_rust(),
                 ),
                 ElementType::Float => write!(
                     writer,
                     r#"
 impl FloatElementDef for {name}Def {{
-    const RANGE: Range<f64> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const RANGE: Range<f64> = {range};
     const DEFAULT: Option<f64> = {default};
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
+                    range = range_def::<f64>(element).expect(RANGE_VALIDATED).to_rust(),
                 ),
                 ElementType::Date => write!(
                     writer,
                     r#"
 impl DateElementDef for {name}Def {{
-    const RANGE: Range<i64> = Range::IsWithin(Bound::Unbounded, Bound::Unbounded);
+    const RANGE: Range<i64> = {range};
     const DEFAULT: Option<i64> = {default};
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
+                    range = range_def::<i64>(element).expect(RANGE_VALIDATED).to_rust(),
                 ),
                 ElementType::String => write!(
                     writer,
@@ -368,7 +1053,7 @@ impl StringElementDef for {name}Def {{
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
                 ),
                 ElementType::Utf8 => write!(
                     writer,
@@ -378,7 +1063,7 @@ impl Utf8ElementDef for {name}Def {{
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
                 ),
                 ElementType::Binary => write!(
                     writer,
@@ -388,42 +1073,229 @@ impl BinaryElementDef for {name}Def {{
 }}
                     "#,
                     name = element.name,
-                    default = "None"
+                    default = default_to_rust(element).expect(DEFAULT_VALIDATED),
                 ),
             }?;
-        }
 
-        Ok(())
-    }
+            let enums = enums_of(element);
+            if element.r#type == ElementType::UnsignedInteger && !enums.is_empty() {
+                write!(
+                    writer,
+                    r#"
+impl EnumElementDef for {name}Def {{
+    const ENUMS: &'static [(&'static str, u64)] = &[{enums}];
+}}
+                    "#,
+                    name = element.name,
+                    enums = itertools::intersperse(
+                        enums
+                            .iter()
+                            .map(|enum_| format!("({:?}, {})", enum_.label, enum_.value)),
+                        ", ".to_string()
+                    )
+                    .collect::<String>(),
+                )?;
+            }
+            if let (Some(int_type), false) = (enum_int_type(element), enums.is_empty()) {
+                let variants = enum_variant_names(&enums);
+                write!(
+                    writer,
+                    r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+{vis} enum {name}Enum {{{variants}
+}}
 
-    pub fn write_parsers<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        let element_names = self
-            .elements
-            .values()
-            .map(|elem| elem.name.clone())
-            .chain(core::iter::once("_Document".to_string()))
-            .collect::<Vec<_>>();
-        let parent_names = self
-            .parents
-            .iter()
-            .map(|(id, parent_ids)| {
-                (
-                    self.elements.get(id).unwrap().name.clone(),
-                    parent_ids
+impl core::convert::TryFrom<{int}> for {name}Enum {{
+    type Error = {int};
+
+    fn try_from(value: {int}) -> Result<Self, Self::Error> {{
+        match value {{{from_arms}
+            value => Err(value),
+        }}
+    }}
+}}
+
+impl From<{name}Enum> for {int} {{
+    fn from(value: {name}Enum) -> Self {{
+        match value {{{into_arms}
+        }}
+    }}
+}}
+                    "#,
+                    vis = self.visibility,
+                    name = element.name,
+                    int = int_type,
+                    variants = variants
                         .iter()
-                        .map(|parent_id| {
-                            parent_id.map_or("_Document".to_string(), |pid| {
-                                self.elements.get(&pid).unwrap().name.clone()
-                            })
+                        .map(|variant| format!("\n    {},", variant))
+                        .collect::<String>(),
+                    from_arms = variants
+                        .iter()
+                        .zip(enums.iter())
+                        .map(|(variant, enum_)| {
+                            format!("\n            {} => Ok(Self::{}),", enum_.value, variant)
                         })
-                        .collect::<BTreeSet<_>>(),
-                )
-            })
-            .collect::<BTreeMap<_, _>>();
-
-        let child_names = self
-            .children
-            .iter()
+                        .collect::<String>(),
+                    into_arms = variants
+                        .iter()
+                        .zip(enums.iter())
+                        .map(|(variant, enum_)| {
+                            format!(
+                                "\n            {}Enum::{} => {},",
+                                element.name, variant, enum_.value
+                            )
+                        })
+                        .collect::<String>(),
+                )?;
+            }
+        }
+
+        write!(
+            writer,
+            r#"
+// (ID, name) pairs of all schema elements, by ascending ID
+{vis} const ELEMENT_NAMES: &[(u32, &str)] = &[{names}];
+
+{vis} fn name_of(id: u32) -> Option<&'static str> {{
+    ELEMENT_NAMES
+        .binary_search_by_key(&id, |&(id, _name)| id)
+        .ok()
+        .map(|index| ELEMENT_NAMES[index].1)
+}}
+
+{vis} fn id_of(name: &str) -> Option<u32> {{
+    ELEMENT_NAMES
+        .iter()
+        .find(|&&(_id, element_name)| element_name == name)
+        .map(|&(id, _name)| id)
+}}
+
+// The elements of the schema, e.g. to resolve the ID in an error to its element's name; these
+// convert from/to their ID's & names, via `TryFrom<u32>`/`u32::from` & `FromStr`/`Display`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+{vis} enum ElementId {{{variants}
+}}
+
+impl ElementId {{
+    {vis} fn from_id(id: u32) -> Option<Self> {{
+        match id {{{from_id_arms}
+            _ => None,
+        }}
+    }}
+
+    {vis} fn from_name(name: &str) -> Option<Self> {{
+        match name {{{from_name_arms}
+            _ => None,
+        }}
+    }}
+
+    {vis} fn id(self) -> u32 {{
+        match self {{{id_arms}
+        }}
+    }}
+
+    {vis} fn name(self) -> &'static str {{
+        match self {{{name_arms}
+        }}
+    }}
+}}
+
+impl core::convert::TryFrom<u32> for ElementId {{
+    type Error = u32;
+
+    fn try_from(id: u32) -> Result<Self, u32> {{
+        Self::from_id(id).ok_or(id)
+    }}
+}}
+
+impl From<ElementId> for u32 {{
+    fn from(element: ElementId) -> u32 {{
+        element.id()
+    }}
+}}
+
+impl core::str::FromStr for ElementId {{
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {{
+        Self::from_name(name).ok_or(())
+    }}
+}}
+
+impl core::fmt::Display for ElementId {{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
+        f.write_str(self.name())
+    }}
+}}
+            "#,
+            vis = self.visibility,
+            variants = self
+                .elements_sorted()
+                .map(|(_id, element)| format!("\n    {},", element.name))
+                .collect::<String>(),
+            from_id_arms = self
+                .elements_sorted()
+                .map(|(id, element)| format!(
+                    "\n            {} => Some(Self::{}),",
+                    id, element.name
+                ))
+                .collect::<String>(),
+            from_name_arms = self
+                .elements_sorted()
+                .map(|(_id, element)| format!(
+                    "\n            {:?} => Some(Self::{}),",
+                    element.name, element.name
+                ))
+                .collect::<String>(),
+            id_arms = self
+                .elements_sorted()
+                .map(|(id, element)| format!("\n            Self::{} => {},", element.name, id))
+                .collect::<String>(),
+            name_arms = self
+                .elements_sorted()
+                .map(|(_id, element)| format!(
+                    "\n            Self::{} => {:?},",
+                    element.name, element.name
+                ))
+                .collect::<String>(),
+            names = itertools::intersperse(
+                self.elements_sorted()
+                    .map(|(id, element)| format!("({}, {:?})", id, element.name)),
+                ", ".to_string()
+            )
+            .collect::<String>(),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn write_parsers<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let element_names = self
+            .elements_sorted()
+            .map(|(_id, elem)| elem.name.clone())
+            .chain(core::iter::once("_Document".to_string()))
+            .collect::<Vec<_>>();
+        let parent_names = self
+            .parents
+            .iter()
+            .map(|(id, parent_ids)| {
+                (
+                    self.elements.get(id).unwrap().name.clone(),
+                    parent_ids
+                        .iter()
+                        .map(|parent_id| {
+                            parent_id.map_or("_Document".to_string(), |pid| {
+                                self.elements.get(&pid).unwrap().name.clone()
+                            })
+                        })
+                        .collect::<BTreeSet<_>>(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let child_names = self
+            .children
+            .iter()
             .map(|(id, child_ids)| {
                 (
                     id.map_or("_Document".to_string(), |pid| {
@@ -437,55 +1309,83 @@ impl BinaryElementDef for {name}Def {{
             })
             .collect::<BTreeMap<_, _>>();
 
-        writer.write_all(
+        write!(
+            writer,
             r#"
-use crate::base::element_defs::ElementDef;
+{allow_dead_code}#[allow(unused_imports)]
+use {base}::element_defs::ElementDef;
+#[cfg(feature = "std")]
 #[allow(unused_imports)]
-use crate::base::parser::{
-    BoundTo, ElementReader, ElementState, IntoReader, NextStateNavigation, ReaderError,
-    SkipStateNavigation, StateDataParser, StateError,
-};
+use {base}::header::read_ebml_header_with_len;
 #[allow(unused_imports)]
-use crate::base::stream::{parse, serialize, stream_diff};
-use crate::core::element_defs;
+use {base}::header::{{DocumentConfig, EbmlHeader}};
 #[allow(unused_imports)]
-use crate::{
-    impl_from_readers_for_states, impl_from_subreaders_for_readers, impl_from_substates_for_states,
-    impl_into_reader, impl_next_state_navigation, impl_skip_state_navigation,
-};
+use {base}::parser::{{
+    check_length, check_path, check_version, skip_unknown_element, BoundTo, CrcAccumulator,
+    DocumentConfigured, ElementData, ElementEvent, ElementLength, ElementState, HandlerRegistry,
+    NextStateNavigation, RemainingBytes, SkipStateNavigation, StateDataParser, StateError,
+    CRC32_ID, DEFAULT_BUFFER_CAPACITY,
+}};
+// the readers (& everything built on them) need `std::io`
+#[cfg(feature = "std")]
+#[allow(unused_imports)]
+use {base}::parser::{{
+    ElementCollector, ElementReader, FromElementData, IntoReader, NextReaderNavigation,
+    ReaderDataParser, ReaderError,
+}};
+#[allow(unused_imports)]
+use {base}::stream::{{parse, serialize, stream_diff}};
+#[allow(unused_imports)]
+use {core}::element_defs;
+#[allow(unused_imports)]
+use {macros}::{{
+    impl_crc_accumulator, impl_document_configured, impl_from_readers_for_states,
+    impl_from_subreaders_for_readers, impl_from_substates_for_states, impl_into_reader,
+    impl_next_state_navigation, impl_remaining_bytes, impl_skip_state_navigation,
+}};
 
 use enum_dispatch::enum_dispatch;
 
-use core::convert::{From, TryInto};
+#[allow(unused_imports)]
+use core::convert::From;
+#[allow(unused_imports)]
+use alloc::{{boxed::Box, vec::Vec}};
 use core::marker::PhantomData;
+#[cfg(feature = "std")]
 use std::io::BufRead;
 
 // Top-Level Reader/State Enums #########################################################################
-            "#.as_bytes()
+            "#,
+            allow_dead_code = self.allow_dead_code(),
+            base = self.base_path,
+            core = self.core_module,
+            macros = self.macro_root(),
         )?;
 
         for element_name in child_names
             .iter()
-            .filter_map(|(name, c_names)| (!c_names.is_empty()).then(|| name))
+            .filter(|(_name, c_names)| !c_names.is_empty())
+            .map(|(name, _c_names)| name)
         {
             write!(
                 writer,
                 r#"
 #[enum_dispatch({name}NextStates)]
-#[enum_dispatch({name}NextReaders<R>)]
+#[cfg_attr(feature = "std", enum_dispatch({name}NextReaders<R>))]
                 "#,
                 name = element_name,
             )?;
         }
         for element_name in parent_names
             .iter()
-            .filter_map(|(name, p_names)| (p_names.len() > 1).then(|| name))
+            .filter(|(_name, p_names)| p_names.len() > 1)
+            .map(|(name, _p_names)| name)
         {
             write!(
                 writer,
                 r#"
 #[enum_dispatch({name}PrevStates)]
-#[enum_dispatch({name}PrevReaders<R>)]
+#[cfg_attr(feature = "std", enum_dispatch({name}PrevReaders<R>))]
                 "#,
                 name = element_name,
             )?;
@@ -494,7 +1394,8 @@ use std::io::BufRead;
         writer.write_all(
             r#"
 #[enum_dispatch(States)]
-#[enum_dispatch(Readers<R>)]
+#[cfg_attr(feature = "std", enum_dispatch(Readers<R>))]
+#[allow(dead_code)]
 trait BlankTrait {}
             "#
             .as_bytes(),
@@ -504,10 +1405,11 @@ trait BlankTrait {}
             writer,
             r#"
 #[enum_dispatch]
-pub enum States {{
+{vis} enum States {{
     {elements}
 }}
             "#,
+            vis = self.visibility,
             elements = element_names
                 .iter()
                 .map(|elem_name| format!("{0}({0}State), ", elem_name))
@@ -516,11 +1418,13 @@ pub enum States {{
         write!(
             writer,
             r#"
+#[cfg(feature = "std")]
 #[enum_dispatch]
-pub enum Readers<R> {{
+{vis} enum Readers<R> {{
     {elements}
 }}
             "#,
+            vis = self.visibility,
             elements = element_names
                 .iter()
                 .map(|elem_name| format!("{0}({0}Reader<R>),", elem_name))
@@ -530,12 +1434,14 @@ pub enum Readers<R> {{
         write!(
             writer,
             r#"
+#[cfg(feature = "std")]
 impl_into_reader!(
     States,
     Readers,
     [{elements}]
 );
 
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(
     Readers,
     States,
@@ -546,98 +1452,610 @@ impl_from_readers_for_states!(
                 .collect::<String>()
         )?;
 
+        let element_arms = |value: &str| {
+            element_names
+                .iter()
+                .filter(|name| name.as_str() != "_Document")
+                .map(|name| {
+                    format!(
+                        "\n            Self::{name}(_) => Some(<element_defs::{name}Def as ElementDef>::{value}),"
+                    )
+                })
+                .collect::<String>()
+        };
+        for (enum_name, generics, attrs) in [
+            ("States", "", ""),
+            ("Readers", "<R>", "#[cfg(feature = \"std\")]\n"),
+        ] {
+            write!(
+                writer,
+                r#"
+{attrs}impl{generics} {enum_name}{generics} {{
+    // the ID of the element being read, or `None` at the top level of the document
+    pub fn element_id(&self) -> Option<u32> {{
+        match self {{
+            Self::_Document(_) => None,{id_arms}
+        }}
+    }}
+
+    pub fn element_name(&self) -> Option<&'static str> {{
+        match self {{
+            Self::_Document(_) => None,{name_arms}
+        }}
+    }}
+}}
+                "#,
+                attrs = attrs,
+                generics = generics,
+                enum_name = enum_name,
+                id_arms = element_arms("ID"),
+                name_arms = element_arms("NAME"),
+            )?;
+        }
+
+        write!(
+            writer,
+            r#"
+// Walks the entire document, passing the data of every element with a registered handler to
+// that handler
+#[cfg(feature = "std")]
+#[allow(unused_variables)] // `registry` goes unused for schemas w/o any data elements
+{vis} fn walk_with_handlers<R: BufRead>(
+    reader: R,
+    registry: &mut HandlerRegistry,
+) -> Result<(), ReaderError> {{
+    let mut reader: Readers<R> = _DocumentReader::new(reader).into();
+
+    loop {{
+        reader = match reader {{
+            Readers::_Document(r) => match r.next() {{
+                Ok(r_next) => r_next.into(),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e),
+            }},{arms}
+        }};
+    }}
+}}
+            "#,
+            vis = self.visibility,
+            arms = self
+                .elements_sorted()
+                .map(|(_id, element)| {
+                    let variant = match element.r#type {
+                        ElementType::Master => {
+                            return format!(
+                                "\n            Readers::{name}(r) => r.next()?.into(),",
+                                name = element.name,
+                            )
+                        }
+                        ElementType::UnsignedInteger => "UInt",
+                        ElementType::SignedInteger => "Int",
+                        ElementType::Float => "Float",
+                        ElementType::Date => "Date",
+                        ElementType::String => "String",
+                        ElementType::Utf8 => "Utf8",
+                        ElementType::Binary => "Binary",
+                    };
+                    format!(
+                        r#"
+            Readers::{name}(mut r) => {{
+                let id = <element_defs::{name}Def as ElementDef>::ID;
+                if registry.is_registered(id) {{
+                    let data = r.read()?;
+                    registry.handle(id, ElementData::{variant}(data));
+                }}
+                r.next()?.into()
+            }}"#,
+                        name = element.name,
+                        variant = variant,
+                    )
+                })
+                .collect::<String>(),
+        )?;
+
         write!(
             writer,
             r#"
-// _Document Objects #########################################################################
+// Callbacks for each element of the schema, as it's entered by `drive`; each defaults to doing
+// nothing, i.e. skipping the element's data
+#[cfg(feature = "std")]
+{vis} trait Visitor<R> {{{methods}
+}}
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct _DocumentState;
-pub type _DocumentReader<R> = ElementReader<R, _DocumentState>;
+// Walks the entire document, passing each element's reader to the visitor as it's entered
+#[cfg(feature = "std")]
+{vis} fn drive<R: BufRead, V: Visitor<R> + ?Sized>(
+    reader: R,
+    visitor: &mut V,
+) -> Result<(), ReaderError> {{
+    let mut reader: Readers<R> = _DocumentReader::new(reader).into();
+
+    loop {{
+        reader = match reader {{
+            Readers::_Document(r) => match r.next() {{
+                Ok(r_next) => visit_entered(r_next.into(), visitor)?,
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e),
+            }},{arms}
+        }};
+    }}
+}}
 
-impl<R: BufRead> _DocumentReader<R> {{
+// passes a newly-entered element's reader to the visitor
+#[cfg(feature = "std")]
+fn visit_entered<R: BufRead, V: Visitor<R> + ?Sized>(
+    reader: Readers<R>,
+    visitor: &mut V,
+) -> Result<Readers<R>, ReaderError> {{
+    match reader {{
+        Readers::_Document(_) => unreachable!("the document is never entered"),{visit_arms}
+    }}
+}}
+"#,
+            vis = self.visibility,
+            methods = self
+                .elements_sorted()
+                .map(|(_id, element)| format!(
+                    r#"
+
+    fn visit_{method}(&mut self, _reader: &mut {name}Reader<R>) -> Result<(), ReaderError> {{
+        Ok(())
+    }}"#,
+                    method = snake_case(&element.name),
+                    name = element.name,
+                ))
+                .collect::<String>(),
+            // (only the elements w/ children can be entered from)
+            arms = self
+                .elements_sorted()
+                .map(|(_id, element)| match child_names.get(&element.name) {
+                    Some(children) if !children.is_empty() => format!(
+                        r#"
+            Readers::{name}(r) => match r.next()? {{
+                {name}NextReaders::Parent(r_prev) => r_prev.into(),
+                r_next => visit_entered(r_next.into(), visitor)?,
+            }},"#,
+                        name = element.name,
+                    ),
+                    _ => format!(
+                        "\n            Readers::{name}(r) => r.next()?.into(),",
+                        name = element.name,
+                    ),
+                })
+                .collect::<String>(),
+            visit_arms = self
+                .elements_sorted()
+                .map(|(_id, element)| format!(
+                    r#"
+        Readers::{name}(mut r) => {{
+            visitor.visit_{method}(&mut r)?;
+            Ok(Readers::{name}(r))
+        }}"#,
+                    method = snake_case(&element.name),
+                    name = element.name,
+                ))
+                .collect::<String>(),
+        )?;
+
+        write!(
+            writer,
+            r#"
+// Reads the data of every element w/ the given ID in the document as `T` (e.g. a `String` for a
+// string element), skipping everything else
+#[cfg(feature = "std")]
+{vis} fn collect_by_id<T: FromElementData, R: BufRead>(
+    reader: R,
+    id: u32,
+) -> Result<Vec<T>, ReaderError> {{
+    let mut collector = ElementCollector::new();
+    let mut registry = HandlerRegistry::new();
+    registry.register_id(id, &mut collector);
+    walk_with_handlers(reader, &mut registry)?;
+    drop(registry);
+
+    collector.into_values()
+}}
+
+// Like `collect_by_id`, for the element w/ the given name in the schema
+#[cfg(feature = "std")]
+{vis} fn collect_by_name<T: FromElementData, R: BufRead>(
+    reader: R,
+    name: &str,
+) -> Result<Vec<T>, ReaderError> {{
+    let id = element_defs::id_of(name).ok_or_else(|| ReaderError::UnknownName(name.to_string()))?;
+    collect_by_id(reader, id)
+}}
+"#,
+            vis = self.visibility,
+        )?;
+
+        // data elements are read & left as soon as they're entered -> only masters are left pending
+        let data_names = self
+            .elements_sorted()
+            .filter(|(_id, element)| element.r#type != ElementType::Master)
+            .map(|(_id, element)| format!("Readers::{}(_)", element.name))
+            .collect::<Vec<_>>();
+        write!(
+            writer,
+            r#"
+// Iterates over the events of walking the entire document, ending at the end of the document or
+// after the first error
+#[cfg(feature = "std")]
+{vis} struct ElementEvents<R> {{
+    reader: Option<Readers<R>>,
+}}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> ElementEvents<R> {{
     pub fn new(reader: R) -> Self {{
         Self {{
-            reader,
-            state: _DocumentState,
+            reader: Some(_DocumentReader::new(reader).into()),
         }}
     }}
+
+    fn enter(&mut self, reader: Readers<R>) -> Result<ElementEvent, ReaderError> {{
+        match reader {{
+            Readers::_Document(_) => unreachable!("the document is never entered"),{enter_arms}
+        }}
+    }}
+
+    fn step(&mut self, reader: Readers<R>) -> Result<Option<ElementEvent>, ReaderError> {{
+        match reader {{
+            Readers::_Document(r) => match r.next() {{
+                Ok(r_next) => self.enter(r_next.into()).map(Some),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => Ok(None),
+                Err(e) => Err(e),
+            }},{step_arms}
+        }}
+    }}
+}}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for ElementEvents<R> {{
+    type Item = Result<ElementEvent, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {{
+        let reader = self.reader.take()?;
+        self.step(reader).transpose()
+    }}
+}}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> core::iter::FusedIterator for ElementEvents<R> {{}}
+
+// Deserializes the first top-level element of a document into a user type, e.g. a struct derived
+// w/ `serde::Deserialize` whose fields are its children's names in snake case.
+#[cfg(all(feature = "serde", feature = "std"))]
+{vis} fn from_ebml<T: serde::de::DeserializeOwned, R: BufRead>(
+    reader: R,
+) -> Result<T, {base}::de::DeError> {{
+    {base}::de::from_events(ElementEvents::new(reader), element_defs::name_of)
+}}
+
+// Reads a whole document into an owned tree of its elements
+#[cfg(feature = "tree")]
+{vis} struct Document;
+
+#[cfg(feature = "tree")]
+impl Document {{
+    pub fn parse<R: BufRead>(reader: R) -> Result<{base}::tree::ElementTree, ReaderError> {{
+        {base}::tree::ElementTree::from_events(ElementEvents::new(reader), element_defs::name_of)
+    }}
+}}
+"#,
+            vis = self.visibility,
+            base = self.base_path,
+            enter_arms = self
+                .elements_sorted()
+                .map(|(_id, element)| {
+                    let variant = match element.r#type {
+                        ElementType::Master => {
+                            return format!(
+                                r#"
+            Readers::{name}(r) => {{
+                self.reader = Some(r.into());
+                Ok(ElementEvent::Enter(<element_defs::{name}Def as ElementDef>::ID))
+            }}"#,
+                                name = element.name,
+                            )
+                        }
+                        ElementType::UnsignedInteger => "UInt",
+                        ElementType::SignedInteger => "Int",
+                        ElementType::Float => "Float",
+                        ElementType::Date => "Date",
+                        ElementType::String => "String",
+                        ElementType::Utf8 => "Utf8",
+                        ElementType::Binary => "Binary",
+                    };
+                    format!(
+                        r#"
+            Readers::{name}(mut r) => {{
+                let value = ElementData::{variant}(r.read()?).into();
+                self.reader = Some(r.next()?.into());
+                Ok(ElementEvent::Data(<element_defs::{name}Def as ElementDef>::ID, value))
+            }}"#,
+                        name = element.name,
+                        variant = variant,
+                    )
+                })
+                .collect::<String>(),
+            step_arms = self
+                .elements_sorted()
+                .filter(|(_id, element)| element.r#type == ElementType::Master)
+                .map(|(_id, element)| {
+                    let leave = format!(
+                        "ElementEvent::Leave(<element_defs::{}Def as ElementDef>::ID)",
+                        element.name
+                    );
+                    if child_names.get(&element.name).unwrap().is_empty() {
+                        format!(
+                            r#"
+            Readers::{name}(r) => {{
+                self.reader = Some(r.next()?.into());
+                Ok(Some({leave}))
+            }}"#,
+                            name = element.name,
+                            leave = leave,
+                        )
+                    } else {
+                        format!(
+                            r#"
+            Readers::{name}(r) => match r.next()? {{
+                {name}NextReaders::Parent(r_prev) => {{
+                    self.reader = Some(r_prev.into());
+                    Ok(Some({leave}))
+                }}
+                r_next => self.enter(r_next.into()).map(Some),
+            }},"#,
+                            name = element.name,
+                            leave = leave,
+                        )
+                    }
+                })
+                .chain((!data_names.is_empty()).then(|| {
+                    format!(
+                        "\n            {} => {{\n                unreachable!(\"data elements are left upon being entered\")\n            }}",
+                        data_names.join(" | ")
+                    )
+                }))
+                .collect::<String>(),
+        )?;
+
+        write!(
+            writer,
+            r#"
+// _Document Objects #########################################################################
+
+#[derive(Debug, Clone, Default, PartialEq)]
+{vis} struct _DocumentState {{
+    pub config: DocumentConfig,
+}}
+#[cfg(feature = "std")]
+{vis} type _DocumentReader<R> = ElementReader<R, _DocumentState>;
+
+#[cfg(feature = "std")]
+impl<R: BufRead> _DocumentReader<R> {{
+    pub fn new(reader: R) -> Self {{
+        Self::with_config(reader, DocumentConfig::default())
+    }}
+
+    pub fn with_config(reader: R, config: DocumentConfig) -> Self {{
+        _DocumentState {{ config }}.into_reader(reader, 0)
+    }}
+
+    // reads the document's EBML header, & limits the document's element ID's & lengths to the
+    // maximum widths that it declares
+    pub fn from_header(mut reader: R) -> Result<(EbmlHeader, Self), ReaderError> {{
+        let (header, position) = read_ebml_header_with_len(&mut reader)?;
+        let config = DocumentConfig::from(&header);
+        Ok((header, _DocumentState {{ config }}.into_reader(reader, position)))
+    }}
+
+    // the ID of the next top-level element, without moving into it -> `None` at the end of the
+    // stream
+    pub fn peek_id(&mut self) -> Result<Option<u32>, ReaderError> {{
+        self.peek_next_id()
+    }}
 }}
 
+// Any `Read` (e.g. a `File` or `TcpStream`) can be parsed by buffering it here. An element header or
+// payload that straddles the end of the read-ahead is gathered into a separate, growable buffer
+// before being parsed -> the capacity only limits how much is read at once, not the size of the
+// elements that can be parsed.
+#[cfg(feature = "std")]
+impl<I: std::io::Read> _DocumentReader<std::io::BufReader<I>> {{
+    // buffers an unbuffered reader, reading ahead `DEFAULT_BUFFER_CAPACITY` bytes at a time
+    pub fn from_read(inner: I) -> Self {{
+        Self::with_capacity(DEFAULT_BUFFER_CAPACITY, inner)
+    }}
+
+    // buffers an unbuffered reader, reading ahead `capacity` bytes at a time
+    pub fn with_capacity(capacity: usize, inner: I) -> Self {{
+        Self::new(std::io::BufReader::with_capacity(capacity, inner))
+    }}
+}}
+
+#[cfg(feature = "std")]
 impl<R: BufRead> IntoReader<R> for _DocumentState {{
     type Reader = _DocumentReader<R>;
-    fn into_reader(self, reader: R) -> _DocumentReader<R> {{
-        _DocumentReader::new(reader)
+    fn into_reader(self, reader: R, position: u64) -> _DocumentReader<R> {{
+        ElementReader {{
+            reader,
+            state: self,
+            pending: Vec::new(),
+            position,
+        }}
+    }}
+}}
+
+#[cfg(feature = "std")]
+impl<R> From<_DocumentReader<R>> for _DocumentState {{
+    fn from(reader: _DocumentReader<R>) -> Self {{
+        reader.state
+    }}
+}}
+
+impl RemainingBytes for _DocumentState {{
+    fn remaining_in_current_master(&self) -> Option<u64> {{
+        None
+    }}
+}}
+
+impl DocumentConfigured for _DocumentState {{
+    fn document_config(&self) -> DocumentConfig {{
+        self.config
+    }}
+
+    fn document_config_mut(&mut self) -> &mut DocumentConfig {{
+        &mut self.config
     }}
 }}
 
+// the document itself has no `CRC-32` elements to check
+impl CrcAccumulator for _DocumentState {{
+    fn update_crc(&mut self, _data: &[u8]) {{}}
+
+    fn start_crc(&mut self, _expected: u32) {{}}
+
+    fn crc_pending(&self) -> bool {{
+        false
+    }}
+}}
+            "#,
+            vis = self.visibility,
+        )?;
+
+        let root_names = child_names.get("_Document").unwrap();
+        if root_names.is_empty() {
+            // A document w/o any root elements has nothing to navigate to -> every element is invalid
+            // (or skipped, in lenient mode)
+            write!(
+                writer,
+                r#"
+impl NextStateNavigation for _DocumentState {{
+    type NextStates = _DocumentNextStates;
+
+    fn next(self, stream: &[u8]) -> nom::IResult<&[u8], Self::NextStates, StateError> {{
+        let (stream, id) = parse::element_id_bounded(stream, self.config.max_id_length)
+            .map_err(nom::Err::convert)?;
+        if !self.config.lenient {{
+            return Err(nom::Err::Failure(StateError::InvalidChildId(None, id)));
+        }}
+        let (stream, len) = parse::element_len_bounded(
+            stream,
+            self.config.max_size_length,
+            self.config.strict_lengths,
+        )
+        .map_err(nom::Err::convert)?;
+        let (stream, _) = skip_unknown_element(stream, id, len)?;
+        self.next(stream)
+    }}
+}}
+
+#[derive(Debug, Clone, PartialEq)]
+{vis} enum _DocumentNextStates {{
+    _Never(core::convert::Infallible),
+}}
+
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+{vis} enum _DocumentNextReaders<R> {{
+    _Never(core::convert::Infallible, PhantomData<R>),
+}}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> IntoReader<R> for _DocumentNextStates {{
+    type Reader = _DocumentNextReaders<R>;
+
+    fn into_reader(self, _reader: R, _position: u64) -> Self::Reader {{
+        match self {{
+            Self::_Never(never) => match never {{}},
+        }}
+    }}
+}}
+
+impl From<_DocumentNextStates> for States {{
+    fn from(states: _DocumentNextStates) -> Self {{
+        match states {{
+            _DocumentNextStates::_Never(never) => match never {{}},
+        }}
+    }}
+}}
+
+#[cfg(feature = "std")]
+impl<R> From<_DocumentNextReaders<R>> for Readers<R> {{
+    fn from(readers: _DocumentNextReaders<R>) -> Self {{
+        match readers {{
+            _DocumentNextReaders::_Never(never, _) => match never {{}},
+        }}
+    }}
+}}
+            "#,
+                vis = self.visibility,
+            )?;
+        } else {
+            write!(
+                writer,
+                r#"
 impl_next_state_navigation!(
     _DocumentState,
     _DocumentNextStates,
     [{child_pairs}]
 );
             "#,
-            child_pairs = itertools::intersperse(
-                child_names
-                    .get("_Document")
-                    .unwrap()
-                    .iter()
-                    .map(|cname| format!("({cname}, {cname}State)")),
-                ", ".to_string()
-            )
-            .collect::<String>(),
-        )?;
+                child_pairs = itertools::intersperse(
+                    root_names
+                        .iter()
+                        .map(|cname| format!("({cname}, {cname}State)")),
+                    ", ".to_string()
+                )
+                .collect::<String>(),
+            )?;
 
-        write!(
-            writer,
-            r#"
+            write!(
+                writer,
+                r#"
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
-pub enum _DocumentNextStates {{
+{vis} enum _DocumentNextStates {{
     {child_states}
 }}
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
-pub enum _DocumentNextReaders<R> {{
+{vis} enum _DocumentNextReaders<R> {{
     {child_readers}
 }}
 
 impl_from_substates_for_states!(_DocumentNextStates, States, [{children}]);
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!(_DocumentNextReaders, Readers, [{children}]);
 
+#[cfg(feature = "std")]
 impl_into_reader!(_DocumentNextStates, _DocumentNextReaders, [{children}]);
+#[cfg(feature = "std")]
 impl_from_readers_for_states!(_DocumentNextReaders, _DocumentNextStates, [{children}]);
             "#,
-            child_states = child_names
-                .get("_Document")
-                .unwrap()
-                .iter()
-                .map(|name| format!("{name}({name}State), "))
-                .collect::<String>(),
-            child_readers = child_names
-                .get("_Document")
-                .unwrap()
-                .iter()
-                .map(|name| format!("{name}({name}Reader<R>), "))
-                .collect::<String>(),
-            children = itertools::intersperse(
-                child_names
-                    .get("_Document")
-                    .unwrap()
+                vis = self.visibility,
+                child_states = root_names
                     .iter()
-                    .map(String::as_str),
-                ", "
-            )
-            .collect::<String>(),
-        )?;
+                    .map(|name| format!("{name}({name}State), "))
+                    .collect::<String>(),
+                child_readers = root_names
+                    .iter()
+                    .map(|name| format!("{name}({name}Reader<R>), "))
+                    .collect::<String>(),
+                children = itertools::intersperse(root_names.iter().map(String::as_str), ", ")
+                    .collect::<String>(),
+            )?;
+        }
 
         let make_state = |name: &str| format!("{}State", name);
         let make_reader = |name: &str| format!("{}Reader", name);
         let make_prev_states = |name: &str| format!("{}PrevStates", name);
         let make_prev_readers = |name: &str| format!("{}PrevReaders", name);
         let make_next_states = |name: &str| format!("{}NextStates", name);
-        let make_next_readers = |name: &str| format!("{}NextReaders", name);
 
         for element_name in element_names {
             if element_name == "_Document" {
@@ -673,29 +2091,47 @@ impl_from_readers_for_states!(_DocumentNextReaders, _DocumentNextStates, [{child
                 r#"
 // {name} Objects #########################################################################
 
-pub type {name}State = ElementState<element_defs::{name}Def, {parent_state}>;
-pub type {name}Reader<R> = ElementReader<R, {name}State>;
+{vis} type {name}State = ElementState<element_defs::{name}Def, {parent_state}>;
+#[cfg(feature = "std")]
+{vis} type {name}Reader<R> = ElementReader<R, {name}State>;
 
 impl {name}State {{
-    pub fn new(bytes_left: usize, parent_state: {parent_state}) -> Self {{
+    pub fn new(bytes_left: ElementLength, parent_state: {parent_state}) -> Self {{
         Self {{
             bytes_left,
             parent_state,
+            occurrences: Default::default(),
+            crc: None,
             _phantom: PhantomData::<_>,
         }}
     }}
 }}
 
+#[cfg(feature = "std")]
 impl<R: BufRead> {name}Reader<R> {{
     pub fn new(reader: R, state: {name}State) -> Self {{
-        Self {{ reader, state }}
+        Self {{
+            reader,
+            state,
+            pending: Vec::new(),
+            position: 0,
+        }}
     }}
 }}
 
 impl_skip_state_navigation!({name}State, {parent_state});
 impl_next_state_navigation!({name}State, {child_state}, [{child_pairs}]);
+impl_remaining_bytes!({kind} {name}State);
                 "#,
+                vis = self.visibility,
                 name = element_name,
+                kind = if self.elements_sorted().any(|(_id, elem)| {
+                    elem.name == element_name && elem.r#type == ElementType::Master
+                }) {
+                    "master"
+                } else {
+                    "child"
+                },
                 parent_state = parent_state_name.as_str(),
                 child_state = child_state_name,
                 child_pairs = itertools::intersperse(
@@ -713,24 +2149,29 @@ impl_next_state_navigation!({name}State, {child_state}, [{child_pairs}]);
                     r#"
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
-pub enum {name}NextStates {{
+{vis} enum {name}NextStates {{
     {child_states}
     Parent({parent_state}),
 }}
 
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
-pub enum {name}NextReaders<R> {{
+{vis} enum {name}NextReaders<R> {{
     {child_readers}
     Parent({parent_reader}<R>),
 }}
 
 impl_from_substates_for_states!({name}NextStates, States, [{children}]);
+#[cfg(feature = "std")]
 impl_from_subreaders_for_readers!({name}NextReaders, Readers, [{children}]);
 
+#[cfg(feature = "std")]
 impl_into_reader!({name}NextStates, {name}NextReaders, [{children}]);
+#[cfg(feature = "std")]
 impl_from_readers_for_states!({name}NextReaders, {name}NextStates, [{children}]);
                     "#,
+                    vis = self.visibility,
                     name = element_name,
                     parent_state = parent_state_name,
                     parent_reader = parent_reader_name,
@@ -759,25 +2200,42 @@ impl_from_readers_for_states!({name}NextReaders, {name}NextStates, [{children}])
                     r#"
 #[derive(Debug, Clone, PartialEq)]
 #[enum_dispatch]
-pub enum {name}PrevStates {{
+{vis} enum {name}PrevStates {{
     {parent_states}
 }}
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq)]
 #[enum_dispatch]
-pub enum {name}PrevReaders<R> {{
+{vis} enum {name}PrevReaders<R> {{
     {parent_readers}
 }}
 
-impl_from_substates_for_states!({name}PrevStates, States, [_Document, Files, File]);
-impl_from_subreaders_for_readers!({name}PrevReaders, Readers, [_Document, Files, File]);
+impl_from_substates_for_states!({name}PrevStates, States, [{parents}]);
+#[cfg(feature = "std")]
+impl_from_subreaders_for_readers!({name}PrevReaders, Readers, [{parents}]);
 
-impl_into_reader!({name}PrevStates, {name}PrevReaders, [_Document, Files, File]);
-impl_from_readers_for_states!({name}PrevReaders, {name}PrevStates, [_Document, Files, File]);
+#[cfg(feature = "std")]
+impl_into_reader!({name}PrevStates, {name}PrevReaders, [{parents}]);
+#[cfg(feature = "std")]
+impl_from_readers_for_states!({name}PrevReaders, {name}PrevStates, [{parents}]);
+
+impl_remaining_bytes!({name}PrevStates, [{parents}]);
+impl_document_configured!({name}PrevStates, [{parents}]);
+impl_crc_accumulator!({name}PrevStates, [{parents}]);
                     "#,
+                    vis = self.visibility,
                     name = element_name,
+                    parents =
+                        itertools::intersperse(elem_parent_names.iter().map(String::as_str), ", ")
+                            .collect::<String>(),
+                    // a recursive element boxes its own state, to keep its state type finite
                     parent_states = elem_parent_names
                         .iter()
-                        .map(|name| format!("{name}({name}State),"))
+                        .map(|name| if name == &element_name {
+                            format!("{name}(Box<{name}State>),")
+                        } else {
+                            format!("{name}({name}State),")
+                        })
                         .collect::<String>(),
                     parent_readers = elem_parent_names
                         .iter()
@@ -785,35 +2243,332 @@ impl_from_readers_for_states!({name}PrevReaders, {name}PrevStates, [_Document, F
                         .collect::<String>(),
                 )?;
             }
+
+            if elem_parent_names.contains(&element_name) {
+                write!(
+                    writer,
+                    r#"
+impl From<{name}State> for {name}PrevStates {{
+    fn from(state: {name}State) -> Self {{
+        Self::{name}(Box::new(state))
+    }}
+}}
+
+impl From<Box<{name}State>> for States {{
+    fn from(state: Box<{name}State>) -> Self {{
+        (*state).into()
+    }}
+}}
+                    "#,
+                    name = element_name,
+                )?;
+            }
+        }
+
+        for (_id, element) in self.elements_sorted() {
+            let int_type = match enum_int_type(element) {
+                Some(int_type) if !enums_of(element).is_empty() => int_type,
+                _ => continue,
+            };
+            write!(
+                writer,
+                r#"
+#[cfg(feature = "std")]
+impl<R: BufRead> {name}Reader<R> {{
+    // reads the element's value as one of its labeled variants
+    pub fn read_enum(&mut self) -> Result<element_defs::{name}Enum, ReaderError> {{
+        let value: {int} = self.read()?;
+        <element_defs::{name}Enum as core::convert::TryFrom<{int}>>::try_from(value).map_err(|_| {{
+            ReaderError::Parse(
+                nom::Err::Failure(StateError::OutOfRange(
+                    <element_defs::{name}Def as ElementDef>::ID,
+                )),
+                self.position,
+            )
+        }})
+    }}
+}}
+                "#,
+                name = element.name,
+                int = int_type,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // the arms of a loop that copies each element read from a document into an `ElementWriter`;
+    // `data_arm` gives the arm of each data element, from the `ElementWriter` method that writes it
+    fn writer_arms(&self, data_arm: impl Fn(&Element, &str) -> String) -> String {
+        self.elements_sorted()
+            .map(|(id, element)| {
+                let method = match element.r#type {
+                    ElementType::Master => {
+                        let has_children = self
+                            .children
+                            .get(&Some(*id))
+                            .is_some_and(|child_ids| !child_ids.is_empty());
+                        return if has_children {
+                            format!(
+                                r#"
+            parser::Readers::{name}(r) => match r.next()? {{
+                parser::{name}NextReaders::Parent(r) => {{
+                    writer.end::<element_defs::{name}Def>()?;
+                    r.into()
+                }}
+                r => start_master(writer, r.into()),
+            }},"#,
+                                name = element.name,
+                            )
+                        } else {
+                            format!(
+                                r#"
+            parser::Readers::{name}(r) => {{
+                let r = r.next()?;
+                writer.end::<element_defs::{name}Def>()?;
+                r.into()
+            }}"#,
+                                name = element.name,
+                            )
+                        };
+                    }
+                    ElementType::UnsignedInteger => "write_uint",
+                    ElementType::SignedInteger => "write_int",
+                    ElementType::Float => "write_float",
+                    ElementType::Date => "write_date",
+                    ElementType::String => "write_string",
+                    ElementType::Utf8 => "write_utf8",
+                    ElementType::Binary => "write_binary",
+                };
+                data_arm(element, method)
+            })
+            .collect::<String>()
+    }
+
+    pub fn write_writers<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write!(
+            writer,
+            r#"
+{allow_dead_code}#[allow(unused_imports)]
+use {base}::parser::{{NextReaderNavigation, ReaderDataParser, ReaderError}};
+use {base}::writer::{{ElementWriter, WriterError}};
+#[allow(unused_imports)]
+use {core}::element_defs;
+use {core}::parser;
+
+use std::io::{{BufRead, Write}};
+            "#,
+            allow_dead_code = self.allow_dead_code(),
+            base = self.base_path,
+            core = self.core_module,
+        )?;
+
+        write!(
+            writer,
+            r#"
+// Copies the entire document into `writer`, re-computing the lengths of all master elements
+{vis} fn copy_document<R: BufRead, W: Write>(
+    reader: R,
+    writer: &mut ElementWriter<W>,
+) -> Result<(), WriterError> {{
+    let mut reader: parser::Readers<R> = parser::_DocumentReader::new(reader).into();
+
+    loop {{
+        reader = match reader {{
+            parser::Readers::_Document(r) => match r.next() {{
+                Ok(r_next) => start_master(writer, r_next.into()),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            }},{arms}
+        }};
+    }}
+}}
+            "#,
+            vis = self.visibility,
+            arms = self.writer_arms(|element, method| format!(
+                r#"
+            parser::Readers::{name}(mut r) => {{
+                writer.{method}::<element_defs::{name}Def>(r.read()?)?;
+                r.next()?.into()
+            }}"#,
+                name = element.name,
+                method = method,
+            )),
+        )?;
+
+        write!(
+            writer,
+            r#"
+// Intercepts the data elements of a document being copied by `transcode`, e.g. to rewrite them; by
+// default, each element's data is passed through as-is.
+{vis} trait Transcoder<R: BufRead, W: Write> {{{methods}
+}}
+
+// Copies the entire document into `writer` like `copy_document`, except that each data element is
+// written by `transcoder`
+{vis} fn transcode<R: BufRead, W: Write, T: Transcoder<R, W> + ?Sized>(
+    reader: R,
+    writer: &mut ElementWriter<W>,
+    transcoder: &mut T,
+) -> Result<(), WriterError> {{
+    let mut reader: parser::Readers<R> = parser::_DocumentReader::new(reader).into();
+
+    loop {{
+        reader = match reader {{
+            parser::Readers::_Document(r) => match r.next() {{
+                Ok(r_next) => start_master(writer, r_next.into()),
+                Err(ReaderError::Parse(nom::Err::Incomplete(_), _)) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            }},{arms}
+        }};
+    }}
+}}
+            "#,
+            vis = self.visibility,
+            methods = self
+                .elements_sorted()
+                .filter(|(_id, element)| element.r#type != ElementType::Master)
+                .map(|(_id, element)| format!(
+                    r#"
+    fn transcode_{method}(
+        &mut self,
+        reader: &mut parser::{name}Reader<R>,
+        writer: &mut ElementWriter<W>,
+    ) -> Result<(), WriterError> {{
+        writer.write_raw::<element_defs::{name}Def>(reader.read_raw()?)
+    }}
+"#,
+                    method = snake_case(&element.name),
+                    name = element.name,
+                ))
+                .collect::<String>(),
+            arms = self.writer_arms(|element, _method| format!(
+                r#"
+            parser::Readers::{name}(mut r) => {{
+                transcoder.transcode_{method}(&mut r, writer)?;
+                r.next()?.into()
+            }}"#,
+                method = snake_case(&element.name),
+                name = element.name,
+            )),
+        )?;
+
+        let start_arms = self
+            .elements_sorted()
+            .filter(|(_id, element)| element.r#type == ElementType::Master)
+            .map(|(_id, element)| {
+                format!(
+                    "\n        parser::Readers::{name}(_) => writer.start::<element_defs::{name}Def>(),",
+                    name = element.name,
+                )
+            })
+            .collect::<String>();
+        write!(
+            writer,
+            r#"
+// starts writing a master element when the reader has just entered one
+#[allow(unused_variables)]
+fn start_master<R, W: Write>(
+    writer: &mut ElementWriter<W>,
+    reader: parser::Readers<R>,
+) -> parser::Readers<R> {{
+    {start}
+    reader
+}}
+            "#,
+            start = if start_arms.is_empty() {
+                String::new()
+            } else {
+                format!("match &reader {{{}\n        _ => {{}}\n    }}", start_arms)
+            },
+        )?;
+
+        for (id, element) in self
+            .elements_sorted()
+            .filter(|(_id, element)| element.r#type == ElementType::Master)
+        {
+            let methods = self
+                .children
+                .get(&Some(*id))
+                .into_iter()
+                .flatten()
+                .map(|child_id| {
+                    let child = self.elements.get(child_id).unwrap();
+                    let (method, value_type) = match child.r#type {
+                        ElementType::Master => {
+                            return format!(
+                                r#"
+    {vis} fn {method}(&mut self) -> {name}Builder<'_, W> {{
+        {name}Builder::new(self.writer)
+    }}
+"#,
+                                vis = self.visibility,
+                                method = method_name(&child.name),
+                                name = child.name,
+                            );
+                        }
+                        ElementType::UnsignedInteger => ("write_uint", "u64"),
+                        ElementType::SignedInteger => ("write_int", "i64"),
+                        ElementType::Float => ("write_float", "f64"),
+                        ElementType::Date => ("write_date", "i64"),
+                        ElementType::String => ("write_string", "&str"),
+                        ElementType::Utf8 => ("write_utf8", "&str"),
+                        ElementType::Binary => ("write_binary", "&[u8]"),
+                    };
+                    format!(
+                        r#"
+    {vis} fn {method}(&mut self, value: {value_type}) -> Result<&mut Self, WriterError> {{
+        self.writer.{write}::<element_defs::{name}Def>(value)?;
+        Ok(self)
+    }}
+"#,
+                        vis = self.visibility,
+                        method = method_name(&child.name),
+                        value_type = value_type,
+                        write = method,
+                        name = child.name,
+                    )
+                })
+                .collect::<String>();
+
+            write!(
+                writer,
+                r#"
+// Writes a `{name}` element; its children are written in the order that their methods are called.
+// The element's header is only written on `finish`, once its length is known.
+{vis} struct {name}Builder<'a, W: Write> {{
+    writer: &'a mut ElementWriter<W>,
+}}
+
+impl<'a, W: Write> {name}Builder<'a, W> {{
+    {vis} fn new(writer: &'a mut ElementWriter<W>) -> Self {{
+        writer.start::<element_defs::{name}Def>();
+        Self {{ writer }}
+    }}
+{methods}
+    {vis} fn finish(self) -> Result<(), WriterError> {{
+        self.writer.end::<element_defs::{name}Def>()
+    }}
+}}
+                "#,
+                vis = self.visibility,
+                name = element.name,
+                methods = methods,
+            )?;
         }
 
         Ok(())
     }
 
     pub fn write_package<P: AsRef<Path>>(&self, path: P) -> Result<(), WriteParserPackageError> {
-        let template_dir_path = {
-            let mut cwd = std::env::var("CARGO_MANIFEST_DIR")
-                .map(std::path::PathBuf::from)
-                .map_err(WriteParserPackageError::NoManifestPath)?;
-            cwd.pop();
-            cwd.push("base_template");
-            cwd
-        };
+        let template_dir_path = template_dir_path()?;
 
         std::fs::create_dir_all((path.as_ref()).join("src/base/"))
             .map_err(WriteParserPackageError::IOError)?;
         std::fs::create_dir_all((path.as_ref()).join("src/core/"))
             .map_err(WriteParserPackageError::IOError)?;
 
-        for filename in &[
-            "Cargo.toml",
-            "src/lib.rs",
-            "src/base/element_defs.rs",
-            "src/base/mod.rs",
-            "src/base/parser.rs",
-            "src/base/stream.rs",
-            "src/core/mod.rs",
-        ] {
+        for filename in TEMPLATE_FILES {
             std::fs::copy(
                 template_dir_path.join(filename),
                 path.as_ref().join(filename),
@@ -821,24 +2576,147 @@ impl_from_readers_for_states!({name}PrevReaders, {name}PrevStates, [_Document, F
             .map_err(WriteParserPackageError::IOError)?;
         }
 
-        {
-            let mut writer = std::fs::File::create(path.as_ref().join("src/core/element_defs.rs"))
-                .map(std::io::BufWriter::new)
+        for (filename, source) in self.generated_sources()? {
+            std::fs::write(path.as_ref().join(filename), source)
                 .map_err(WriteParserPackageError::IOError)?;
-            self.write_element_defs(&mut writer)
+        }
+
+        Ok(())
+    }
+
+    // Compares a previously-written package against what `write_package` would write now -> the
+    // paths of all files that are missing or out-of-date. Files are compared token-wise on
+    // whitespace, s.t. re-indenting the committed code (e.g. w/ `rustfmt`) isn't a difference.
+    pub fn check_package<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<Vec<PathBuf>, WriteParserPackageError> {
+        let template_dir_path = template_dir_path()?;
+        let mut expected_files = Vec::new();
+
+        for filename in TEMPLATE_FILES {
+            let source = std::fs::read_to_string(template_dir_path.join(filename))
                 .map_err(WriteParserPackageError::IOError)?;
+            expected_files.push((filename.to_string(), source));
+        }
+        for (filename, source) in self.generated_sources()? {
+            expected_files.push((filename.to_string(), source));
+        }
+
+        let mut stale_files = Vec::new();
+        for (filename, expt_source) in expected_files {
+            let file_path = path.as_ref().join(filename);
+            let is_stale = match std::fs::read_to_string(&file_path) {
+                Ok(source) => !source.split_whitespace().eq(expt_source.split_whitespace()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+                Err(e) => return Err(WriteParserPackageError::IOError(e)),
+            };
+            if is_stale {
+                stale_files.push(file_path);
+            }
         }
 
-        {
-            let mut writer = std::fs::File::create(path.as_ref().join("src/core/parser.rs"))
-                .map(std::io::BufWriter::new)
-                .map_err(WriteParserPackageError::IOError)?;
-            self.write_parsers(&mut writer)
-                .map_err(WriteParserPackageError::IOError)?;
-        }
+        Ok(stale_files)
+    }
+
+    // the generated source files of the package, by their paths within it
+    fn generated_sources(&self) -> Result<Vec<(&'static str, String)>, WriteParserPackageError> {
+        let mut element_defs = Vec::new();
+        self.write_element_defs(&mut element_defs)
+            .map_err(WriteParserPackageError::IOError)?;
+        let mut parsers = Vec::new();
+        self.write_parsers(&mut parsers)
+            .map_err(WriteParserPackageError::IOError)?;
+        let mut writers = Vec::new();
+        self.write_writers(&mut writers)
+            .map_err(WriteParserPackageError::IOError)?;
+
+        vec![
+            ("src/core/element_defs.rs", element_defs),
+            ("src/core/parser.rs", parsers),
+            ("src/core/writer.rs", writers),
+        ]
+        .into_iter()
+        .map(|(filename, source)| {
+            let source = String::from_utf8(source).expect("generated code is valid utf-8");
+            let source = if self.format {
+                format_source(&source)?.unwrap_or(source)
+            } else {
+                source
+            };
+            Ok((filename, source))
+        })
+        .collect()
+    }
+}
+
+// runs source code through `rustfmt` -> `None` if `rustfmt` isn't installed
+fn format_source(source: &str) -> Result<Option<String>, WriteParserPackageError> {
+    format_source_with("rustfmt", source)
+}
 
-        Ok(())
+fn format_source_with(
+    rustfmt: &str,
+    source: &str,
+) -> Result<Option<String>, WriteParserPackageError> {
+    let mut rustfmt = match std::process::Command::new(rustfmt)
+        .args(["--edition", "2018", "--emit", "stdout"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+    {
+        Ok(rustfmt) => rustfmt,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(WriteParserPackageError::IOError(error)),
+    };
+    // `rustfmt` reads all of its input before writing anything out, so this can't deadlock
+    rustfmt
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(source.as_bytes())
+        .map_err(WriteParserPackageError::IOError)?;
+    let output = rustfmt
+        .wait_with_output()
+        .map_err(WriteParserPackageError::IOError)?;
+
+    if !output.status.success() {
+        return Err(WriteParserPackageError::FormatError(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
     }
+    Ok(Some(
+        String::from_utf8(output.stdout).expect("rustfmt output is valid utf-8"),
+    ))
+}
+
+const RANGE_VALIDATED: &str = "ranges are validated in `Builder::generate`";
+const LENGTH_VALIDATED: &str = "lengths are validated in `Builder::generate`";
+const DEFAULT_VALIDATED: &str = "defaults are validated in `Builder::generate`";
+
+const TEMPLATE_FILES: [&str; 12] = [
+    "Cargo.toml",
+    "src/lib.rs",
+    "src/base/async_reader.rs",
+    "src/base/de.rs",
+    "src/base/element_defs.rs",
+    "src/base/header.rs",
+    "src/base/mod.rs",
+    "src/base/parser.rs",
+    "src/base/stream.rs",
+    "src/base/tree.rs",
+    "src/base/writer.rs",
+    "src/core/mod.rs",
+];
+
+fn template_dir_path() -> Result<PathBuf, WriteParserPackageError> {
+    let mut cwd = std::env::var("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .map_err(WriteParserPackageError::NoManifestPath)?;
+    cwd.pop();
+    cwd.push("base_template");
+    Ok(cwd)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -847,6 +2725,8 @@ pub enum WriteParserPackageError {
     NoManifestPath(std::env::VarError),
     #[error("IO error: {0}")]
     IOError(std::io::Error),
+    #[error("rustfmt failed: {0}")]
+    FormatError(String),
 }
 
 #[cfg(test)]
@@ -889,6 +2769,110 @@ mod tests {
         assert_eq!(s.parse(), expt_result);
     }
 
+    #[rstest]
+    #[case("FileName", "file_name")]
+    #[case("CRC32", "crc32")]
+    #[case("EBMLVersion", "ebml_version")]
+    #[case("DocType", "doc_type")]
+    #[case("Type", "r#type")]
+    #[case("Finish", "finish_")]
+    fn method_name_from_element(#[case] element_name: &str, #[case] expt_name: &str) {
+        assert_eq!(method_name(element_name), expt_name);
+    }
+
+    #[rstest]
+    #[case("0.5", Ok(RangeDef::IsExactly(0.5)))]
+    #[case("not 0", Ok(RangeDef::Excludes(0.0)))]
+    #[case(
+        "> 0.0",
+        Ok(RangeDef::IsWithin(Bound::Excluded(0.0), Bound::Unbounded))
+    )]
+    #[case(">=-1.5", Ok(RangeDef::IsWithin(Bound::Included(-1.5), Bound::Unbounded)))]
+    #[case(
+        "<= 1e3",
+        Ok(RangeDef::IsWithin(Bound::Unbounded, Bound::Included(1000.0)))
+    )]
+    #[case("0x1p-1074..", Err(RangeDefParserError::InvalidValue("0x1p-1074..".to_string())))]
+    #[case(
+        ">=0x1p-1074",
+        Ok(RangeDef::IsWithin(Bound::Included(f64::from_bits(1)), Bound::Unbounded))
+    )]
+    #[case("-0x1.8p+1", Ok(RangeDef::IsExactly(-3.0)))]
+    #[case(
+        ">0,<=0x1p+0",
+        Ok(RangeDef::IsWithin(Bound::Excluded(0.0), Bound::Included(1.0)))
+    )]
+    #[case("-2.5-2.5", Ok(RangeDef::IsWithin(Bound::Included(-2.5), Bound::Included(2.5))))]
+    #[case(
+        "0x1p+0-0x1.fffffffffffffp+1023",
+        Ok(RangeDef::IsWithin(Bound::Included(1.0), Bound::Included(f64::MAX)))
+    )]
+    #[case(">0,>1", Err(RangeDefParserError::InvalidBound(">0,>1".to_string())))]
+    #[case(">1,<0", Err(RangeDefParserError::InvertedBounds(">1,<0".to_string())))]
+    #[case(
+        "&gt;0,&lt;1",
+        Ok(RangeDef::IsWithin(Bound::Excluded(0.0), Bound::Excluded(1.0)))
+    )]
+    #[case("NaN", Err(RangeDefParserError::InvalidValue("NaN".to_string())))]
+    fn range_def_parse_float(
+        #[case] s: &'static str,
+        #[case] expt_result: Result<RangeDef<f64>, RangeDefParserError>,
+    ) {
+        assert_eq!(s.parse(), expt_result);
+    }
+
+    #[rstest]
+    #[case("not 0", Ok(RangeDef::Excludes(0)))]
+    #[case(
+        "1-10",
+        Ok(RangeDef::IsWithin(Bound::Included(1), Bound::Included(10)))
+    )]
+    #[case("0x10", Ok(RangeDef::IsExactly(16)))]
+    #[case("-1", Err(RangeDefParserError::InvalidValue("-1".to_string())))]
+    #[case("10-1", Err(RangeDefParserError::InvertedBounds("10-1".to_string())))]
+    #[case("&gt;0", Ok(RangeDef::IsWithin(Bound::Excluded(0), Bound::Unbounded)))]
+    #[case(
+        "&lt;=10",
+        Ok(RangeDef::IsWithin(Bound::Unbounded, Bound::Included(10)))
+    )]
+    #[case("&amp;1", Err(RangeDefParserError::InvalidValue("&1".to_string())))]
+    #[case("&amp;gt;1", Err(RangeDefParserError::InvalidValue("&gt;1".to_string())))]
+    fn range_def_parse_uint(
+        #[case] s: &'static str,
+        #[case] expt_result: Result<RangeDef<u64>, RangeDefParserError>,
+    ) {
+        assert_eq!(s.parse(), expt_result);
+    }
+
+    #[rstest]
+    #[case("+5", Ok(RangeDef::IsExactly(5)))]
+    #[case("+-5", Err(RangeDefParserError::InvalidValue("+-5".to_string())))]
+    #[case(
+        "-10--1",
+        Ok(RangeDef::IsWithin(Bound::Included(-10), Bound::Included(-1)))
+    )]
+    #[case(
+        ">=-0x8000000000000000",
+        Ok(RangeDef::IsWithin(Bound::Included(i64::MIN), Bound::Unbounded))
+    )]
+    #[case("0x8000000000000000", Err(RangeDefParserError::InvalidValue("0x8000000000000000".to_string())))]
+    fn range_def_parse_int(
+        #[case] s: &'static str,
+        #[case] expt_result: Result<RangeDef<i64>, RangeDefParserError>,
+    ) {
+        assert_eq!(s.parse(), expt_result);
+    }
+
+    #[rstest]
+    #[case(RangeDef::IsExactly(1.0), "Range::IsExactly(1.0)")]
+    #[case(
+        RangeDef::IsWithin(Bound::Excluded(f64::from_bits(1)), Bound::Unbounded),
+        "Range::IsWithin(Bound::Excluded(5e-324), Bound::Unbounded)"
+    )]
+    fn range_def_to_rust(#[case] range: RangeDef<f64>, #[case] expt_result: &str) {
+        assert_eq!(range.to_rust(), expt_result);
+    }
+
     #[fixture]
     fn schema() -> EbmlSchema {
         EbmlSchema {
@@ -970,7 +2954,7 @@ mod tests {
 
     #[rstest]
     fn builder_generate(schema: EbmlSchema) {
-        let result = Builder { schema }.generate();
+        let result = Builder::from_schema(schema).generate();
         let result = result.unwrap();
 
         assert_eq!(
@@ -1016,4 +3000,972 @@ mod tests {
             ]
         );
     }
+
+    fn element(name: &str, path: &str, id: u32, r#type: ElementType) -> Element {
+        Element::new(name, path, id, r#type)
+    }
+
+    #[rstest]
+    #[case("Type3D", true)]
+    #[case("EBML_Version", true)]
+    #[case("type", false)]
+    #[case("Self", false)]
+    #[case("3D", false)]
+    #[case("CRC-32", false)]
+    #[case("Name.With.Dots", false)]
+    #[case("_Document", false)]
+    #[case("Parent", false)]
+    #[case("Element", false)]
+    #[case("MasterElement", false)]
+    #[case("Parents", true)]
+    #[case("", false)]
+    fn builder_generate_names(#[case] name: &str, #[case] expt_valid: bool) {
+        let path = format!("\\{}", name);
+        let schema = EbmlSchema::new("test", 1).with_element(element(
+            name,
+            &path,
+            0x81,
+            ElementType::Binary,
+        ));
+
+        match Builder::from_schema(schema).generate() {
+            Ok(_) => assert!(expt_valid),
+            Err(BuilderGenerateError::InvalidName(invalid_name)) => {
+                assert!(!expt_valid);
+                assert_eq!(invalid_name, name);
+            }
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[rstest]
+    fn builder_generate_empty() {
+        let result = Builder::from_schema(EbmlSchema::new("test", 1))
+            .generate()
+            .unwrap();
+
+        assert!(result.elements.is_empty());
+        assert!(result.parents.is_empty());
+        // the root always exists, even w/o children
+        assert_eq!(
+            result.children.into_iter().collect::<Vec<_>>(),
+            vec![(None, BTreeSet::new())]
+        );
+
+        assert!(matches!(
+            Builder::from_schema(EbmlSchema::new("test", 1))
+                .require_root(true)
+                .generate(),
+            Err(BuilderGenerateError::NoElements)
+        ));
+    }
+
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    fn builder_generate_root_only(#[case] require_root: bool) {
+        let schema = EbmlSchema::new("test", 1).with_element(element(
+            "Files",
+            "\\Files",
+            0x1946696C,
+            ElementType::Master,
+        ));
+
+        let result = Builder::from_schema(schema)
+            .require_root(require_root)
+            .generate()
+            .unwrap();
+
+        assert_eq!(
+            result.elements.keys().collect::<Vec<_>>(),
+            vec![&0x1946696C]
+        );
+        assert_eq!(
+            result.parents.into_iter().collect::<Vec<_>>(),
+            vec![(0x1946696C, vec![None].into_iter().collect::<BTreeSet<_>>())]
+        );
+        assert_eq!(
+            result.children.into_iter().collect::<Vec<_>>(),
+            vec![
+                (None, vec![0x1946696C].into_iter().collect::<BTreeSet<_>>()),
+                (Some(0x1946696C), BTreeSet::new()),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn builder_generate_global_and_positional_paths() {
+        // `\(-\)X` & `\A\X` end in the same name, but differ in their global placeholders
+        assert_ne!(
+            "\\(-\\)X".parse::<PathAtoms>().unwrap().0,
+            "\\X".parse::<PathAtoms>().unwrap().0,
+        );
+        // ... yet both elements would be generated as the same `X` items
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![
+                element("A", "\\A", 0x81, ElementType::Master),
+                element("X", "\\(-\\)X", 0x82, ElementType::Binary),
+                element("X", "\\A\\X", 0x83, ElementType::UnsignedInteger),
+            ]),
+        };
+        assert!(matches!(
+            Builder::from_schema(schema).generate(),
+            Err(BuilderGenerateError::DuplicateName(name, 0x82, 0x83)) if name == "X"
+        ));
+
+        // a global & a positional element, under the same parent
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![
+                element("A", "\\A", 0x81, ElementType::Master),
+                element("X", "\\(-\\)X", 0x82, ElementType::Binary),
+                element("Y", "\\A\\Y", 0x83, ElementType::UnsignedInteger),
+            ]),
+        };
+        let result = Builder::from_schema(schema).generate().unwrap();
+
+        let mut source = Vec::new();
+        result.write_element_defs(&mut source).unwrap();
+        result.write_parsers(&mut source).unwrap();
+        let source = String::from_utf8(source).unwrap();
+        for item in ["struct XDef;", "struct YDef;", "type XState", "type YState"] {
+            assert_eq!(source.matches(item).count(), 1, "{}", item);
+        }
+
+        assert_eq!(
+            result.elements.keys().collect::<Vec<_>>(),
+            vec![&0x81, &0x82, &0x83]
+        );
+        assert_eq!(
+            result.parents.into_iter().collect::<Vec<_>>(),
+            vec![
+                (0x81, vec![None].into_iter().collect::<BTreeSet<_>>()),
+                (
+                    0x82,
+                    vec![None, Some(0x81)].into_iter().collect::<BTreeSet<_>>()
+                ),
+                (0x83, vec![Some(0x81)].into_iter().collect::<BTreeSet<_>>()),
+            ]
+        );
+        assert_eq!(
+            result.children.into_iter().collect::<Vec<_>>(),
+            vec![
+                (None, vec![0x81, 0x82].into_iter().collect::<BTreeSet<_>>()),
+                (
+                    Some(0x81),
+                    vec![0x82, 0x83].into_iter().collect::<BTreeSet<_>>()
+                ),
+                (Some(0x82), BTreeSet::new()),
+                (Some(0x83), BTreeSet::new()),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn write_element_defs_float_range() {
+        let mut ratio = element("Ratio", "\\Ratio", 0x81, ElementType::Float);
+        ratio.range = Some(">0x0p+0,<=1.0".to_string());
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![ratio]),
+        };
+
+        let mut source = Vec::new();
+        Builder::from_schema(schema)
+            .generate()
+            .unwrap()
+            .write_element_defs(&mut source)
+            .unwrap();
+
+        assert!(String::from_utf8(source).unwrap().contains(
+            "const RANGE: Range<f64> = Range::IsWithin(Bound::Excluded(0.0), Bound::Included(1.0));"
+        ));
+    }
+
+    #[rstest]
+    fn builder_generate_invalid_range() {
+        let mut ratio = element("Ratio", "\\Ratio", 0x81, ElementType::Float);
+        ratio.range = Some("> zero".to_string());
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![ratio]),
+        };
+
+        assert!(matches!(
+            Builder::from_schema(schema).generate(),
+            Err(BuilderGenerateError::InvalidRange(name, RangeDefParserError::InvalidValue(_)))
+                if name == "Ratio"
+        ));
+    }
+
+    #[rstest]
+    #[case("4", Some("Range::IsExactly(4)"))]
+    #[case(">0", Some("Range::IsWithin(Bound::Excluded(0), Bound::Unbounded)"))]
+    #[case("2-4", Some("Range::IsWithin(Bound::Included(2), Bound::Included(4))"))]
+    #[case("-1", None)]
+    fn write_element_defs_length(#[case] length: &str, #[case] expt_length: Option<&str>) {
+        let mut crc = element("CRC32", "\\CRC32", 0xBF, ElementType::Binary);
+        crc.length = Some(length.to_string());
+        let schema = EbmlSchema::new("test", 1).with_element(crc);
+
+        match (Builder::from_schema(schema).generate(), expt_length) {
+            (Ok(parsers), Some(expt_length)) => {
+                let mut source = Vec::new();
+                parsers.write_element_defs(&mut source).unwrap();
+                assert!(String::from_utf8(source)
+                    .unwrap()
+                    .contains(&format!("const LENGTH: Range<usize> = {};", expt_length)));
+            }
+            (Err(BuilderGenerateError::InvalidLength(name, _)), None) => {
+                assert_eq!(name, "CRC32")
+            }
+            (result, _) => panic!("unexpected result {:?}", result.map(drop)),
+        }
+    }
+
+    #[rstest]
+    #[case(
+        ElementType::UnsignedInteger,
+        "1",
+        Some("const DEFAULT: Option<u64> = Some(1);")
+    )]
+    #[case(
+        ElementType::SignedInteger,
+        "-0x10",
+        Some("const DEFAULT: Option<i64> = Some(-16);")
+    )]
+    #[case(
+        ElementType::Float,
+        "1.0",
+        Some("const DEFAULT: Option<f64> = Some(1.0);")
+    )]
+    #[case(
+        ElementType::Float,
+        "0x1p+0",
+        Some("const DEFAULT: Option<f64> = Some(1.0);")
+    )]
+    #[case(
+        ElementType::Float,
+        "-0x1.8p-1",
+        Some("const DEFAULT: Option<f64> = Some(-0.75);")
+    )]
+    #[case(ElementType::Date, "0", Some("const DEFAULT: Option<i64> = Some(0);"))]
+    #[case(
+        ElementType::String,
+        "say \"hi\"",
+        Some(r#"const DEFAULT: Option<&'static str> = Some("say \"hi\"");"#)
+    )]
+    #[case(
+        ElementType::Utf8,
+        "caf\u{e9}",
+        Some("const DEFAULT: Option<&'static str> = Some(\"caf\u{e9}\");")
+    )]
+    #[case(
+        ElementType::Binary,
+        "0x0AFF",
+        Some("const DEFAULT: Option<&'static [u8]> = Some(&[0x0A, 0xFF]);")
+    )]
+    #[case(
+        ElementType::Binary,
+        "0x01020304",
+        Some("const DEFAULT: Option<&'static [u8]> = Some(&[0x01, 0x02, 0x03, 0x04]);")
+    )]
+    #[case(
+        ElementType::Binary,
+        "01020304",
+        Some("const DEFAULT: Option<&'static [u8]> = Some(&[0x01, 0x02, 0x03, 0x04]);")
+    )]
+    #[case(ElementType::UnsignedInteger, "-1", None)]
+    #[case(ElementType::Float, "0x1.8", None)]
+    #[case(ElementType::Float, "one", None)]
+    #[case(ElementType::String, "caf\u{e9}", None)]
+    #[case(ElementType::Binary, "0xABC", None)]
+    #[case(ElementType::Binary, "0x0G", None)]
+    #[case(ElementType::Master, "1", None)]
+    fn write_element_defs_default(
+        #[case] r#type: ElementType,
+        #[case] default: &str,
+        #[case] expt_default: Option<&str>,
+    ) {
+        let mut value = element("Value", "\\Value", 0x81, r#type);
+        value.default = Some(default.to_string());
+        let schema = EbmlSchema::new("test", 1).with_element(value);
+
+        match (Builder::from_schema(schema).generate(), expt_default) {
+            (Ok(parsers), Some(expt_default)) => {
+                let mut source = Vec::new();
+                parsers.write_element_defs(&mut source).unwrap();
+                assert!(String::from_utf8(source).unwrap().contains(expt_default));
+            }
+            (Err(BuilderGenerateError::InvalidDefault(name, _)), None) => {
+                assert_eq!(name, "Value")
+            }
+            (result, _) => panic!("unexpected result {:?}", result.map(drop)),
+        }
+    }
+
+    #[rstest]
+    fn write_parsers_prev_states() {
+        let schema = EbmlSchema::new("test", 1)
+            .with_element(element("Segment", "\\Segment", 0x81, ElementType::Master))
+            .with_element(element(
+                "Cluster",
+                "\\Segment\\Cluster",
+                0x82,
+                ElementType::Master,
+            ))
+            .with_element(element("Void", "\\(-\\)Void", 0xEC, ElementType::Binary));
+        let parsers = Builder::from_schema(schema).generate().unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_parsers(&mut source).unwrap();
+        let source = String::from_utf8(source).unwrap();
+
+        for expt_line in [
+            "impl_from_substates_for_states!(VoidPrevStates, States, [Cluster, Segment, _Document]);",
+            "impl_from_subreaders_for_readers!(VoidPrevReaders, Readers, [Cluster, Segment, _Document]);",
+            "impl_into_reader!(VoidPrevStates, VoidPrevReaders, [Cluster, Segment, _Document]);",
+            "impl_from_readers_for_states!(VoidPrevReaders, VoidPrevStates, [Cluster, Segment, _Document]);",
+        ] {
+            assert!(source.contains(expt_line), "missing {}", expt_line);
+        }
+        assert!(!source.contains("Files"));
+    }
+
+    #[rstest]
+    #[case("\\(-\\)Void", "BlockGroup, Cluster, Segment, _Document")]
+    #[case("\\(1-\\)Void", "BlockGroup, Cluster, Segment")]
+    #[case("\\(-1\\)Void", "Segment, _Document")]
+    #[case("\\Segment\\(-\\)Void", "BlockGroup, Cluster, Segment")]
+    #[case("\\Segment\\(1-\\)Void", "BlockGroup, Cluster")]
+    fn write_parsers_prev_states_nested(#[case] path: &str, #[case] expt_parents: &str) {
+        let schema = EbmlSchema::new("test", 1)
+            .with_element(element("Segment", "\\Segment", 0x81, ElementType::Master))
+            .with_element(element(
+                "Cluster",
+                "\\Segment\\Cluster",
+                0x82,
+                ElementType::Master,
+            ))
+            .with_element(element(
+                "BlockGroup",
+                "\\Segment\\Cluster\\BlockGroup",
+                0x83,
+                ElementType::Master,
+            ))
+            .with_element(element("Void", path, 0xEC, ElementType::Binary));
+        let parsers = Builder::from_schema(schema).generate().unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_parsers(&mut source).unwrap();
+        let source = String::from_utf8(source).unwrap();
+
+        let expt_line = format!(
+            "impl_into_reader!(VoidPrevStates, VoidPrevReaders, [{}]);",
+            expt_parents
+        );
+        assert!(source.contains(&expt_line), "missing {}", expt_line);
+    }
+
+    #[rstest]
+    fn builder_generate_missing_ancestor() {
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![
+                element("Segment", "\\Segment", 0x81, ElementType::Master),
+                element(
+                    "Timestamp",
+                    "\\Segment\\Cluster\\Timestamp",
+                    0x82,
+                    ElementType::UnsignedInteger,
+                ),
+            ]),
+        };
+
+        assert!(matches!(
+            Builder::from_schema(schema).generate(),
+            Err(BuilderGenerateError::MissingAncestor { element, missing_ancestor })
+                if element == "Timestamp" && missing_ancestor == "Cluster"
+        ));
+    }
+
+    #[rstest]
+    fn builder_generate_non_master_parent() {
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![
+                element("Title", "\\Title", 0x81, ElementType::Utf8),
+                element("Language", "\\Title\\Language", 0x82, ElementType::String),
+            ]),
+        };
+
+        assert!(matches!(
+            Builder::from_schema(schema).generate(),
+            Err(BuilderGenerateError::NonMasterParent { element, parent })
+                if element == "Language" && parent == "Title"
+        ));
+    }
+
+    #[rstest]
+    fn builder_generate_non_master_parent_message() {
+        let schema = EbmlSchema::new("test", 1)
+            .with_element(element("Info", "\\Info", 0x81, ElementType::Master))
+            .with_element(element(
+                "Count",
+                "\\Info\\Count",
+                0x82,
+                ElementType::UnsignedInteger,
+            ))
+            .with_element(element(
+                "Label",
+                "\\Info\\Count\\Label",
+                0x83,
+                ElementType::Utf8,
+            ));
+
+        assert_eq!(
+            Builder::from_schema(schema)
+                .generate()
+                .err()
+                .unwrap()
+                .to_string(),
+            "element Label is nested under Count, which isn't a master element"
+        );
+    }
+
+    #[rstest]
+    #[case(Some(1), Some(1), true)]
+    #[case(None, Some(1), true)]
+    #[case(Some(2), None, true)]
+    #[case(Some(2), Some(1), false)]
+    fn builder_generate_occurrence_bounds(
+        #[case] min_occurs: Option<usize>,
+        #[case] max_occurs: Option<usize>,
+        #[case] expt_valid: bool,
+    ) {
+        let mut title = element("Title", "\\Title", 0x81, ElementType::Utf8);
+        title.min_occurs = min_occurs;
+        title.max_occurs = max_occurs;
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![title]),
+        };
+
+        match Builder::from_schema(schema).generate() {
+            Ok(_) => assert!(expt_valid),
+            Err(BuilderGenerateError::InvalidOccurrenceBounds { name, min, max }) => {
+                assert!(!expt_valid);
+                assert_eq!((name.as_str(), min, max), ("Title", 2, 1));
+            }
+            Err(error) => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[rstest]
+    fn builder_generate_duplicate_id() {
+        let schema = EbmlSchema {
+            doc_type: "test".to_string(),
+            version: 1,
+            ebml: None,
+            elements: Some(vec![
+                element("Title", "\\Title", 0x81, ElementType::Utf8),
+                element("Language", "\\Language", 0x81, ElementType::String),
+            ]),
+        };
+
+        assert!(matches!(
+            Builder::from_schema(schema).generate(),
+            Err(BuilderGenerateError::DuplicateId(0x81, first, second))
+                if first == "Title" && second == "Language"
+        ));
+    }
+
+    #[rstest]
+    fn write_package_deterministic(schema: EbmlSchema) {
+        let write_all = |parsers: Parsers| {
+            let mut source = Vec::new();
+            parsers.write_element_defs(&mut source).unwrap();
+            parsers.write_parsers(&mut source).unwrap();
+            source
+        };
+
+        let source1 = write_all(Builder::from_schema(schema.clone()).generate().unwrap());
+        let source2 = write_all(Builder::from_schema(schema).generate().unwrap());
+
+        assert_eq!(source1, source2);
+    }
+
+    #[rstest]
+    fn builder_generate_empty_schema() {
+        let schema: EbmlSchema = from_str(
+            r#"<EBMLSchema xmlns="urn:ietf:rfc:8794" docType="files-in-ebml-demo" version="1"/>"#,
+        )
+        .unwrap();
+        assert_eq!(schema.elements, None);
+
+        let parsers = Builder::from_schema(schema).generate().unwrap();
+        assert!(parsers.elements.is_empty());
+        assert!(parsers.parents.is_empty());
+        assert_eq!(
+            parsers.children.into_iter().collect::<Vec<_>>(),
+            vec![(None, BTreeSet::new())]
+        );
+    }
+
+    #[rstest]
+    fn write_parsers_empty_schema() {
+        let parsers = Builder::from_schema(EbmlSchema::new("test", 1))
+            .generate()
+            .unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_parsers(&mut source).unwrap();
+        let source = String::from_utf8(source).unwrap();
+
+        // the document can't have any children -> no navigation macros for `_Document`
+        assert!(source.contains("_Never(core::convert::Infallible)"));
+        assert!(!source.contains("impl_next_state_navigation!(\n    _DocumentState"));
+    }
+
+    #[rstest]
+    #[case(Visibility::Pub, "pub struct FileDef;", "pub type FileState")]
+    #[case(
+        Visibility::Crate,
+        "pub(crate) struct FileDef;",
+        "pub(crate) type FileState"
+    )]
+    fn write_package_visibility(
+        #[case] visibility: Visibility,
+        #[case] expt_def: &str,
+        #[case] expt_state: &str,
+    ) {
+        let schema = EbmlSchema::new("test", 1)
+            .with_element(element("File", "\\File", 0x81, ElementType::Master))
+            .with_element(element("Data", "\\File\\Data", 0x82, ElementType::Binary));
+        let parsers = Builder::from_schema(schema)
+            .visibility(visibility)
+            .generate()
+            .unwrap();
+
+        let mut element_defs = Vec::new();
+        parsers.write_element_defs(&mut element_defs).unwrap();
+        let element_defs = String::from_utf8(element_defs).unwrap();
+        let mut parsers_source = Vec::new();
+        parsers.write_parsers(&mut parsers_source).unwrap();
+        let parsers_source = String::from_utf8(parsers_source).unwrap();
+
+        assert!(element_defs.contains(expt_def));
+        assert!(element_defs.contains(&format!("{} enum ElementId", visibility)));
+        assert!(parsers_source.contains(expt_state));
+        for item in [
+            "enum Readers<R>",
+            "struct _DocumentState",
+            "fn walk_with_handlers",
+            "fn collect_by_id",
+            "fn collect_by_name",
+            "trait Visitor<R>",
+            "fn drive",
+            "struct ElementEvents",
+        ] {
+            assert!(parsers_source.contains(&format!("{} {}", visibility, item)));
+        }
+
+        let mut writers_source = Vec::new();
+        parsers.write_writers(&mut writers_source).unwrap();
+        let writers_source = String::from_utf8(writers_source).unwrap();
+        for item in [
+            "fn copy_document",
+            "trait Transcoder<R: BufRead, W: Write>",
+            "fn transcode",
+        ] {
+            assert!(writers_source.contains(&format!("{} {}", visibility, item)));
+        }
+
+        // only crate-private items may go unused
+        for source in [&element_defs, &parsers_source, &writers_source] {
+            assert_eq!(
+                source.starts_with("\n#![allow(dead_code)]\n"),
+                visibility == Visibility::Crate
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(None, Some(vec!["Group".to_string(), "Group".to_string()]))]
+    #[case(Some(true), None)]
+    fn builder_generate_cycle(
+        #[case] recursive: Option<bool>,
+        #[case] expt_cycle: Option<Vec<String>>,
+    ) {
+        // a global master element may be nested within any master, *including itself*
+        let mut group = element("Group", "\\(1-\\)Group", 0x82, ElementType::Master);
+        group.recursive = recursive;
+        let schema = EbmlSchema::new("test", 1)
+            .with_element(element("Segment", "\\Segment", 0x81, ElementType::Master))
+            .with_element(group);
+
+        let result = Builder::from_schema(schema).generate();
+        match expt_cycle {
+            Some(expt_cycle) => assert!(matches!(
+                result,
+                Err(BuilderGenerateError::UnexpectedCycle(cycle)) if cycle == expt_cycle
+            )),
+            None => assert!(result.is_ok()),
+        }
+    }
+
+    #[rstest]
+    fn write_parsers_recursive() {
+        let mut tag = element("Tag", "\\Tags\\Tag", 0x82, ElementType::Master);
+        tag.recursive = Some(true);
+        tag.min_occurs = Some(1);
+        let parsers = Builder::from_schema(
+            EbmlSchema::new("test", 1)
+                .with_element(element("Tags", "\\Tags", 0x81, ElementType::Master))
+                .with_element(tag),
+        )
+        .generate()
+        .unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_parsers(&mut source).unwrap();
+        let source = String::from_utf8(source).unwrap();
+        // a `Tag` may be nested in a `Tag`, whose state is boxed to keep the state type finite
+        assert!(source.contains("pub enum TagNextStates {\n    Tag(TagState), \n"));
+        assert!(source.contains("Tag(Box<TagState>),"));
+
+        let mut source = Vec::new();
+        parsers.write_element_defs(&mut source).unwrap();
+        let source = String::from_utf8(source).unwrap();
+        // ...but a `Tag` mustn't *require* another `Tag` within it
+        assert!(source.contains("const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[];"));
+        assert!(source.contains("const MANDATORY_CHILDREN: &'static [(u32, usize)] = &[(130, 1)];"));
+    }
+
+    #[rstest]
+    fn generated_sources_module_paths() {
+        let parsers = Builder::from_schema(
+            EbmlSchema::new("test", 1)
+                .with_element(element("Tags", "\\Tags", 0x81, ElementType::Master))
+                .with_element(element(
+                    "TagName",
+                    "\\Tags\\TagName",
+                    0x83,
+                    ElementType::Utf8,
+                )),
+        )
+        .with_base_path("ebml_base::base")
+        .with_core_module("crate::ebml")
+        .generate()
+        .unwrap()
+        .format(false);
+
+        let sources = parsers.generated_sources().unwrap();
+        for (filename, source) in sources.iter() {
+            assert!(!source.contains("crate::base"), "{}", filename);
+            assert!(!source.contains("crate::core"), "{}", filename);
+        }
+        let (_, element_defs) = &sources[0];
+        assert!(element_defs.contains("use ebml_base::base::element_defs::{"));
+        let (_, parsers) = &sources[1];
+        assert!(parsers.contains("use ebml_base::base::parser::{"));
+        assert!(parsers.contains("use crate::ebml::element_defs;"));
+        // the base's macros are exported from the root of its crate
+        assert!(parsers.contains("use ebml_base::{"));
+        let (_, writers) = &sources[2];
+        assert!(writers.contains("use ebml_base::base::writer::{ElementWriter, WriterError};"));
+        assert!(writers.contains("use crate::ebml::parser;"));
+    }
+
+    #[rstest]
+    fn generated_sources_format() {
+        let mut tag = element("Tag", "\\Tags\\Tag", 0x82, ElementType::Master);
+        tag.recursive = Some(true);
+        let parsers = Builder::from_schema(
+            EbmlSchema::new("test", 1)
+                .with_element(element("Tags", "\\Tags", 0x81, ElementType::Master))
+                .with_element(tag)
+                .with_element(element(
+                    "TagName",
+                    "\\Tags\\Tag\\TagName",
+                    0x83,
+                    ElementType::Utf8,
+                )),
+        )
+        .generate()
+        .unwrap();
+
+        for (filename, source) in parsers.generated_sources().unwrap() {
+            // formatting already-formatted code changes nothing
+            assert_eq!(
+                format_source(&source).unwrap(),
+                Some(source),
+                "{}",
+                filename
+            );
+        }
+        // w/o `rustfmt`, the code is left as-is
+        assert_eq!(
+            format_source_with("no-such-rustfmt", "fn  f( ) {}").unwrap(),
+            None
+        );
+
+        let parsers = parsers.format(false);
+        let mut source = Vec::new();
+        parsers.write_parsers(&mut source).unwrap();
+        assert_eq!(
+            parsers.generated_sources().unwrap()[1],
+            ("src/core/parser.rs", String::from_utf8(source).unwrap())
+        );
+    }
+
+    #[rstest]
+    fn write_element_defs_enums() {
+        let schema = r#"
+            <EBMLSchema xmlns="urn:ietf:rfc:8794" docType="matroska" version="4">
+                <element name="TrackType" path="\TrackType" id="0x83" type="uinteger">
+                    <restriction>
+                        <enum value="1" label="video"/>
+                        <enum value="2" label="audio"/>
+                    </restriction>
+                </element>
+            </EBMLSchema>
+        "#;
+        let parsers = Builder::new(schema.as_bytes()).unwrap().generate().unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_element_defs(&mut source).unwrap();
+
+        assert!(String::from_utf8(source).unwrap().contains(
+            r#"const ENUMS: &'static [(&'static str, u64)] = &[("video", 1), ("audio", 2)];"#
+        ));
+    }
+
+    #[rstest]
+    #[case(
+        "en",
+        "/// An attached file.\n///\n/// Files are stored whole.\n#[derive"
+    )]
+    #[case("de", "/// Eine angehängte Datei.\n#[derive")]
+    fn write_element_defs_docs(#[case] lang: &str, #[case] expt_docs: &str) {
+        let schema = r#"
+            <EBMLSchema xmlns="urn:ietf:rfc:8794" docType="files-in-ebml-demo" version="1">
+                <element name="File" path="\File" id="0x6146" type="master">
+                    <documentation lang="en" purpose="rationale">
+                        Files are stored
+                        whole.
+                    </documentation>
+                    <documentation lang="en" purpose="definition">An attached file.</documentation>
+                    <documentation lang="de" purpose="definition">Eine angehängte Datei.</documentation>
+                </element>
+            </EBMLSchema>
+        "#;
+        let parsers = Builder::new(schema.as_bytes())
+            .unwrap()
+            .doc_lang(lang)
+            .generate()
+            .unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_element_defs(&mut source).unwrap();
+
+        assert!(String::from_utf8(source).unwrap().contains(expt_docs));
+    }
+
+    #[rstest]
+    fn write_element_defs_rust_enums() {
+        let schema = r#"
+            <EBMLSchema xmlns="urn:ietf:rfc:8794" docType="matroska" version="4">
+                <element name="TrackType" path="\TrackType" id="0x83" type="uinteger">
+                    <restriction>
+                        <enum value="1" label="video"/>
+                        <enum value="2" label="audio"/>
+                        <enum value="3" label="3d-video"/>
+                        <enum value="4" label="Video"/>
+                    </restriction>
+                </element>
+            </EBMLSchema>
+        "#;
+        let parsers = Builder::new(schema.as_bytes()).unwrap().generate().unwrap();
+
+        let mut element_defs = Vec::new();
+        parsers.write_element_defs(&mut element_defs).unwrap();
+        let element_defs = String::from_utf8(element_defs).unwrap();
+        let mut parsers_source = Vec::new();
+        parsers.write_parsers(&mut parsers_source).unwrap();
+        let parsers_source = String::from_utf8(parsers_source).unwrap();
+
+        assert!(element_defs.contains(
+            "pub enum TrackTypeEnum {\n    Video,\n    Audio,\n    V3dVideo,\n    Video4,\n}"
+        ));
+        assert!(element_defs.contains("1 => Ok(Self::Video),"));
+        assert!(element_defs.contains("TrackTypeEnum::Video4 => 4,"));
+        assert!(parsers_source.contains(
+            "pub fn read_enum(&mut self) -> Result<element_defs::TrackTypeEnum, ReaderError>"
+        ));
+    }
+
+    #[rstest]
+    fn write_element_defs_rust_enums_unique() {
+        // named like the `Range` type that the element definitions import
+        let schema = r#"
+            <EBMLSchema xmlns="urn:ietf:rfc:8794" docType="matroska" version="4">
+                <element name="Range" path="\Range" id="0x83" type="uinteger">
+                    <restriction>
+                        <enum value="0" label="a"/>
+                        <enum value="1" label="a"/>
+                        <enum value="2" label="a1"/>
+                    </restriction>
+                </element>
+            </EBMLSchema>
+        "#;
+        let parsers = Builder::new(schema.as_bytes()).unwrap().generate().unwrap();
+
+        let mut element_defs = Vec::new();
+        parsers.write_element_defs(&mut element_defs).unwrap();
+        let element_defs = String::from_utf8(element_defs).unwrap();
+
+        assert!(element_defs.contains("pub enum RangeEnum {\n    A,\n    A1,\n    A12,\n}"));
+        assert!(element_defs.contains("2 => Ok(Self::A12),"));
+    }
+
+    #[rstest]
+    fn write_element_defs_names() {
+        let schema = EbmlSchema::new("test", 1)
+            .with_element(element("Files", "\\Files", 0x82, ElementType::Master))
+            .with_element(element("File", "\\Files\\File", 0x81, ElementType::Master));
+        let parsers = Builder::from_schema(schema).generate().unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_element_defs(&mut source).unwrap();
+
+        let source = String::from_utf8(source).unwrap();
+        assert!(source.contains(
+            r#"pub const ELEMENT_NAMES: &[(u32, &str)] = &[(129, "File"), (130, "Files")];"#
+        ));
+        assert!(source.contains("pub enum ElementId {\n    File,\n    Files,\n}"));
+        assert!(source.contains("130 => Some(Self::Files),"));
+        assert!(source.contains(r#""Files" => Some(Self::Files),"#));
+    }
+
+    #[rstest]
+    #[case(EbmlSchema::new("test", 1), "pub const EBML_VERSION: u32 = 1;")]
+    #[case(EbmlSchema::new("test", 1).with_ebml(2), "pub const EBML_VERSION: u32 = 2;")]
+    fn write_element_defs_ebml_version(#[case] schema: EbmlSchema, #[case] expt_version: &str) {
+        let parsers = Builder::from_schema(schema).generate().unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_element_defs(&mut source).unwrap();
+        assert!(String::from_utf8(source).unwrap().contains(expt_version));
+    }
+
+    #[rstest]
+    fn write_element_defs_names_empty() {
+        let parsers = Builder::from_schema(EbmlSchema::new("test", 1))
+            .generate()
+            .unwrap();
+
+        let mut source = Vec::new();
+        parsers.write_element_defs(&mut source).unwrap();
+
+        // an empty enum is still valid, as long as it's never cast to an integer
+        let source = String::from_utf8(source).unwrap();
+        assert!(source.contains("pub enum ElementId {\n}"));
+        assert!(source.contains("match self {\n        }"));
+    }
+
+    fn example_generated_path() -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.pop();
+        path.push("example_generated");
+        path
+    }
+
+    #[rstest]
+    fn write_schema_round_trip() {
+        let schema_file =
+            std::fs::File::open(example_generated_path().join("eg_schema.xml")).unwrap();
+        let builder = Builder::new(std::io::BufReader::new(schema_file)).unwrap();
+
+        let mut xml = Vec::new();
+        builder.write_schema(&mut xml).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+        assert!(xml.contains(
+            r#"<EBMLSchema xmlns="urn:ietf:rfc:8794" docType="files-in-ebml-demo" version="1">"#
+        ));
+        assert!(xml.contains(r#"id="0x1946696C""#));
+
+        assert_eq!(Builder::new(xml.as_bytes()).unwrap().schema, builder.schema);
+    }
+
+    #[rstest]
+    fn from_path() {
+        let schema_file =
+            std::fs::File::open(example_generated_path().join("eg_schema.xml")).unwrap();
+        let builder = Builder::new(std::io::BufReader::new(schema_file)).unwrap();
+
+        let builder_from_path =
+            Builder::from_path(example_generated_path().join("eg_schema.xml")).unwrap();
+        assert_eq!(builder_from_path.schema, builder.schema);
+        assert!(builder_from_path.generate().is_ok());
+
+        assert!(matches!(
+            Builder::from_path(example_generated_path().join("missing.xml")),
+            Err(BuilderError::IOError(_))
+        ));
+        assert!(matches!(
+            Builder::from_path(example_generated_path().join("Cargo.toml")),
+            Err(BuilderError::SchemaError(_))
+        ));
+    }
+
+    #[rstest]
+    fn check_package_example_generated() {
+        let path = example_generated_path();
+        let parsers = Builder::from_path(path.join("eg_schema.xml"))
+            .unwrap()
+            .generate()
+            .unwrap();
+
+        assert_eq!(
+            parsers.check_package(path.join("parser")).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[rstest]
+    fn check_package_stale(schema: EbmlSchema) {
+        let parsers = Builder::from_schema(schema).generate().unwrap();
+        let path = std::env::temp_dir().join(format!("iron-ebmlem-check-{}", std::process::id()));
+        parsers.write_package(&path).unwrap();
+        assert_eq!(parsers.check_package(&path).unwrap(), Vec::<PathBuf>::new());
+
+        let parser_path = path.join("src/core/parser.rs");
+        let mut source = std::fs::read_to_string(&parser_path).unwrap();
+        source.push_str("\npub struct Extra;\n");
+        std::fs::write(&parser_path, source).unwrap();
+        std::fs::remove_file(path.join("src/core/element_defs.rs")).unwrap();
+        let result = parsers.check_package(&path);
+        std::fs::remove_dir_all(&path).unwrap();
+
+        assert_eq!(
+            result.unwrap(),
+            vec![path.join("src/core/element_defs.rs"), parser_path]
+        );
+    }
 }
@@ -1,5 +1,7 @@
 use serde_derive::{Deserialize, Serialize};
+#[allow(unused_imports)]
 pub(crate) use serde_xml_rs::{from_reader, from_str, to_string};
+use xml::writer::XmlEvent;
 
 // documentation, element, enum, extension, implementation_note, restriction, EBMLSchema
 
@@ -26,7 +28,11 @@ pub(crate) mod custom_serde {
         where
             S: Serializer,
         {
-            format!("{:#X}", value).serialize(serializer)
+            to_string(value).serialize(serializer)
+        }
+
+        pub(crate) fn to_string(value: &u32) -> String {
+            format!("{:#X}", value)
         }
     }
 }
@@ -34,7 +40,7 @@ pub(crate) mod custom_serde {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename = "EBMLSchema")]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct EbmlSchema {
+pub struct EbmlSchema {
     pub(crate) doc_type: String,
     pub(crate) version: u32,
     pub(crate) ebml: Option<u32>,
@@ -42,9 +48,47 @@ pub(crate) struct EbmlSchema {
     pub(crate) elements: Option<Vec<Element>>,
 }
 
+impl EbmlSchema {
+    pub fn new(doc_type: &str, version: u32) -> Self {
+        Self {
+            doc_type: doc_type.to_string(),
+            version,
+            ebml: None,
+            elements: None,
+        }
+    }
+
+    pub fn with_element(mut self, element: Element) -> Self {
+        self.elements.get_or_insert_with(Vec::new).push(element);
+        self
+    }
+
+    // sets the EBML version that the schema's documents use (defaults to 1)
+    pub fn with_ebml(mut self, ebml: u32) -> Self {
+        self.ebml = Some(ebml);
+        self
+    }
+
+    pub fn doc_type(&self) -> &str {
+        &self.doc_type
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn ebml(&self) -> u32 {
+        self.ebml.unwrap_or(1)
+    }
+
+    pub fn elements(&self) -> &[Element] {
+        self.elements.as_deref().unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct Element {
+pub struct Element {
     pub(crate) name: String,
     pub(crate) path: String,
     #[serde(with = "custom_serde::hexadecimal")]
@@ -65,9 +109,157 @@ pub(crate) struct Element {
     pub(crate) metadata: Option<Vec<ElementValue>>,
 }
 
+/// Elements can also be defined in code, rather than read from a schema file:
+///
+/// ```
+/// use iron_ebmlem::parser_gen::Builder;
+/// use iron_ebmlem::serde_schema::{EbmlSchema, Element, ElementType};
+///
+/// let schema = EbmlSchema::new("files-in-ebml-demo", 1)
+///     .with_element(Element::new("Files", r"\Files", 0x1946696C, ElementType::Master))
+///     .with_element(
+///         Element::new("FileName", r"\Files\FileName", 0x614E, ElementType::Utf8)
+///             .with_min_occurs(1)
+///             .with_max_occurs(1),
+///     );
+/// assert_eq!(schema.elements()[1].min_occurs(), Some(1));
+///
+/// let parsers = Builder::from_schema(schema).generate().unwrap();
+/// let mut source = Vec::new();
+/// parsers.write_element_defs(&mut source).unwrap();
+/// assert!(String::from_utf8(source).unwrap().contains("pub struct FileNameDef;"));
+/// ```
+impl Element {
+    pub fn new(name: &str, path: &str, id: u32, r#type: ElementType) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            id,
+            min_occurs: None,
+            max_occurs: None,
+            range: None,
+            length: None,
+            default: None,
+            r#type,
+            unknownsizeallowed: None,
+            recursive: None,
+            recurring: None,
+            minver: None,
+            maxver: None,
+            metadata: None,
+        }
+    }
+
+    pub fn with_min_occurs(mut self, min_occurs: usize) -> Self {
+        self.min_occurs = Some(min_occurs);
+        self
+    }
+
+    pub fn with_max_occurs(mut self, max_occurs: usize) -> Self {
+        self.max_occurs = Some(max_occurs);
+        self
+    }
+
+    pub fn with_range(mut self, range: &str) -> Self {
+        self.range = Some(range.to_string());
+        self
+    }
+
+    pub fn with_length(mut self, length: &str) -> Self {
+        self.length = Some(length.to_string());
+        self
+    }
+
+    pub fn with_default(mut self, default: &str) -> Self {
+        self.default = Some(default.to_string());
+        self
+    }
+
+    pub fn with_unknownsizeallowed(mut self, unknownsizeallowed: bool) -> Self {
+        self.unknownsizeallowed = Some(unknownsizeallowed);
+        self
+    }
+
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = Some(recursive);
+        self
+    }
+
+    pub fn with_recurring(mut self, recurring: bool) -> Self {
+        self.recurring = Some(recurring);
+        self
+    }
+
+    pub fn with_minver(mut self, minver: u32) -> Self {
+        self.minver = Some(minver);
+        self
+    }
+
+    pub fn with_maxver(mut self, maxver: u32) -> Self {
+        self.maxver = Some(maxver);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn element_type(&self) -> &ElementType {
+        &self.r#type
+    }
+
+    pub fn min_occurs(&self) -> Option<usize> {
+        self.min_occurs
+    }
+
+    pub fn max_occurs(&self) -> Option<usize> {
+        self.max_occurs
+    }
+
+    pub fn range(&self) -> Option<&str> {
+        self.range.as_deref()
+    }
+
+    pub fn length(&self) -> Option<&str> {
+        self.length.as_deref()
+    }
+
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn unknownsizeallowed(&self) -> Option<bool> {
+        self.unknownsizeallowed
+    }
+
+    pub fn recursive(&self) -> Option<bool> {
+        self.recursive
+    }
+
+    pub fn recurring(&self) -> Option<bool> {
+        self.recurring
+    }
+
+    pub fn minver(&self) -> Option<u32> {
+        self.minver
+    }
+
+    pub fn maxver(&self) -> Option<u32> {
+        self.maxver
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename = "type")]
-pub(crate) enum ElementType {
+pub enum ElementType {
     #[serde(rename = "integer")]
     SignedInteger,
     #[serde(rename = "uinteger")]
@@ -143,6 +335,301 @@ pub(crate) struct Extension {
     pub(crate) cppname: Option<String>,
 }
 
+impl ElementType {
+    // the type's name in schema files
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::SignedInteger => "integer",
+            Self::UnsignedInteger => "uinteger",
+            Self::Float => "float",
+            Self::String => "string",
+            Self::Date => "date",
+            Self::Utf8 => "utf-8",
+            Self::Master => "master",
+            Self::Binary => "binary",
+        }
+    }
+}
+
+impl DocumentationPurpose {
+    // the purpose's name in schema files
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Definition => "definition",
+            Self::Rationale => "rationale",
+            Self::UsageNotes => "usage notes",
+            Self::References => "references",
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReadSchemaError {
+    #[error("IO error: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("XML error: {0}")]
+    XmlError(#[from] serde_xml_rs::Error),
+    #[error(
+        "invalid schema elements: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    InvalidElements(Vec<InvalidElement>),
+}
+
+// a schema element that failed to deserialize, by its position among the schema's elements
+#[derive(thiserror::Error, Debug)]
+#[error("element #{index} ({}): {error}", .name.as_deref().unwrap_or("unnamed"))]
+pub struct InvalidElement {
+    pub index: usize,
+    pub name: Option<String>,
+    pub error: serde_xml_rs::Error,
+}
+
+impl EbmlSchema {
+    // Reads a schema from its XML. If the schema fails to deserialize, each of its elements is
+    // deserialized on its own, s.t. the error names the elements at fault.
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> Result<Self, ReadSchemaError> {
+        let mut source = Vec::new();
+        reader.read_to_end(&mut source)?;
+
+        let error = match from_reader(&source[..]) {
+            Ok(schema) => return Ok(schema),
+            Err(error) => error,
+        };
+        let invalid_elements = invalid_elements(&source);
+        if invalid_elements.is_empty() {
+            // the error is outside of the elements, e.g. in the schema's own attributes
+            Err(error.into())
+        } else {
+            Err(ReadSchemaError::InvalidElements(invalid_elements))
+        }
+    }
+}
+
+// Deserializes each of the schema's elements individually, by copying its XML into a standalone
+// document. Stops at the first malformed XML, since no later element can be told apart from it.
+fn invalid_elements(source: &[u8]) -> Vec<InvalidElement> {
+    let mut invalid_elements = Vec::new();
+    let mut depth = 0usize;
+    let mut index = 0usize;
+    // the XML of the element being copied, & its name
+    let mut current: Option<(XmlWriter<Vec<u8>>, Option<String>)> = None;
+
+    for event in xml::reader::EventReader::new(source) {
+        let event = match event {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+        match &event {
+            xml::reader::XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                depth += 1;
+                if depth == 2 && name.local_name == "element" {
+                    let element_name = attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == "name")
+                        .map(|attribute| attribute.value.clone());
+                    current = Some((XmlWriter::new(Vec::new()), element_name));
+                }
+            }
+            xml::reader::XmlEvent::EndElement { .. } => depth -= 1,
+            _ => {}
+        }
+
+        if let Some((writer, _)) = current.as_mut() {
+            if let Some(writer_event) = event.as_writer_event() {
+                if writer.write(writer_event).is_err() {
+                    break;
+                }
+            }
+        }
+
+        if depth == 1 && matches!(event, xml::reader::XmlEvent::EndElement { .. }) {
+            if let Some((writer, name)) = current.take() {
+                if let Err(error) = from_reader::<_, Element>(&writer.into_inner()[..]) {
+                    invalid_elements.push(InvalidElement { index, name, error });
+                }
+                index += 1;
+            }
+        }
+    }
+
+    invalid_elements
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WriteSchemaError {
+    #[error("XML error: {0}")]
+    XmlError(#[from] xml::writer::Error),
+}
+
+// Schema files keep all of their values in attributes, which `serde_xml_rs` can't serialize; the
+// XML is instead written event-by-event.
+type XmlWriter<W> = xml::writer::EventWriter<W>;
+
+// writes an XML element's start tag, w/ any attributes that have values
+fn write_start<W: std::io::Write>(
+    writer: &mut XmlWriter<W>,
+    name: &str,
+    attributes: &[(&str, Option<String>)],
+) -> Result<(), WriteSchemaError> {
+    let event = attributes
+        .iter()
+        .filter_map(|(name, value)| Some((*name, value.as_deref()?)))
+        .fold(XmlEvent::start_element(name), |event, (name, value)| {
+            event.attr(name, value)
+        });
+    Ok(writer.write(event)?)
+}
+
+fn write_end<W: std::io::Write>(writer: &mut XmlWriter<W>) -> Result<(), WriteSchemaError> {
+    Ok(writer.write(XmlEvent::end_element())?)
+}
+
+// boolean attributes are written as `1`/`0`, as in the EBML specification
+fn bool_attribute(value: Option<bool>) -> Option<String> {
+    value.map(|value| if value { "1" } else { "0" }.to_string())
+}
+
+fn write_documentation<W: std::io::Write>(
+    writer: &mut XmlWriter<W>,
+    documentation: &Documentation,
+) -> Result<(), WriteSchemaError> {
+    write_start(
+        writer,
+        "documentation",
+        &[
+            ("lang", documentation.lang.clone()),
+            ("purpose", Some(documentation.purpose.as_str().to_string())),
+        ],
+    )?;
+    writer.write(XmlEvent::characters(&documentation.value))?;
+    write_end(writer)
+}
+
+impl EbmlSchema {
+    /// Writes the schema as EBML schema XML, s.t. reading it back yields an equal schema:
+    ///
+    /// ```
+    /// use iron_ebmlem::serde_schema::{EbmlSchema, Element, ElementType};
+    ///
+    /// let schema = EbmlSchema::new("files-in-ebml-demo", 1)
+    ///     .with_element(Element::new("Files", r"\Files", 0x1946696C, ElementType::Master));
+    /// let mut xml = Vec::new();
+    /// schema.write_xml(&mut xml).unwrap();
+    /// assert!(String::from_utf8(xml).unwrap().contains(r#"id="0x1946696C""#));
+    /// ```
+    pub fn write_xml<W: std::io::Write>(&self, writer: W) -> Result<(), WriteSchemaError> {
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(writer);
+
+        writer.write(XmlEvent::StartDocument {
+            version: xml::common::XmlVersion::Version10,
+            encoding: Some("utf-8"),
+            standalone: None,
+        })?;
+        let doc_type = self.doc_type.clone();
+        let version = self.version.to_string();
+        let ebml = self.ebml.map(|ebml| ebml.to_string());
+        let mut start = XmlEvent::start_element("EBMLSchema")
+            .default_ns("urn:ietf:rfc:8794")
+            .attr("docType", &doc_type)
+            .attr("version", &version);
+        if let Some(ebml) = ebml.as_deref() {
+            start = start.attr("ebml", ebml);
+        }
+        writer.write(start)?;
+
+        for element in self.elements() {
+            element.write_xml(&mut writer)?;
+        }
+
+        write_end(&mut writer)
+    }
+}
+
+impl Element {
+    fn write_xml<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), WriteSchemaError> {
+        write_start(
+            writer,
+            "element",
+            &[
+                ("name", Some(self.name.clone())),
+                ("path", Some(self.path.clone())),
+                ("id", Some(custom_serde::hexadecimal::to_string(&self.id))),
+                ("type", Some(self.r#type.as_str().to_string())),
+                ("minOccurs", self.min_occurs.map(|n| n.to_string())),
+                ("maxOccurs", self.max_occurs.map(|n| n.to_string())),
+                ("range", self.range.clone()),
+                ("length", self.length.clone()),
+                ("default", self.default.clone()),
+                (
+                    "unknownsizeallowed",
+                    bool_attribute(self.unknownsizeallowed),
+                ),
+                ("recursive", bool_attribute(self.recursive)),
+                ("recurring", bool_attribute(self.recurring)),
+                ("minver", self.minver.map(|n| n.to_string())),
+                ("maxver", self.maxver.map(|n| n.to_string())),
+            ],
+        )?;
+
+        for value in self.metadata.iter().flatten() {
+            match value {
+                ElementValue::Documentation(documentation) => {
+                    write_documentation(writer, documentation)?
+                }
+                ElementValue::Extension(extension) => {
+                    write_start(
+                        writer,
+                        "extension",
+                        &[
+                            ("type", Some(extension.r#type.clone())),
+                            ("webm", bool_attribute(extension.webm)),
+                            ("keep", bool_attribute(extension.keep)),
+                            ("cppname", extension.cppname.clone()),
+                        ],
+                    )?;
+                    write_end(writer)?;
+                }
+                ElementValue::ImplementationNote(note) => {
+                    write_start(
+                        writer,
+                        "implementation_note",
+                        &[("note_attribute", Some(note.note_attribute.clone()))],
+                    )?;
+                    write_end(writer)?;
+                }
+                ElementValue::Restriction(restriction) => {
+                    write_start(writer, "restriction", &[])?;
+                    for enum_ in restriction.enums.iter() {
+                        write_start(
+                            writer,
+                            "enum",
+                            &[
+                                ("label", Some(enum_.label.clone())),
+                                ("value", Some(enum_.value.to_string())),
+                            ],
+                        )?;
+                        for documentation in enum_.docs.iter().flatten() {
+                            write_documentation(writer, documentation)?;
+                        }
+                        write_end(writer)?;
+                    }
+                    write_end(writer)?;
+                }
+            }
+        }
+
+        write_end(writer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +673,14 @@ mod tests {
             metadata: None,
         },
     )]
+    // XML entities in attributes are decoded on reading
+    #[case(
+        r#"<element name="Data" path="\Data" id="0x4664" type="uinteger" range="&lt;10" length="&gt;0" default="&amp;"/>"#,
+        Element::new("Data", "\\Data", 0x4664, ElementType::UnsignedInteger)
+            .with_range("<10")
+            .with_length(">0")
+            .with_default("&"),
+    )]
     fn test_deserialize_element(#[case] source: &str, #[case] expt_result: Element) {
         let result: Element = from_str(source).unwrap();
         assert_eq!(result, expt_result);
@@ -204,4 +699,56 @@ mod tests {
         let result: ElementType = from_str(source).unwrap();
         assert_eq!(result, expt_result);
     }
+
+    #[rstest]
+    fn test_from_reader() {
+        let source = r#"
+        <EBMLSchema xmlns="urn:ietf:rfc:8794" docType="files-in-ebml-demo" version="1">
+            <element name="Files" path="\Files" id="0x1946696C" type="master"/>
+            <element name="FileName" path="\Files\FileName" id="0x614E" type="utf-8">
+                <documentation lang="en" purpose="definition">The name of a file.</documentation>
+            </element>
+        </EBMLSchema>
+        "#;
+        assert_eq!(
+            EbmlSchema::from_reader(source.as_bytes()).unwrap(),
+            from_str(source).unwrap()
+        );
+    }
+
+    #[rstest]
+    fn test_from_reader_invalid_elements() {
+        let source = r#"
+        <EBMLSchema xmlns="urn:ietf:rfc:8794" docType="files-in-ebml-demo" version="1">
+            <element name="Files" path="\Files" id="0x1946696C" type="master"/>
+            <element name="FileName" path="\Files\FileName" id="0x614E" type="utf8"/>
+            <element name="Data" path="\Files\Data" id="0x4664" type="binary"/>
+            <element name="MimeType" path="\Files\MimeType" id="4646" type="string"/>
+        </EBMLSchema>
+        "#;
+        let invalid_elements = match EbmlSchema::from_reader(source.as_bytes()) {
+            Err(ReadSchemaError::InvalidElements(invalid_elements)) => invalid_elements,
+            result => panic!("unexpected result: {:?}", result),
+        };
+        assert_eq!(
+            invalid_elements
+                .iter()
+                .map(|invalid| (invalid.index, invalid.name.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(1, Some("FileName")), (3, Some("MimeType"))]
+        );
+        assert!(invalid_elements[0]
+            .to_string()
+            .starts_with("element #1 (FileName): "));
+    }
+
+    #[rstest]
+    fn test_from_reader_invalid_schema() {
+        // the schema's own attributes are at fault -> there are no elements to blame
+        let source = r#"<EBMLSchema docType="files-in-ebml-demo" version="one"/>"#;
+        assert!(matches!(
+            EbmlSchema::from_reader(source.as_bytes()),
+            Err(ReadSchemaError::XmlError(_))
+        ));
+    }
 }
@@ -36,7 +36,7 @@ where
             Some(next_key) => self
                 .subtries
                 .entry(next_key)
-                .or_insert_with(Self::default)
+                .or_default()
                 .insert(keys, value),
             None => self.leaf.replace(value),
         }
@@ -53,6 +53,33 @@ where
         }
     }
 
+    #[allow(dead_code)]
+    pub fn get_mut<'a, I: IntoIterator<Item = &'a K>>(&mut self, keys: I) -> Option<&mut V>
+    where
+        K: 'a,
+    {
+        let mut keys = keys.into_iter();
+        match keys.next() {
+            Some(next_key) => self
+                .subtries
+                .get_mut(next_key)
+                .and_then(|trie| trie.get_mut(keys)),
+            None => self.leaf.as_mut(),
+        }
+    }
+
+    // the (possibly empty) value slot at a key path, adding any missing nodes along the way; e.g.
+    // `trie.entry(keys).get_or_insert_with(...)` inserts a value or updates the existing one
+    #[allow(dead_code)]
+    pub fn entry<I: IntoIterator<Item = K>>(&mut self, keys: I) -> &mut Option<V> {
+        let mut keys = keys.into_iter();
+        match keys.next() {
+            Some(next_key) => self.subtries.entry(next_key).or_default().entry(keys),
+            None => &mut self.leaf,
+        }
+    }
+
+    // an empty key path gives the whole trie
     pub fn subtrie<'a, I: IntoIterator<Item = &'a K>>(&self, keys: I) -> Option<&Trie<K, V>>
     where
         K: 'a,
@@ -61,7 +88,7 @@ where
         match keys.next() {
             Some(next_key) => self
                 .subtries
-                .get(&next_key)
+                .get(next_key)
                 .and_then(|trie| trie.subtrie(keys)),
             None => Some(self),
         }
@@ -86,6 +113,8 @@ where
             .filter_map(|(keypath, trie)| trie.leaf.as_ref().map(|value| (keypath, value)))
     }
 
+    // yields the values breadth-first, w/ the number of keys leading to each; the root itself is
+    // at depth 0, & only yields a value if one was inserted w/ an empty key path
     pub fn iter_depths(&self) -> impl core::iter::Iterator<Item = (usize, &V)> {
         let mut buffer1 = vec![self];
         let mut buffer2 = Vec::new();
@@ -111,6 +140,7 @@ where
         .filter_map(|(depth, trie)| trie.leaf.as_ref().map(|value| (depth, value)))
     }
 
+    #[allow(dead_code)]
     pub fn iter_values(&self) -> impl core::iter::Iterator<Item = &V> {
         let mut trie_buffer = vec![self];
 
@@ -139,3 +169,126 @@ where
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn trie() -> Trie<&'static str, u32> {
+        vec![
+            (vec!["A"], 1),
+            (vec!["A", "B"], 2),
+            (vec!["A", "B", "C"], 3),
+            (vec!["D"], 4),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[rstest]
+    fn iter_depths_wo_root() {
+        let mut depths = trie()
+            .iter_depths()
+            .map(|(d, &v)| (d, v))
+            .collect::<Vec<_>>();
+        depths.sort_unstable();
+        assert_eq!(depths, vec![(1, 1), (1, 4), (2, 2), (3, 3)]);
+
+        // values are given in order of their depth
+        let trie = trie();
+        let depths = trie.iter_depths().map(|(d, _v)| d).collect::<Vec<_>>();
+        assert!(depths.windows(2).all(|w| w[0] <= w[1]));
+
+        assert_eq!(Trie::<&str, u32>::new().iter_depths().count(), 0);
+    }
+
+    #[rstest]
+    fn iter_depths_w_root() {
+        let mut trie = trie();
+        trie.insert(vec![], 0);
+        assert_eq!(trie.iter_depths().next(), Some((0, &0)));
+        assert_eq!(trie.get(vec![]), Some(&0));
+    }
+
+    #[rstest]
+    fn get_mut_existing() {
+        let mut trie = trie();
+        *trie.get_mut(&["A", "B"]).unwrap() += 10;
+        assert_eq!(trie.get(&["A", "B"]), Some(&12));
+
+        assert!(trie.get_mut(&["A", "B", "C", "D"]).is_none());
+        assert!(trie.get_mut(vec![]).is_none());
+    }
+
+    #[rstest]
+    fn entry_update() {
+        let mut trie = trie();
+        *trie.entry(vec!["A", "B"]).get_or_insert(0) += 10;
+        assert_eq!(trie.get(&["A", "B"]), Some(&12));
+        assert_eq!(trie.iter_depths().count(), 4);
+    }
+
+    #[rstest]
+    fn entry_insert_deep() {
+        let mut trie = trie();
+        *trie.entry(vec!["D", "E", "F"]).get_or_insert(0) += 10;
+        assert_eq!(trie.get(&["D", "E", "F"]), Some(&10));
+        // the intermediate nodes are created w/o values
+        assert_eq!(trie.get(&["D", "E"]), None);
+        assert!(trie.subtrie(&["D", "E"]).is_some());
+        assert!(trie.iter_depths().any(|item| item == (3, &10)));
+
+        // an intermediate node can still be given a value afterwards
+        assert_eq!(trie.entry(vec!["D", "E"]).replace(5), None);
+        assert_eq!(trie.get(&["D", "E"]), Some(&5));
+    }
+
+    #[rstest]
+    fn subtrie_empty_path() {
+        let trie = trie();
+        let subtrie = trie.subtrie(vec![]).unwrap();
+        assert!(core::ptr::eq(subtrie, &trie));
+        assert_eq!(subtrie.get(vec![]), None);
+        assert_eq!(subtrie.get(&["A", "B"]), Some(&2));
+    }
+
+    #[rstest]
+    #[case(&["A"], vec![(0, 1), (1, 2), (2, 3)])]
+    #[case(&["A", "B", "C"], vec![(0, 3)])]
+    fn subtrie_iter_depths(#[case] path: &[&'static str], #[case] expt_depths: Vec<(usize, u32)>) {
+        let trie = trie();
+        let subtrie = trie.subtrie(path).unwrap();
+        let depths = subtrie
+            .iter_depths()
+            .map(|(d, &v)| (d, v))
+            .collect::<Vec<_>>();
+        assert_eq!(depths, expt_depths);
+    }
+
+    #[rstest]
+    #[case(&["E"])]
+    #[case(&["A", "C"])]
+    fn subtrie_missing(#[case] path: &[&'static str]) {
+        assert!(trie().subtrie(path).is_none());
+    }
+
+    #[rstest]
+    fn iter_keypaths() {
+        let trie = trie();
+        let mut items = trie
+            .iter()
+            .map(|(keys, &v)| (keys.into_iter().copied().collect::<Vec<_>>(), v))
+            .collect::<Vec<_>>();
+        items.sort_unstable();
+        assert_eq!(
+            items,
+            vec![
+                (vec!["A"], 1),
+                (vec!["A", "B"], 2),
+                (vec!["A", "B", "C"], 3),
+                (vec!["D"], 4),
+            ]
+        );
+    }
+}